@@ -7,10 +7,11 @@ use crate::{
 };
 use cpal::{
     traits::{DeviceTrait, HostTrait, StreamTrait},
-    BackendSpecificError, BuildStreamError, DefaultStreamConfigError, FromSample, PlayStreamError,
-    Sample, StreamError,
+    BackendSpecificError, BuildStreamError, DefaultStreamConfigError, PlayStreamError, Sample,
+    StreamError,
 };
 use std::error::Error;
+use std::time::Instant;
 
 pub use cpal::BufferSize as CpalBufferSize;
 
@@ -149,33 +150,67 @@ impl CpalBackend {
     }
 }
 
-/// Converts Awedio's internal i16 samples to the format required by the audio
-/// device (type T).
+/// Renders directly into the buffer of the audio device so f32 devices get
+/// the f32 mix without a round trip through i16.
 fn make_data_callback<T>(
     mut renderer: Renderer,
     channel_count: u16,
 ) -> impl FnMut(&mut [T], &cpal::OutputCallbackInfo)
 where
-    T: Sample + FromSample<i16>,
+    T: OutputSample,
 {
-    move |buffer: &mut [T], _info: &cpal::OutputCallbackInfo| {
+    move |buffer: &mut [T], info: &cpal::OutputCallbackInfo| {
         assert!(buffer.len() % channel_count as usize == 0);
 
+        // The stream timestamps are on a clock of their own so only the
+        // difference between them is used.
+        let timestamp = info.timestamp();
+        if let Some(latency) = timestamp.playback.duration_since(&timestamp.callback) {
+            let now = Instant::now();
+            renderer.set_batch_timestamp(now, now + latency);
+        }
         renderer.on_start_of_batch();
 
-        buffer.fill_with(|| {
-            let sample = renderer
-                .next_sample()
-                .expect("renderer should never return an Error");
-            match sample {
-                crate::NextSample::Sample(s) => T::from_sample(s),
-                crate::NextSample::MetadataChanged => {
-                    unreachable!("we never change metadata mid-batch")
-                }
-                crate::NextSample::Paused => T::from_sample(0), // TODO: implement pausing
-                crate::NextSample::Finished => T::from_sample(0), // TODO: implement finishing
+        let (num_frames, next) =
+            T::render(&mut renderer, buffer).expect("renderer should never return an Error");
+        let num_samples = match next {
+            None => buffer.len(),
+            Some(crate::NextSample::MetadataChanged) => {
+                unreachable!("we never change metadata mid-batch")
+            }
+            // TODO: implement pausing and finishing. For now play silence.
+            Some(crate::NextSample::Paused) | Some(crate::NextSample::Finished) => {
+                num_frames * channel_count as usize
             }
-        });
+            Some(crate::NextSample::Sample(_)) => unreachable!(),
+        };
+        buffer[num_samples..].fill(T::EQUILIBRIUM);
+    }
+}
+
+/// The device sample formats the renderer can output directly.
+trait OutputSample: Sample + Send + 'static {
+    fn render(
+        renderer: &mut Renderer,
+        buffer: &mut [Self],
+    ) -> Result<(usize, Option<crate::NextSample>), crate::Error>;
+}
+
+impl OutputSample for i16 {
+    fn render(
+        renderer: &mut Renderer,
+        buffer: &mut [Self],
+    ) -> Result<(usize, Option<crate::NextSample>), crate::Error> {
+        renderer.fill_buffer(buffer)
+    }
+}
+
+impl OutputSample for f32 {
+    fn render(
+        renderer: &mut Renderer,
+        buffer: &mut [Self],
+    ) -> Result<(usize, Option<crate::NextSample>), crate::Error> {
+        renderer.fill_buffer_f32(buffer)
     }
 }
 
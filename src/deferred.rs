@@ -0,0 +1,151 @@
+//! Work the renderer thread hands off so that it never frees memory or takes
+//! a lock: dropping removed sounds and used commands and delivering events.
+
+use std::sync::{mpsc, OnceLock};
+use std::thread;
+
+use crate::events::{EventSender, SoundId};
+use crate::sounds::MasterEffect;
+use crate::Sound;
+
+/// How many tasks can be waiting. If the queue is full tasks are run on the
+/// calling thread instead.
+const QUEUE_CAPACITY: usize = 4096;
+
+/// The queue of the worker once it has been started.
+static WORKER: OnceLock<mpsc::SyncSender<Task>> = OnceLock::new();
+
+/// Anything that can be dropped on another thread.
+pub(crate) trait Garbage: Send {}
+
+impl<T: Send> Garbage for T {}
+
+pub(crate) enum Task {
+    Drop(Box<dyn Garbage>),
+    /// Kept apart from Drop since a boxed Sound or MasterEffect can not be
+    /// turned into a boxed Garbage without boxing it again.
+    DropSound(Box<dyn Sound>),
+    DropEffect(Box<dyn MasterEffect>),
+    Finished(EventSender, SoundId),
+    Error(EventSender, SoundId, crate::Error),
+}
+
+impl Task {
+    pub(crate) fn run(self) {
+        match self {
+            Task::Drop(garbage) => drop(garbage),
+            Task::DropSound(sound) => drop(sound),
+            Task::DropEffect(effect) => drop(effect),
+            Task::Finished(events, id) => events.send_finished(id),
+            Task::Error(events, id, error) => {
+                log::error!("dropping sound which returned error: {}", error);
+                events.send_error(id, error);
+            }
+        }
+    }
+}
+
+/// Where tasks run: on the background worker shared by all Managers or, for
+/// sounds that are not played by a Manager, on the calling thread.
+#[derive(Clone)]
+pub(crate) struct Deferred {
+    target: Target,
+}
+
+#[derive(Clone)]
+enum Target {
+    /// Run tasks on the calling thread.
+    Inline,
+    /// The worker if it has been started by the time a task is sent.
+    Current,
+    Worker(mpsc::SyncSender<Task>),
+}
+
+impl Deferred {
+    /// Run tasks on the calling thread.
+    pub(crate) fn inline() -> Deferred {
+        Deferred {
+            target: Target::Inline,
+        }
+    }
+
+    /// The shared worker once a Manager has started it, otherwise inline. The
+    /// worker is looked up when each task is sent so that sounds created before
+    /// the Manager use it once they are played. Never starts the worker.
+    pub(crate) fn current() -> Deferred {
+        Deferred {
+            target: Target::Current,
+        }
+    }
+
+    /// The shared worker which is started by the first call. Only Manager::new
+    /// and Bus::new call this so that the worker never runs without a
+    /// Manager.
+    pub(crate) fn worker() -> Deferred {
+        let sender = WORKER.get_or_init(|| {
+            let (sender, receiver) = mpsc::sync_channel(QUEUE_CAPACITY);
+            thread::Builder::new()
+                .name("awedio-deferred".to_owned())
+                .spawn(move || run_worker(receiver))
+                .expect("failed to spawn the awedio-deferred thread");
+            sender
+        });
+        Deferred {
+            target: Target::Worker(sender.clone()),
+        }
+    }
+
+    /// Whether tasks always run on the calling thread.
+    #[cfg(test)]
+    pub(crate) fn is_inline(&self) -> bool {
+        matches!(self.target, Target::Inline)
+    }
+
+    /// Run `task` on the worker, or now if there is none or its queue is full.
+    /// Never waits for the worker, though waking it when it is idle is a
+    /// system call.
+    pub(crate) fn send(&self, task: Task) {
+        let sender = match &self.target {
+            Target::Inline => None,
+            Target::Current => WORKER.get(),
+            Target::Worker(sender) => Some(sender),
+        };
+        let Some(sender) = sender else {
+            return task.run();
+        };
+        if let Err(mpsc::TrySendError::Full(task) | mpsc::TrySendError::Disconnected(task)) =
+            sender.try_send(task)
+        {
+            task.run();
+        }
+    }
+
+    /// Drop `garbage` on the worker.
+    pub(crate) fn drop(&self, garbage: Box<dyn Garbage>) {
+        self.send(Task::Drop(garbage));
+    }
+
+    /// Drop `sound` on the worker.
+    pub(crate) fn drop_sound(&self, sound: Box<dyn Sound>) {
+        self.send(Task::DropSound(sound));
+    }
+
+    /// Drop `effect` on the worker.
+    pub(crate) fn drop_effect(&self, effect: Box<dyn MasterEffect>) {
+        self.send(Task::DropEffect(effect));
+    }
+}
+
+impl Default for Deferred {
+    fn default() -> Self {
+        Deferred::inline()
+    }
+}
+
+/// Run tasks as they arrive. The worker sleeps until a task is sent so it
+/// costs nothing while idle.
+fn run_worker(receiver: mpsc::Receiver<Task>) {
+    while let Ok(task) = receiver.recv() {
+        task.run();
+    }
+}
@@ -10,6 +10,14 @@ pub enum Error {
     /// An error other than std::io::Error occurred. The real type of the boxed
     /// error is normally the error type of the format decoding library.
     FormatError(Box<dyn std::error::Error + Send + Sync + 'static>),
+    /// There is no rule to convert between the channel counts. See
+    /// [ChannelMixMatrix][crate::sounds::wrappers::ChannelMixMatrix].
+    UnsupportedChannelConversion {
+        /// The number of channels of the sound.
+        from: u16,
+        /// The requested number of channels.
+        to: u16,
+    },
 }
 
 impl std::error::Error for Error {
@@ -17,6 +25,7 @@ impl std::error::Error for Error {
         match self {
             Error::IoError(e) => e.source(),
             Error::FormatError(e) => Some(e.as_ref()),
+            Error::UnsupportedChannelConversion { .. } => None,
         }
     }
 }
@@ -26,6 +35,9 @@ impl std::fmt::Display for Error {
         match *self {
             Error::IoError(ref err) => err.fmt(f),
             Error::FormatError(ref inner) => write!(f, "format error: {}", inner),
+            Error::UnsupportedChannelConversion { from, to } => {
+                write!(f, "unsupported conversion from {} to {} channels", from, to)
+            }
         }
     }
 }
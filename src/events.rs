@@ -0,0 +1,211 @@
+//! Events such as errors reported by sounds that are being played and the
+//! state of those sounds.
+//!
+//! Sounds that contain other sounds ([SoundMixer][crate::sounds::SoundMixer],
+//! [SoundList][crate::sounds::SoundList] and
+//! [SoundsFromFn][crate::sounds::SoundsFromFn]) report events about the sounds
+//! they contain to an [EventSender]. The [Manager][crate::manager::Manager]
+//! creates one for its mixer which can be subscribed to with
+//! [Manager::subscribe_events][crate::manager::Manager::subscribe_events].
+
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+
+use crate::deferred::Deferred;
+
+/// Identifies a sound added to a Manager or to a sound containing other
+/// sounds. Identifiers are unique for the lifetime of the process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SoundId(u64);
+
+impl SoundId {
+    /// Allocate a new unique identifier.
+    pub fn new() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+        SoundId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// The raw value of the identifier.
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Default for SoundId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for SoundId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "sound #{}", self.0)
+    }
+}
+
+/// An event about a sound reported to subscribers of an [EventSender].
+#[derive(Debug, Clone)]
+pub enum SoundEvent {
+    /// The sound returned an error and was dropped. Sounds playing alongside or
+    /// after it are not affected.
+    Error {
+        /// The sound that failed.
+        id: SoundId,
+        /// The error returned by the sound. Shared since there can be multiple
+        /// subscribers.
+        error: Arc<crate::Error>,
+    },
+    /// The sound returned Finished or was stopped and was removed.
+    Finished {
+        /// The sound that finished.
+        id: SoundId,
+    },
+}
+
+impl SoundEvent {
+    /// The sound the event is about.
+    pub fn id(&self) -> SoundId {
+        match self {
+            SoundEvent::Error { id, .. } => *id,
+            SoundEvent::Finished { id } => *id,
+        }
+    }
+}
+
+/// The playback state of a sound added to a
+/// [SoundMixer][crate::sounds::SoundMixer]. See
+/// [SoundHandle::state][crate::manager::SoundHandle::state].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundState {
+    /// The sound is playing or waiting to be mixed in.
+    Playing,
+    /// The sound was paused with a handle or the sound itself returned Paused.
+    Paused,
+    /// The sound finished or was stopped and has been removed.
+    Finished,
+    /// The sound returned an error and has been removed.
+    Errored,
+}
+
+/// A [SoundState] shared between a mixer and the handles of a sound.
+#[derive(Debug)]
+pub(crate) struct SharedSoundState(AtomicU8);
+
+impl SharedSoundState {
+    pub(crate) fn new(state: SoundState) -> Self {
+        SharedSoundState(AtomicU8::new(state as u8))
+    }
+
+    pub(crate) fn get(&self) -> SoundState {
+        match self.0.load(Ordering::Relaxed) {
+            0 => SoundState::Playing,
+            1 => SoundState::Paused,
+            2 => SoundState::Finished,
+            _ => SoundState::Errored,
+        }
+    }
+
+    pub(crate) fn set(&self, state: SoundState) {
+        self.0.store(state as u8, Ordering::Relaxed);
+    }
+}
+
+enum Subscriber {
+    Sync(mpsc::Sender<SoundEvent>),
+    #[cfg(feature = "async")]
+    Async(tokio::sync::mpsc::UnboundedSender<SoundEvent>),
+}
+
+impl Subscriber {
+    /// Returns false if the receiver has been dropped.
+    fn send(&self, event: SoundEvent) -> bool {
+        match self {
+            Subscriber::Sync(sender) => sender.send(event).is_ok(),
+            #[cfg(feature = "async")]
+            Subscriber::Async(sender) => sender.send(event).is_ok(),
+        }
+    }
+}
+
+/// Delivers [SoundEvent]s to any number of subscribers.
+///
+/// Clones share the same subscribers. Events sent while there are no
+/// subscribers are discarded. Subscribers whose receiver has been dropped are
+/// removed on the next event.
+#[derive(Clone, Default)]
+pub struct EventSender {
+    subscribers: Arc<Mutex<Vec<Subscriber>>>,
+    /// Where sounds containing other sounds send events and drop finished
+    /// sounds. The sender of a Manager uses the worker so that the renderer
+    /// thread never takes the lock.
+    deferred: Deferred,
+}
+
+impl EventSender {
+    /// Create a new EventSender without any subscribers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Receive all events sent after this call.
+    pub fn subscribe(&self) -> mpsc::Receiver<SoundEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.lock().push(Subscriber::Sync(sender));
+        receiver
+    }
+
+    /// Receive all events sent after this call on a tokio channel.
+    #[cfg(feature = "async")]
+    pub fn subscribe_async(&self) -> tokio::sync::mpsc::UnboundedReceiver<SoundEvent> {
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+        self.lock().push(Subscriber::Async(sender));
+        receiver
+    }
+
+    /// Send `event` to all subscribers.
+    pub fn send(&self, event: SoundEvent) {
+        self.lock()
+            .retain(|subscriber| subscriber.send(event.clone()));
+    }
+
+    /// Report that the sound `id` returned `error`.
+    pub fn send_error(&self, id: SoundId, error: crate::Error) {
+        self.send(SoundEvent::Error {
+            id,
+            error: Arc::new(error),
+        });
+    }
+
+    /// Report that the sound `id` finished.
+    pub fn send_finished(&self, id: SoundId) {
+        self.send(SoundEvent::Finished { id });
+    }
+
+    pub(crate) fn deferred(&self) -> &Deferred {
+        &self.deferred
+    }
+
+    pub(crate) fn set_deferred(&mut self, deferred: Deferred) {
+        self.deferred = deferred;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Subscriber>> {
+        // A panic while holding the lock can not leave the Vec in an invalid
+        // state so ignore poisoning.
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl std::fmt::Debug for EventSender {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventSender")
+            .field("subscribers", &self.lock().len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/events.rs"]
+mod tests;
@@ -0,0 +1,115 @@
+use std::{
+    ops::{Deref, DerefMut},
+    time::Duration,
+};
+
+use crate::{sounds::wrappers::FromFloat, NextSample};
+
+/// A provider of 32-bit float audio samples.
+///
+/// This is the float counterpart of [Sound][crate::Sound] for sources that
+/// naturally produce floats (e.g. synthesizers or effects). Samples are in the
+/// range -1.0 to 1.0 and all other behavior is the same as for Sound. Use
+/// [into_sound][FloatSound::into_sound] to play a FloatSound on a
+/// [Manager][crate::manager::Manager] or mix it with other sounds. When the
+/// output uses floats the samples are never converted to i16.
+///
+/// To use a Sound where a FloatSound is expected see
+/// [Sound::into_float][crate::Sound::into_float].
+pub trait FloatSound: Send {
+    /// Returns the number of channels.
+    fn channel_count(&self) -> u16;
+
+    /// Returns the number of samples per second for each channel for this sound
+    /// (e.g. 48,000).
+    fn sample_rate(&self) -> u32;
+
+    /// Retrieve the next sample or notification if something has changed.
+    ///
+    /// See [Sound::next_sample][crate::Sound::next_sample].
+    fn next_sample(&mut self) -> Result<NextSample<f32>, crate::Error>;
+
+    /// Fill `buffer` with whole frames of samples.
+    ///
+    /// See [Sound::fill_buffer][crate::Sound::fill_buffer].
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [f32],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        let channel_count = self.channel_count() as usize;
+        debug_assert!(buffer.len().is_multiple_of(channel_count));
+        let mut num_frames = 0;
+        for frame in buffer.chunks_exact_mut(channel_count) {
+            for sample in frame {
+                match self.next_sample()? {
+                    NextSample::Sample(s) => *sample = s,
+                    NextSample::MetadataChanged => {
+                        return Ok((num_frames, Some(NextSample::MetadataChanged)))
+                    }
+                    NextSample::Paused => return Ok((num_frames, Some(NextSample::Paused))),
+                    NextSample::Finished => return Ok((num_frames, Some(NextSample::Finished))),
+                }
+            }
+            num_frames += 1;
+        }
+        Ok((num_frames, None))
+    }
+
+    /// Called whenever a new batch of audio samples is requested by the
+    /// backend.
+    ///
+    /// See [Sound::on_start_of_batch][crate::Sound::on_start_of_batch].
+    fn on_start_of_batch(&mut self);
+
+    /// Returns how far into the sound playback currently is, if known.
+    fn position(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Returns the total length of the sound, if known.
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Convert into a [Sound][crate::Sound] so it can be played, mixed or
+    /// wrapped like any other Sound.
+    fn into_sound(self) -> FromFloat<Self>
+    where
+        Self: Sized,
+    {
+        FromFloat::new(self)
+    }
+}
+
+impl FloatSound for Box<dyn FloatSound> {
+    fn channel_count(&self) -> u16 {
+        self.deref().channel_count()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.deref().sample_rate()
+    }
+
+    fn next_sample(&mut self) -> Result<NextSample<f32>, crate::Error> {
+        self.deref_mut().next_sample()
+    }
+
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [f32],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.deref_mut().fill_buffer(buffer)
+    }
+
+    fn on_start_of_batch(&mut self) {
+        self.deref_mut().on_start_of_batch()
+    }
+
+    fn position(&self) -> Option<Duration> {
+        self.deref().position()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.deref().total_duration()
+    }
+}
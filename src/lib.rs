@@ -3,16 +3,20 @@
 #![doc = include_str!("../README.md")]
 
 pub mod backends;
+pub mod events;
 pub mod manager;
 pub mod sounds;
 pub mod utils;
 
+mod deferred;
 mod error;
+mod float_sound;
 mod sound;
 #[cfg(test)]
 mod tests;
 
 pub use error::Error;
+pub use float_sound::FloatSound;
 pub use sound::NextSample;
 pub use sound::Sound;
 
@@ -1,19 +1,40 @@
 //! Manager is how sounds are played on a backend.
 mod backend_source;
+mod bus;
+mod output_clock;
 mod renderer;
+mod sound_handle;
 
+use crate::deferred::Deferred;
+use crate::events::{EventSender, SharedSoundState, SoundEvent, SoundId, SoundState};
 use crate::sounds::wrappers::Controllable;
 use crate::sounds::wrappers::Controller;
-use crate::sounds::SoundMixer;
+use crate::sounds::wrappers::ResamplerQuality;
+use crate::sounds::wrappers::Wrapper;
+use crate::sounds::{
+    Ducking, MasterEffect, MasterStage, PreparedSound, SharedFormat, SoundMixer, StartTime,
+    VoiceLimit,
+};
 use crate::Sound;
 pub use backend_source::BackendSource;
+pub use bus::Bus;
+pub use output_clock::OutputClock;
+use output_clock::SharedClock;
 pub use renderer::Renderer;
+pub use sound_handle::SoundHandle;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// A Manager can play sounds by rendering sounds on a [`Renderer`] for a
 /// backend.
 #[derive(Clone)]
 pub struct Manager {
     mixer_controller: Controller<SoundMixer>,
+    events: EventSender,
+    clock: OutputClock,
+    /// The output format of the mixer so that sounds can be converted to it
+    /// before they are sent to the renderer.
+    format: Arc<SharedFormat>,
 }
 
 // These are undocumented, should not be relied on and subject to change.
@@ -27,10 +48,26 @@ impl Manager {
     /// Normally you do not need to call this function directly but you instead
     /// call `.start(...)` on a backend which will call this function.
     pub fn new() -> (Self, Renderer) {
-        let (mixer, mixer_controller) =
-            Controllable::new(SoundMixer::new(DEFAULT_CHANNEL_COUNT, DEFAULT_SAMPLE_RATE));
-        let renderer = Renderer::new(mixer);
-        let manager = Manager { mixer_controller };
+        let mut mixer = SoundMixer::new(DEFAULT_CHANNEL_COUNT, DEFAULT_SAMPLE_RATE);
+        let deferred = Deferred::worker();
+        mixer.set_deferred(deferred.clone());
+        let events = mixer.event_sender().clone();
+        let format = mixer.shared_format();
+        let (mut mixer, mixer_controller) = Controllable::new(mixer);
+        mixer.set_deferred(deferred);
+        // Scheduled sounds use the same clock as timed commands, which the
+        // Renderer keeps counting while nothing plays.
+        mixer
+            .inner_mut()
+            .follow_frame(mixer_controller.shared_frame());
+        let clock = Arc::new(SharedClock::new(DEFAULT_SAMPLE_RATE));
+        let renderer = Renderer::new(mixer, clock.clone());
+        let manager = Manager {
+            mixer_controller,
+            events,
+            clock: OutputClock::new(clock),
+            format,
+        };
         (manager, renderer)
     }
 
@@ -41,8 +78,193 @@ impl Manager {
     ///
     /// See the modifier functions on [Sound] to control sounds before and/or
     /// after playing.
-    pub fn play(&mut self, sound: Box<dyn Sound>) {
-        self.mixer_controller.add(sound);
+    ///
+    /// Returns the identifier of the sound in events. See
+    /// [Manager::subscribe_events].
+    pub fn play(&mut self, sound: Box<dyn Sound>) -> SoundId {
+        let id = SoundId::new();
+        let sound = PreparedSound::new(sound, id, &self.format);
+        self.mixer_controller
+            .send_command(move |mixer: &mut SoundMixer| mixer.add_prepared(sound));
+        id
+    }
+
+    /// The same as [Manager::play] but returns a handle to control the sound
+    /// while it plays and query its state.
+    pub fn play_with_handle(&mut self, sound: Box<dyn Sound>) -> SoundHandle {
+        let id = SoundId::new();
+        let state = Arc::new(SharedSoundState::new(SoundState::Playing));
+        let sound = PreparedSound::new(sound, id, &self.format).with_state(state.clone());
+        self.mixer_controller
+            .send_command(move |mixer: &mut SoundMixer| mixer.add_prepared(sound));
+        SoundHandle::new(id, state, self.mixer_controller.clone())
+    }
+
+    /// Play `sound` starting at exactly `frame` of the output. See
+    /// [Manager::output_frame]. Frames in the past start right away.
+    ///
+    /// Scheduled sounds are Paused until they start.
+    pub fn play_at(&mut self, sound: Box<dyn Sound>, frame: u64) -> SoundId {
+        self.play_scheduled(sound, StartTime::Frame(frame))
+    }
+
+    /// Play `sound` `delay` after the start of the next batch rendered by the
+    /// backend. The start is accurate to the sample.
+    pub fn play_after(&mut self, sound: Box<dyn Sound>, delay: Duration) -> SoundId {
+        self.play_scheduled(sound, StartTime::After(delay))
+    }
+
+    /// Play `sound` `frames` frames after the start of the next batch rendered
+    /// by the backend.
+    pub fn play_after_frames(&mut self, sound: Box<dyn Sound>, frames: u64) -> SoundId {
+        self.play_scheduled(sound, StartTime::AfterFrames(frames))
+    }
+
+    /// Play `sound` at `start`. See [StartTime].
+    pub fn play_scheduled(&mut self, sound: Box<dyn Sound>, start: StartTime) -> SoundId {
+        let id = SoundId::new();
+        let sound = PreparedSound::new(sound, id, &self.format);
+        self.mixer_controller
+            .send_command(move |mixer: &mut SoundMixer| mixer.add_prepared_scheduled(sound, start));
+        id
+    }
+
+    /// The same as [Manager::play_scheduled] but returns a handle to control
+    /// the sound and query its state. The state is Paused until the sound
+    /// starts.
+    pub fn play_scheduled_with_handle(
+        &mut self,
+        sound: Box<dyn Sound>,
+        start: StartTime,
+    ) -> SoundHandle {
+        let id = SoundId::new();
+        let state = Arc::new(SharedSoundState::new(SoundState::Paused));
+        let sound = PreparedSound::new(sound, id, &self.format).with_state(state.clone());
+        self.mixer_controller
+            .send_command(move |mixer: &mut SoundMixer| mixer.add_prepared_scheduled(sound, start));
+        SoundHandle::new(id, state, self.mixer_controller.clone())
+    }
+
+    /// The output frame at the start of the last batch rendered by the
+    /// backend. This is the clock used by [Manager::play_at] and the `_at`
+    /// methods of [SoundHandle]. It counts frames at the current output
+    /// sample rate, including the silence output while the Manager is paused
+    /// or has nothing to play.
+    ///
+    /// The same as [OutputClock::frames] so frames from
+    /// [OutputClock::instant_to_frame] can be passed to [Manager::play_at].
+    pub fn output_frame(&self) -> u64 {
+        self.clock.frames()
+    }
+
+    /// The clock of the frames handed to the backend with the estimated output
+    /// latency. See [OutputClock].
+    pub fn output_clock(&self) -> OutputClock {
+        self.clock.clone()
+    }
+
+    /// Create a named submix bus. Sounds played on the bus can be controlled
+    /// together with the returned handle. See [Bus].
+    pub fn create_bus(&mut self, name: &str) -> Bus {
+        Bus::new(name, &mut self.mixer_controller, &self.format, &self.events)
+    }
+
+    /// Receive an event when a played sound returns an error or finishes.
+    ///
+    /// Sounds that return an error are dropped while other sounds keep
+    /// playing.
+    pub fn subscribe_events(&self) -> std::sync::mpsc::Receiver<SoundEvent> {
+        self.events.subscribe()
+    }
+
+    /// The same as [Manager::subscribe_events] but on a tokio channel.
+    #[cfg(feature = "async")]
+    pub fn subscribe_events_async(&self) -> tokio::sync::mpsc::UnboundedReceiver<SoundEvent> {
+        self.events.subscribe_async()
+    }
+
+    /// The sender events are reported to. Pass it to
+    /// [SoundList::set_event_sender][crate::sounds::SoundList::set_event_sender]
+    /// or
+    /// [SoundsFromFn::set_event_sender][crate::sounds::SoundsFromFn::set_event_sender]
+    /// to receive the errors of the sounds they contain on the same
+    /// subscriptions.
+    pub fn event_sender(&self) -> &EventSender {
+        &self.events
+    }
+
+    /// Set the resampler quality used for sounds whose sample rate differs from
+    /// the output. Sounds use [ResamplerQuality::Linear] by default.
+    pub fn set_resampler_quality(&mut self, quality: ResamplerQuality) {
+        self.mixer_controller
+            .send_command(move |mixer: &mut SoundMixer| mixer.set_resampler_quality(quality));
+    }
+
+    /// Set the processing applied to the mixed output of all sounds. Defaults
+    /// to a soft clip. See [MasterStage].
+    pub fn set_master_stage(&mut self, stage: MasterStage) {
+        self.mixer_controller
+            .send_command(move |mixer: &mut SoundMixer| mixer.set_master_stage(stage));
+    }
+
+    /// Set the master volume multiplier applied to the mix of all sounds.
+    /// Changes are ramped over 10ms.
+    pub fn set_master_volume(&mut self, multiplier: f32) {
+        self.mixer_controller.set_volume(multiplier);
+    }
+
+    /// Set the master volume in decibels. See [Manager::set_master_volume].
+    pub fn set_master_volume_db(&mut self, db: f32) {
+        self.mixer_controller.set_volume_db(db);
+    }
+
+    /// Mute or unmute the output. Sounds keep playing while muted.
+    pub fn set_muted(&mut self, muted: bool) {
+        self.mixer_controller
+            .send_command(move |mixer: &mut SoundMixer| mixer.set_muted(muted));
+    }
+
+    /// Pause or resume all sounds. Paused sounds keep their position and new
+    /// sounds do not start until resumed.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.mixer_controller.set_paused(paused);
+    }
+
+    /// Insert `effect` at the end of the master chain applied to the mix of
+    /// all sounds before the master volume and [MasterStage].
+    pub fn add_master_effect(&mut self, effect: Box<dyn MasterEffect>) {
+        self.mixer_controller
+            .send_command(move |mixer: &mut SoundMixer| mixer.add_master_effect(effect));
+    }
+
+    /// Remove all effects added with [Manager::add_master_effect].
+    pub fn clear_master_effects(&mut self) {
+        self.mixer_controller
+            .send_command(|mixer: &mut SoundMixer| mixer.clear_master_effects());
+    }
+
+    /// Lower the volume of a sound or bus played on the Manager while another
+    /// plays. See [Ducking].
+    pub fn add_ducking(&mut self, ducking: Ducking) {
+        self.mixer_controller
+            .send_command(move |mixer: &mut SoundMixer| mixer.add_ducking(ducking));
+    }
+
+    /// Stop `trigger` from ducking `target`.
+    pub fn remove_ducking(&mut self, trigger: SoundId, target: SoundId) {
+        self.mixer_controller
+            .send_command(move |mixer: &mut SoundMixer| mixer.remove_ducking(trigger, target));
+    }
+
+    /// Limit how many sounds played on the Manager play at once. See [VoiceLimit].
+    ///
+    /// Sounds played on a [Bus] are not counted towards this limit, nor is the
+    /// bus itself. Each bus has its own limit set with
+    /// [Bus::set_voice_limit], so the most voices that play at once is the sum
+    /// of the limits of the Manager and all of its buses.
+    pub fn set_voice_limit(&mut self, limit: Option<VoiceLimit>) {
+        self.mixer_controller
+            .send_command(move |mixer: &mut SoundMixer| mixer.set_voice_limit(limit));
     }
 
     /// Stop playing and remove all audio sounds. New sounds can still be added.
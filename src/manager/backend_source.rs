@@ -1,9 +1,10 @@
 use crate::Sound;
 
 /// Backends obtain the samples to output from a BackendSource. The default
-/// BackendSource is the [Renderer](crate::manager::Renderer). You can wrap a
-/// Renderer to make changes before output to a backend (e.g. a global volume or
-/// mute control).
+/// BackendSource is the [Renderer](crate::manager::Renderer). Global volume,
+/// mute, pause and processing of the final mix are controlled on the
+/// [Manager](crate::manager::Manager) (see
+/// [Manager::add_master_effect](crate::manager::Manager::add_master_effect)).
 ///
 /// Different backends are implemented in external crates for different
 /// platforms and use cases.
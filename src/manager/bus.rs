@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use crate::deferred::Deferred;
+use crate::events::{EventSender, SharedSoundState, SoundId, SoundState};
+use crate::sounds::wrappers::{Controllable, Controller, Wrapper};
+use crate::sounds::{Ducking, MasterStage, PreparedSound, SharedFormat, SoundMixer, VoiceLimit};
+use crate::Sound;
+
+use super::SoundHandle;
+
+/// Control a group of sounds created with
+/// [Manager::create_bus][crate::manager::Manager::create_bus] or
+/// [Bus::create_bus].
+///
+/// A bus is a submix: the sounds played on it are mixed together and the mix
+/// is played on the Manager or on the parent bus. Its volume, mute and pause
+/// apply to all of its sounds and nested buses.
+///
+/// Like a [SoundHandle], commands are applied at the start of the next batch
+/// of samples rendered by the backend. A bus lives as long as its parent,
+/// even after all of its handles are dropped.
+#[derive(Clone)]
+pub struct Bus {
+    name: Arc<str>,
+    id: SoundId,
+    mixer_controller: Controller<SoundMixer>,
+    events: EventSender,
+    format: Arc<SharedFormat>,
+}
+
+impl Bus {
+    /// Create a new bus and add it to `parent`.
+    pub(crate) fn new(
+        name: &str,
+        parent: &mut Controller<SoundMixer>,
+        parent_format: &Arc<SharedFormat>,
+        events: &EventSender,
+    ) -> Self {
+        let id = SoundId::new();
+        // The bus follows the channel count and sample rate of the parent.
+        // Clipping is left to the final output.
+        let (channel_count, sample_rate, _) = parent_format.load();
+        let mut mixer = SoundMixer::new(channel_count, sample_rate);
+        mixer.set_master_stage(MasterStage::None);
+        let deferred = Deferred::worker();
+        mixer.set_deferred(deferred.clone());
+        mixer.set_event_sender(events.clone());
+        mixer.follow_format(parent_format.clone());
+        let format = mixer.shared_format();
+        let (mut mixer, mixer_controller) = Controllable::new(mixer);
+        mixer.set_deferred(deferred);
+        // Timed commands and scheduled sounds use the output frames like
+        // those of the Manager, even while the bus has nothing to play.
+        mixer.follow_frame(parent.shared_frame());
+        mixer
+            .inner_mut()
+            .follow_frame(mixer_controller.shared_frame());
+        let bus = PreparedSound::bus(mixer, mixer_controller.clone(), id, parent_format);
+        parent.send_command(move |parent: &mut SoundMixer| parent.add_prepared(bus));
+        Bus {
+            name: name.into(),
+            id,
+            mixer_controller,
+            events: events.clone(),
+            format,
+        }
+    }
+
+    /// The name given when the bus was created.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The identifier of the bus in the mixer of its parent.
+    pub fn id(&self) -> SoundId {
+        self.id
+    }
+
+    /// Play `sound` on this bus. See
+    /// [Manager::play][crate::manager::Manager::play].
+    pub fn play(&mut self, sound: Box<dyn Sound>) -> SoundId {
+        let id = SoundId::new();
+        let sound = PreparedSound::new(sound, id, &self.format);
+        self.mixer_controller
+            .send_command(move |mixer: &mut SoundMixer| mixer.add_prepared(sound));
+        id
+    }
+
+    /// The same as [Bus::play] but returns a handle to control the sound
+    /// while it plays and query its state.
+    pub fn play_with_handle(&mut self, sound: Box<dyn Sound>) -> SoundHandle {
+        let id = SoundId::new();
+        let state = Arc::new(SharedSoundState::new(SoundState::Playing));
+        let sound = PreparedSound::new(sound, id, &self.format).with_state(state.clone());
+        self.mixer_controller
+            .send_command(move |mixer: &mut SoundMixer| mixer.add_prepared(sound));
+        SoundHandle::new(id, state, self.mixer_controller.clone())
+    }
+
+    /// Create a bus nested in this one.
+    pub fn create_bus(&mut self, name: &str) -> Bus {
+        Bus::new(name, &mut self.mixer_controller, &self.format, &self.events)
+    }
+
+    /// Set the volume multiplier of the bus. Changes are ramped over 10ms.
+    pub fn set_volume(&mut self, multiplier: f32) {
+        self.mixer_controller.set_volume(multiplier);
+    }
+
+    /// Set the volume of the bus in decibels.
+    pub fn set_volume_db(&mut self, db: f32) {
+        self.mixer_controller.set_volume_db(db);
+    }
+
+    /// Mute or unmute the bus. Its sounds keep playing while muted.
+    pub fn set_muted(&mut self, muted: bool) {
+        self.mixer_controller
+            .send_command(move |mixer: &mut SoundMixer| mixer.set_muted(muted));
+    }
+
+    /// Pause the sounds of the bus until [resume][Bus::resume] is called.
+    pub fn pause(&mut self) {
+        self.set_paused(true);
+    }
+
+    /// Continue playing the sounds of the bus after it was paused.
+    pub fn resume(&mut self) {
+        self.set_paused(false);
+    }
+
+    /// Pause or resume the sounds of the bus.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.mixer_controller.set_paused(paused);
+    }
+
+    /// Lower the volume of a sound or bus played on this bus while another
+    /// plays. See [Ducking].
+    pub fn add_ducking(&mut self, ducking: Ducking) {
+        self.mixer_controller
+            .send_command(move |mixer: &mut SoundMixer| mixer.add_ducking(ducking));
+    }
+
+    /// Stop `trigger` from ducking `target`.
+    pub fn remove_ducking(&mut self, trigger: SoundId, target: SoundId) {
+        self.mixer_controller
+            .send_command(move |mixer: &mut SoundMixer| mixer.remove_ducking(trigger, target));
+    }
+
+    /// Limit how many sounds played on this bus play at once. See [VoiceLimit].
+    pub fn set_voice_limit(&mut self, limit: Option<VoiceLimit>) {
+        self.mixer_controller
+            .send_command(move |mixer: &mut SoundMixer| mixer.set_voice_limit(limit));
+    }
+
+    /// Stop and remove all sounds of the bus and its nested buses. New sounds
+    /// can still be played.
+    pub fn clear(&mut self) {
+        self.mixer_controller.clear();
+    }
+}
+
+impl std::fmt::Debug for Bus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Bus")
+            .field("name", &self.name)
+            .field("id", &self.id)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/bus.rs"]
+mod tests;
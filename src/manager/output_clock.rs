@@ -0,0 +1,201 @@
+use std::sync::atomic::{fence, AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// The output sample clock of a [Manager][crate::manager::Manager] obtained
+/// with [Manager::output_clock][crate::manager::Manager::output_clock].
+///
+/// The clock counts the frames the [Renderer][crate::manager::Renderer] has
+/// handed to the backend, including silence output while the Manager is
+/// paused, so it only ever moves forward. It is updated at the start of each
+/// batch. This is the clock of
+/// [Manager::play_at][crate::manager::Manager::play_at] and the `_at`
+/// methods of [SoundHandle][crate::manager::SoundHandle].
+///
+/// Backends that know when a batch will be heard, such as the cpal backend,
+/// report it with
+/// [Renderer::set_batch_timestamp][crate::manager::Renderer::set_batch_timestamp].
+/// This gives an estimate of the output latency and lets frames be converted
+/// to and from [Instant]s, e.g. to sync animations with audio.
+#[derive(Clone)]
+pub struct OutputClock {
+    shared: Arc<SharedClock>,
+}
+
+/// A consistent view of the [SharedClock] fields.
+#[derive(Clone, Copy)]
+struct Snapshot {
+    frames: u64,
+    sample_rate: u32,
+    /// A frame and when it is heard in nanoseconds since `base`.
+    anchor: Option<(u64, u64)>,
+    latency: Option<Duration>,
+}
+
+/// Written by the renderer and read by any number of clocks. A sequence lock
+/// keeps readers from seeing fields from different batches without making the
+/// renderer wait.
+pub(crate) struct SharedClock {
+    base: Instant,
+    /// Odd while the fields are being written.
+    sequence: AtomicU64,
+    frames: AtomicU64,
+    sample_rate: AtomicU32,
+    anchor_frame: AtomicU64,
+    /// u64::MAX when no timestamp has been reported.
+    anchor_nanos: AtomicU64,
+    latency_nanos: AtomicU64,
+}
+
+impl SharedClock {
+    pub(crate) fn new(sample_rate: u32) -> Self {
+        SharedClock {
+            base: Instant::now(),
+            sequence: AtomicU64::new(0),
+            frames: AtomicU64::new(0),
+            sample_rate: AtomicU32::new(sample_rate),
+            anchor_frame: AtomicU64::new(0),
+            anchor_nanos: AtomicU64::new(u64::MAX),
+            latency_nanos: AtomicU64::new(u64::MAX),
+        }
+    }
+
+    /// Publish the state at the start of a batch. Must only be called by a
+    /// single writer.
+    pub(crate) fn publish(
+        &self,
+        frames: u64,
+        sample_rate: u32,
+        timestamp: Option<(Instant, Instant)>,
+    ) {
+        self.sequence.fetch_add(1, Ordering::Relaxed);
+        fence(Ordering::Release);
+        self.frames.store(frames, Ordering::Relaxed);
+        self.sample_rate.store(sample_rate, Ordering::Relaxed);
+        if let Some((callback, playback)) = timestamp {
+            self.anchor_frame.store(frames, Ordering::Relaxed);
+            self.anchor_nanos
+                .store(self.nanos_since_base(playback), Ordering::Relaxed);
+            self.latency_nanos.store(
+                playback.saturating_duration_since(callback).as_nanos() as u64,
+                Ordering::Relaxed,
+            );
+        }
+        self.sequence.fetch_add(1, Ordering::Release);
+    }
+
+    fn nanos_since_base(&self, instant: Instant) -> u64 {
+        (instant.saturating_duration_since(self.base).as_nanos() as u64).min(u64::MAX - 1)
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        loop {
+            let before = self.sequence.load(Ordering::Acquire);
+            if before % 2 == 1 {
+                std::hint::spin_loop();
+                continue;
+            }
+            let frames = self.frames.load(Ordering::Relaxed);
+            let sample_rate = self.sample_rate.load(Ordering::Relaxed);
+            let anchor_frame = self.anchor_frame.load(Ordering::Relaxed);
+            let anchor_nanos = self.anchor_nanos.load(Ordering::Relaxed);
+            let latency_nanos = self.latency_nanos.load(Ordering::Relaxed);
+            fence(Ordering::Acquire);
+            if self.sequence.load(Ordering::Relaxed) == before {
+                return Snapshot {
+                    frames,
+                    sample_rate,
+                    anchor: (anchor_nanos != u64::MAX).then_some((anchor_frame, anchor_nanos)),
+                    latency: (latency_nanos != u64::MAX)
+                        .then(|| Duration::from_nanos(latency_nanos)),
+                };
+            }
+        }
+    }
+}
+
+impl OutputClock {
+    pub(crate) fn new(shared: Arc<SharedClock>) -> Self {
+        OutputClock { shared }
+    }
+
+    /// The number of frames rendered before the current batch.
+    pub fn frames(&self) -> u64 {
+        self.shared.snapshot().frames
+    }
+
+    /// The output sample rate, i.e. how many frames are rendered per second.
+    pub fn sample_rate(&self) -> u32 {
+        self.shared.snapshot().sample_rate
+    }
+
+    /// The estimated time from when the backend requested the last batch until
+    /// its first frame is heard. None if the backend does not report
+    /// timestamps.
+    pub fn latency(&self) -> Option<Duration> {
+        self.shared.snapshot().latency
+    }
+
+    /// When `frame` is or was heard. None if the backend does not report
+    /// timestamps.
+    ///
+    /// Assumes the sample rate has not changed between `frame` and the last
+    /// batch.
+    pub fn frame_to_instant(&self, frame: u64) -> Option<Instant> {
+        let snapshot = self.shared.snapshot();
+        let (anchor_frame, anchor_nanos) = snapshot.anchor?;
+        let anchor = self.shared.base + Duration::from_nanos(anchor_nanos);
+        let to_duration = |frames: u64| {
+            Duration::from_nanos(
+                (frames as u128 * NANOS_PER_SEC / snapshot.sample_rate as u128) as u64,
+            )
+        };
+        if frame >= anchor_frame {
+            anchor.checked_add(to_duration(frame - anchor_frame))
+        } else {
+            anchor.checked_sub(to_duration(anchor_frame - frame))
+        }
+    }
+
+    /// The frame heard at `instant`. Instants before the first frame give 0.
+    /// None if the backend does not report timestamps.
+    pub fn instant_to_frame(&self, instant: Instant) -> Option<u64> {
+        let snapshot = self.shared.snapshot();
+        let (anchor_frame, anchor_nanos) = snapshot.anchor?;
+        let anchor = self.shared.base + Duration::from_nanos(anchor_nanos);
+        let sample_rate = snapshot.sample_rate as u128;
+        match instant.checked_duration_since(anchor) {
+            Some(after) => {
+                Some(anchor_frame + (after.as_nanos() * sample_rate / NANOS_PER_SEC) as u64)
+            }
+            None => {
+                // Round down to the frame playing at `instant`.
+                let before = (anchor - instant).as_nanos() * sample_rate;
+                let frames = before.div_ceil(NANOS_PER_SEC) as u64;
+                Some(anchor_frame.saturating_sub(frames))
+            }
+        }
+    }
+
+    /// The frame being heard now. See [OutputClock::instant_to_frame].
+    pub fn now_playing(&self) -> Option<u64> {
+        self.instant_to_frame(Instant::now())
+    }
+}
+
+impl std::fmt::Debug for OutputClock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let snapshot = self.shared.snapshot();
+        f.debug_struct("OutputClock")
+            .field("frames", &snapshot.frames)
+            .field("sample_rate", &snapshot.sample_rate)
+            .field("latency", &snapshot.latency)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/output_clock.rs"]
+mod tests;
@@ -1,3 +1,6 @@
+use std::sync::Arc;
+use std::time::Instant;
+
 use crate::sounds::wrappers::Controllable;
 use crate::sounds::wrappers::Wrapper;
 use crate::sounds::SoundMixer;
@@ -5,16 +8,62 @@ use crate::NextSample;
 use crate::Sound;
 
 use super::backend_source::BackendSource;
+use super::output_clock::SharedClock;
 
 /// The default [BackendSource]. Renderer is essentially half of
 /// [Manager][crate::manager::Manager].
 pub struct Renderer {
     mixer: Controllable<SoundMixer>,
+    clock: Arc<SharedClock>,
+    /// Frames handed to the backend. See
+    /// [OutputClock][crate::manager::OutputClock].
+    frames: u64,
+    next_channel_idx: u16,
+    timestamp: Option<(Instant, Instant)>,
 }
 
 impl Renderer {
-    pub(crate) fn new(mixer: Controllable<SoundMixer>) -> Self {
-        Renderer { mixer }
+    pub(crate) fn new(mixer: Controllable<SoundMixer>, clock: Arc<SharedClock>) -> Self {
+        Renderer {
+            mixer,
+            clock,
+            frames: 0,
+            next_channel_idx: 0,
+            timestamp: None,
+        }
+    }
+
+    /// Report that the backend requested the next batch at `callback` and
+    /// that its first frame will be heard at `playback`. Must be called before
+    /// [on_start_of_batch][Sound::on_start_of_batch].
+    ///
+    /// Backends that know when their output is heard should call this for
+    /// every batch so the [OutputClock][crate::manager::OutputClock] can
+    /// convert between frames and instants.
+    pub fn set_batch_timestamp(&mut self, callback: Instant, playback: Instant) {
+        self.timestamp = Some((callback, playback));
+    }
+
+    /// Count the frames of a buffer filled by the backend. Buffers are
+    /// counted whole since backends output silence after Paused or Finished.
+    fn count_buffer<T>(&mut self, len: usize, next: &Option<NextSample<T>>) {
+        if !matches!(next, Some(NextSample::MetadataChanged)) {
+            self.frames += (len / self.mixer.channel_count() as usize) as u64;
+        }
+    }
+
+    fn count_sample<T>(&mut self, next: &NextSample<T>) {
+        match next {
+            NextSample::Sample(_) => {
+                self.next_channel_idx += 1;
+                if self.next_channel_idx == self.mixer.channel_count() {
+                    self.next_channel_idx = 0;
+                    self.frames += 1;
+                }
+            }
+            NextSample::MetadataChanged => self.next_channel_idx = 0,
+            NextSample::Paused | NextSample::Finished => (),
+        }
     }
 }
 
@@ -48,15 +97,51 @@ impl Sound for Renderer {
     ///
     /// Guaranteed to not return an Error.
     fn next_sample(&mut self) -> Result<NextSample, crate::Error> {
-        self.mixer.next_sample()
+        let next = self.mixer.next_sample()?;
+        self.count_sample(&next);
+        Ok(next)
+    }
+
+    /// The block version of `next_sample` with the same guarantees.
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        let (num_frames, next) = self.mixer.fill_buffer(buffer)?;
+        self.count_buffer(buffer.len(), &next);
+        Ok((num_frames, next))
+    }
+
+    /// The float version of `next_sample` with the same guarantees.
+    fn next_sample_f32(&mut self) -> Result<NextSample<f32>, crate::Error> {
+        let next = self.mixer.next_sample_f32()?;
+        self.count_sample(&next);
+        Ok(next)
+    }
+
+    /// The float version of `fill_buffer` with the same guarantees.
+    fn fill_buffer_f32(
+        &mut self,
+        buffer: &mut [f32],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        let (num_frames, next) = self.mixer.fill_buffer_f32(buffer)?;
+        self.count_buffer(buffer.len(), &next);
+        Ok((num_frames, next))
     }
 
     /// Inform the playing or queued sounds that a new batch of samples will be
     /// requested. This must only be called when the next sample to be delivered
     /// from `next_sample` is for the first channel.
     ///
+    /// Also publishes the [OutputClock][crate::manager::OutputClock].
+    ///
     /// See [Sound::on_start_of_batch]
     fn on_start_of_batch(&mut self) {
+        self.clock
+            .publish(self.frames, self.mixer.sample_rate(), self.timestamp.take());
+        // The mixer only counts the frames it outputs so catch it up with the
+        // silence output since the last batch.
+        self.mixer.set_frame(self.frames);
         self.mixer.on_start_of_batch()
     }
 }
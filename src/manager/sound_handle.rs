@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use crate::events::{SharedSoundState, SoundId, SoundState};
+use crate::sounds::wrappers::{Controller, ResamplerQuality};
+use crate::sounds::SoundMixer;
+
+/// Control a single sound played with
+/// [Manager::play_with_handle][crate::manager::Manager::play_with_handle].
+///
+/// Commands are applied at the start of the next batch of samples rendered by
+/// the backend. Commands for a sound that has finished are ignored.
+///
+/// Like a clone of the [Manager][crate::manager::Manager], a handle keeps the
+/// Renderer from finishing while it exists.
+#[derive(Clone)]
+pub struct SoundHandle {
+    id: SoundId,
+    state: Arc<SharedSoundState>,
+    mixer_controller: Controller<SoundMixer>,
+}
+
+impl SoundHandle {
+    pub(crate) fn new(
+        id: SoundId,
+        state: Arc<SharedSoundState>,
+        mixer_controller: Controller<SoundMixer>,
+    ) -> Self {
+        SoundHandle {
+            id,
+            state,
+            mixer_controller,
+        }
+    }
+
+    /// The identifier of the sound in events.
+    pub fn id(&self) -> SoundId {
+        self.id
+    }
+
+    /// Whether the sound is playing, paused, finished or errored.
+    pub fn state(&self) -> SoundState {
+        self.state.get()
+    }
+
+    /// Stop playing the sound and remove it. Subscribers receive a
+    /// [Finished][crate::events::SoundEvent::Finished] event for it.
+    pub fn stop(&mut self) {
+        let id = self.id;
+        self.mixer_controller
+            .send_command(move |mixer: &mut SoundMixer| mixer.stop_sound(id));
+    }
+
+    /// Pause the sound until [resume][SoundHandle::resume] is called.
+    pub fn pause(&mut self) {
+        self.set_paused(true);
+    }
+
+    /// Continue playing the sound after it was paused.
+    pub fn resume(&mut self) {
+        self.set_paused(false);
+    }
+
+    /// Pause or resume the sound.
+    pub fn set_paused(&mut self, paused: bool) {
+        let id = self.id;
+        self.mixer_controller
+            .send_command(move |mixer: &mut SoundMixer| mixer.set_sound_paused(id, paused));
+    }
+
+    /// Set the volume multiplier of the sound. 1.0 is the original volume.
+    pub fn set_volume(&mut self, multiplier: f32) {
+        let id = self.id;
+        self.mixer_controller
+            .send_command(move |mixer: &mut SoundMixer| mixer.set_sound_volume(id, multiplier));
+    }
+
+    /// Stop the sound exactly at `frame` of
+    /// [Manager::output_frame][crate::manager::Manager::output_frame].
+    pub fn stop_at(&mut self, frame: u64) {
+        let id = self.id;
+        self.mixer_controller
+            .send_command_at(frame, move |mixer: &mut SoundMixer| mixer.stop_sound(id));
+    }
+
+    /// Pause or resume the sound exactly at `frame` of
+    /// [Manager::output_frame][crate::manager::Manager::output_frame].
+    pub fn set_paused_at(&mut self, frame: u64, paused: bool) {
+        let id = self.id;
+        self.mixer_controller
+            .send_command_at(frame, move |mixer: &mut SoundMixer| {
+                mixer.set_sound_paused(id, paused)
+            });
+    }
+
+    /// Set the volume multiplier of the sound exactly at `frame` of
+    /// [Manager::output_frame][crate::manager::Manager::output_frame].
+    pub fn set_volume_at(&mut self, frame: u64, multiplier: f32) {
+        let id = self.id;
+        self.mixer_controller
+            .send_command_at(frame, move |mixer: &mut SoundMixer| {
+                mixer.set_sound_volume(id, multiplier)
+            });
+    }
+
+    /// Set the priority of the sound for a
+    /// [VoiceLimit][crate::sounds::VoiceLimit]. Voices with a lower priority
+    /// are stolen first. Sounds start with a priority of 0.
+    pub fn set_priority(&mut self, priority: i32) {
+        let id = self.id;
+        self.mixer_controller
+            .send_command(move |mixer: &mut SoundMixer| mixer.set_sound_priority(id, priority));
+    }
+
+    /// Set the resampler quality of the sound, overriding the quality set with
+    /// [Manager::set_resampler_quality][crate::manager::Manager::set_resampler_quality].
+    pub fn set_resampler_quality(&mut self, quality: ResamplerQuality) {
+        let id = self.id;
+        self.mixer_controller
+            .send_command(move |mixer: &mut SoundMixer| {
+                mixer.set_sound_resampler_quality(id, quality)
+            });
+    }
+}
+
+impl std::fmt::Debug for SoundHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SoundHandle")
+            .field("id", &self.id)
+            .field("state", &self.state())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/sound_handle.rs"]
+mod tests;
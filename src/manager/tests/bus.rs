@@ -0,0 +1,137 @@
+use crate::{
+    events::SoundState,
+    manager::{BackendSource, Manager, Renderer},
+    tests::{ConstantValueSound, DEFAULT_CHANNEL_COUNT, DEFAULT_SAMPLE_RATE},
+    NextSample, Sound,
+};
+
+fn start() -> (Manager, Renderer) {
+    let (manager, mut renderer) = Manager::new();
+    renderer.set_output_channel_count_and_sample_rate(DEFAULT_CHANNEL_COUNT, DEFAULT_SAMPLE_RATE);
+    assert_eq!(renderer.next_sample().unwrap(), NextSample::MetadataChanged);
+    (manager, renderer)
+}
+
+/// Render enough batches for volume ramps to complete and return the last
+/// frame.
+fn render(renderer: &mut Renderer) -> [i16; 2] {
+    let mut buffer = [0; 1024];
+    for _ in 0..2 {
+        renderer.on_start_of_batch();
+        renderer.fill_buffer(&mut buffer).unwrap();
+    }
+    [buffer[1022], buffer[1023]]
+}
+
+#[test]
+fn bus_volume_and_mute() {
+    let (mut manager, mut renderer) = start();
+    let mut music = manager.create_bus("music");
+    assert_eq!(music.name(), "music");
+    music.play(Box::new(ConstantValueSound::new(1000)));
+    manager.play(Box::new(ConstantValueSound::new(10)));
+    assert_eq!(render(&mut renderer), [1010, 1010]);
+
+    music.set_volume(0.5);
+    assert_eq!(render(&mut renderer), [510, 510]);
+
+    music.set_muted(true);
+    assert_eq!(render(&mut renderer), [10, 10]);
+}
+
+#[test]
+fn pause_and_clear_bus() {
+    let (mut manager, mut renderer) = start();
+    let mut effects = manager.create_bus("effects");
+    let handle = effects.play_with_handle(Box::new(ConstantValueSound::new(1000)));
+    manager.play(Box::new(ConstantValueSound::new(10)));
+    assert_eq!(render(&mut renderer), [1010, 1010]);
+
+    effects.pause();
+    assert_eq!(render(&mut renderer), [10, 10]);
+    assert_eq!(handle.state(), SoundState::Playing);
+    effects.resume();
+    assert_eq!(render(&mut renderer), [1010, 1010]);
+
+    effects.clear();
+    assert_eq!(render(&mut renderer), [10, 10]);
+    assert_eq!(handle.state(), SoundState::Finished);
+    effects.play(Box::new(ConstantValueSound::new(100)));
+    assert_eq!(render(&mut renderer), [110, 110]);
+}
+
+#[test]
+fn nested_buses() {
+    let (mut manager, mut renderer) = start();
+    let mut game = manager.create_bus("game");
+    let mut voice = game.create_bus("voice");
+    let handle = voice.play_with_handle(Box::new(ConstantValueSound::new(1000)));
+    game.play(Box::new(ConstantValueSound::new(100)));
+    assert_eq!(render(&mut renderer), [1100, 1100]);
+
+    game.set_volume(0.5);
+    voice.set_volume(0.5);
+    assert_eq!(render(&mut renderer), [300, 300]);
+
+    manager.clear();
+    assert_eq!(render(&mut renderer), [0, 0]);
+    assert_eq!(handle.state(), SoundState::Finished);
+    voice.play(Box::new(ConstantValueSound::new(1000)));
+    assert_eq!(render(&mut renderer), [250, 250]);
+}
+
+#[test]
+fn voice_bus_ducks_music_bus() {
+    let (mut manager, mut renderer) = start();
+    let mut music = manager.create_bus("music");
+    let mut voice = manager.create_bus("voice");
+    manager.add_ducking(crate::sounds::Ducking::new(voice.id(), music.id(), -6.0));
+    music.play(Box::new(ConstantValueSound::new(1000)));
+    assert_eq!(render(&mut renderer), [1000, 1000]);
+
+    let narration = voice.play_with_handle(Box::new(ConstantValueSound::new(0)));
+    // The default attack is 50ms.
+    for _ in 0..3 {
+        render(&mut renderer);
+    }
+    let ducked = render(&mut renderer);
+    assert_eq!(ducked[0], ducked[1]);
+    assert!(ducked[0] < 510 && ducked[0] > 490);
+
+    let mut narration = narration;
+    narration.stop();
+    // The default release is 500ms.
+    for _ in 0..30 {
+        render(&mut renderer);
+    }
+    assert_eq!(render(&mut renderer), [1000, 1000]);
+}
+
+#[test]
+fn timed_commands_on_bus_sounds_use_output_frames() {
+    let (mut manager, mut renderer) = Manager::new();
+    renderer.set_output_channel_count_and_sample_rate(1, DEFAULT_SAMPLE_RATE);
+    assert_eq!(renderer.next_sample().unwrap(), NextSample::MetadataChanged);
+    let mut buffer = [0; 4];
+    for _ in 0..2 {
+        renderer.on_start_of_batch();
+        renderer.fill_buffer(&mut buffer).unwrap();
+    }
+    // The bus is created after frames were output and is silent at first.
+    let mut bus = manager.create_bus("effects");
+    renderer.on_start_of_batch();
+    renderer.fill_buffer(&mut buffer).unwrap();
+    let mut handle = bus.play_with_handle(Box::new(ConstantValueSound::new(100)));
+    renderer.on_start_of_batch();
+    renderer.fill_buffer(&mut buffer).unwrap();
+    assert_eq!(buffer, [100; 4]);
+    renderer.on_start_of_batch();
+    assert_eq!(manager.output_frame(), 16);
+
+    handle.set_volume_at(18, 0.5);
+    handle.stop_at(19);
+    renderer.on_start_of_batch();
+    assert_eq!(renderer.fill_buffer(&mut buffer).unwrap().0, 3);
+    assert_eq!(buffer[..3], [100, 100, 50]);
+    assert_eq!(handle.state(), SoundState::Finished);
+}
@@ -0,0 +1,152 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    manager::{BackendSource, Manager, Renderer},
+    tests::ConstantValueSound,
+    NextSample, Sound,
+};
+
+fn start(channel_count: u16) -> (Manager, Renderer) {
+    let (manager, mut renderer) = Manager::new();
+    renderer.set_output_channel_count_and_sample_rate(channel_count, 1000);
+    assert_eq!(renderer.next_sample().unwrap(), NextSample::MetadataChanged);
+    (manager, renderer)
+}
+
+fn render(renderer: &mut Renderer) {
+    let mut buffer = [0; 10];
+    renderer.on_start_of_batch();
+    renderer.fill_buffer(&mut buffer).unwrap();
+}
+
+#[test]
+fn counts_frames_including_silence() {
+    let (mut manager, mut renderer) = start(1);
+    let clock = manager.output_clock();
+    render(&mut renderer);
+    assert_eq!(clock.frames(), 0);
+    render(&mut renderer);
+    assert_eq!(clock.frames(), 10);
+    assert_eq!(clock.sample_rate(), 1000);
+
+    manager.play(Box::new(ConstantValueSound::new(5)));
+    manager.set_paused(true);
+    for _ in 0..3 {
+        render(&mut renderer);
+    }
+    renderer.on_start_of_batch();
+    assert_eq!(clock.frames(), 50);
+}
+
+#[test]
+fn counts_frames_from_next_sample() {
+    let (mut manager, mut renderer) = start(2);
+    let clock = manager.output_clock();
+    manager.play(Box::new(ConstantValueSound::new(5)));
+    renderer.on_start_of_batch();
+    for _ in 0..7 {
+        assert!(matches!(
+            renderer.next_sample().unwrap(),
+            NextSample::Sample(_)
+        ));
+    }
+    assert_eq!(renderer.next_sample().unwrap(), NextSample::Sample(5));
+    renderer.on_start_of_batch();
+    assert_eq!(clock.frames(), 4);
+}
+
+#[test]
+fn converts_between_frames_and_instants() {
+    let (manager, mut renderer) = start(1);
+    let clock = manager.output_clock();
+    for _ in 0..10 {
+        render(&mut renderer);
+    }
+    assert_eq!(clock.latency(), None);
+    assert_eq!(clock.frame_to_instant(100), None);
+    assert_eq!(clock.instant_to_frame(Instant::now()), None);
+
+    let callback = Instant::now();
+    let playback = callback + Duration::from_millis(50);
+    renderer.set_batch_timestamp(callback, playback);
+    renderer.on_start_of_batch();
+    assert_eq!(clock.frames(), 100);
+    assert_eq!(clock.latency(), Some(Duration::from_millis(50)));
+    assert_eq!(clock.frame_to_instant(100), Some(playback));
+    assert_eq!(
+        clock.frame_to_instant(1100),
+        Some(playback + Duration::from_secs(1))
+    );
+    assert_eq!(
+        clock.frame_to_instant(90),
+        Some(playback - Duration::from_millis(10))
+    );
+    assert_eq!(
+        clock.instant_to_frame(playback + Duration::from_micros(500_500)),
+        Some(600)
+    );
+    assert_eq!(clock.instant_to_frame(callback), Some(50));
+    assert_eq!(
+        clock.instant_to_frame(callback - Duration::from_micros(500)),
+        Some(49)
+    );
+    assert_eq!(
+        clock.instant_to_frame(callback - Duration::from_secs(1)),
+        Some(0)
+    );
+
+    // The anchor is kept for batches without a timestamp.
+    render(&mut renderer);
+    renderer.on_start_of_batch();
+    assert_eq!(clock.frames(), 110);
+    assert_eq!(clock.frame_to_instant(100), Some(playback));
+}
+
+#[test]
+fn play_at_counts_silence_while_paused() {
+    let (mut manager, mut renderer) = start(1);
+    manager.set_paused(true);
+    manager.play(Box::new(ConstantValueSound::new(1)));
+    for _ in 0..3 {
+        render(&mut renderer);
+    }
+
+    manager.clear();
+    manager.set_paused(false);
+    manager.play_at(Box::new(ConstantValueSound::new(5)), 45);
+    render(&mut renderer);
+    let mut buffer = [0; 10];
+    renderer.on_start_of_batch();
+    renderer.fill_buffer(&mut buffer).unwrap();
+    assert_eq!(buffer, [0, 0, 0, 0, 0, 5, 5, 5, 5, 5]);
+}
+
+#[test]
+fn play_at_instant_after_pause() {
+    let (mut manager, mut renderer) = start(1);
+    let clock = manager.output_clock();
+    manager.set_paused(true);
+    manager.play(Box::new(ConstantValueSound::new(1)));
+    for _ in 0..3 {
+        render(&mut renderer);
+    }
+    // The sound plays while the resume is ramped in.
+    manager.set_paused(false);
+
+    let callback = Instant::now();
+    let playback = callback + Duration::from_millis(50);
+    renderer.set_batch_timestamp(callback, playback);
+    render(&mut renderer);
+    assert_eq!(manager.output_frame(), clock.frames());
+
+    let frame = clock
+        .instant_to_frame(playback + Duration::from_millis(15))
+        .unwrap();
+    assert_eq!(frame, 45);
+    manager.clear();
+    manager.play_at(Box::new(ConstantValueSound::new(5)), frame);
+    let mut buffer = [0; 10];
+    renderer.on_start_of_batch();
+    renderer.fill_buffer(&mut buffer).unwrap();
+    assert_eq!(buffer, [0, 0, 0, 0, 0, 5, 5, 5, 5, 5]);
+}
@@ -0,0 +1,93 @@
+use crate::{
+    events::SoundState,
+    manager::{BackendSource, Manager},
+    tests::{ConstantValueSound, DEFAULT_CHANNEL_COUNT, DEFAULT_SAMPLE_RATE},
+    NextSample, Sound,
+};
+
+#[test]
+fn control_and_state() {
+    let (mut manager, mut renderer) = Manager::new();
+    renderer.set_output_channel_count_and_sample_rate(DEFAULT_CHANNEL_COUNT, DEFAULT_SAMPLE_RATE);
+    assert_eq!(renderer.next_sample().unwrap(), NextSample::MetadataChanged);
+    let mut first = manager.play_with_handle(Box::new(ConstantValueSound::new(100)));
+    let mut second = manager.play_with_handle(Box::new(ConstantValueSound::new(10)));
+    assert_ne!(first.id(), second.id());
+    assert_eq!(first.state(), SoundState::Playing);
+
+    let mut buffer = [0; 2];
+    renderer.on_start_of_batch();
+    renderer.fill_buffer(&mut buffer).unwrap();
+    assert_eq!(buffer, [110, 110]);
+
+    first.set_volume(0.5);
+    second.pause();
+    renderer.on_start_of_batch();
+    renderer.fill_buffer(&mut buffer).unwrap();
+    assert_eq!(buffer, [50, 50]);
+    assert_eq!(second.state(), SoundState::Paused);
+
+    second.resume();
+    first.stop();
+    renderer.on_start_of_batch();
+    renderer.fill_buffer(&mut buffer).unwrap();
+    assert_eq!(buffer, [10, 10]);
+    assert_eq!(first.state(), SoundState::Finished);
+    assert_eq!(second.state(), SoundState::Playing);
+}
+
+#[test]
+fn errored_state() {
+    let (mut manager, mut renderer) = Manager::new();
+    renderer.set_output_channel_count_and_sample_rate(DEFAULT_CHANNEL_COUNT, DEFAULT_SAMPLE_RATE);
+    assert_eq!(renderer.next_sample().unwrap(), NextSample::MetadataChanged);
+    let handle = manager.play_with_handle(Box::new(crate::tests::ErrorSound));
+    renderer.on_start_of_batch();
+    let mut buffer = [0; 2];
+    renderer.fill_buffer(&mut buffer).unwrap();
+    assert_eq!(handle.state(), SoundState::Errored);
+}
+
+#[test]
+fn timed_commands_apply_at_output_frame() {
+    let (mut manager, mut renderer) = Manager::new();
+    renderer.set_output_channel_count_and_sample_rate(1, DEFAULT_SAMPLE_RATE);
+    assert_eq!(renderer.next_sample().unwrap(), NextSample::MetadataChanged);
+    let mut handle = manager.play_with_handle(Box::new(ConstantValueSound::new(100)));
+    let mut buffer = [0; 4];
+    renderer.on_start_of_batch();
+    renderer.fill_buffer(&mut buffer).unwrap();
+    renderer.on_start_of_batch();
+    assert_eq!(manager.output_frame(), 4);
+
+    handle.set_volume_at(6, 0.5);
+    handle.stop_at(7);
+    renderer.on_start_of_batch();
+    assert_eq!(renderer.fill_buffer(&mut buffer).unwrap().0, 3);
+    assert_eq!(buffer[..3], [100, 100, 50]);
+    assert_eq!(handle.state(), SoundState::Finished);
+}
+
+#[test]
+fn sounds_played_before_the_first_batch_are_not_lost() {
+    let (mut manager, mut renderer) = Manager::new();
+    renderer.set_output_channel_count_and_sample_rate(DEFAULT_CHANNEL_COUNT, DEFAULT_SAMPLE_RATE);
+    assert_eq!(renderer.next_sample().unwrap(), NextSample::MetadataChanged);
+    // More than fit in the command queue of the Manager.
+    let handles: Vec<_> = (0..300)
+        .map(|_| {
+            manager.play_with_handle(Box::new(crate::sounds::MemorySound::from_samples(
+                std::sync::Arc::new(vec![1; DEFAULT_CHANNEL_COUNT as usize]),
+                DEFAULT_CHANNEL_COUNT,
+                DEFAULT_SAMPLE_RATE,
+            )))
+        })
+        .collect();
+
+    let mut buffer = [0; 2 * DEFAULT_CHANNEL_COUNT as usize];
+    renderer.on_start_of_batch();
+    renderer.fill_buffer(&mut buffer).unwrap();
+    assert!(handles
+        .iter()
+        .all(|handle| handle.state() == SoundState::Finished));
+}
@@ -6,8 +6,9 @@ use std::{
 use crate::{
     sounds::{
         wrappers::{
-            AdjustableSpeed, AdjustableVolume, Controllable, Controller, FinishAfter, Pausable,
-            SetPaused, Stoppable,
+            AdjustableSpeed, AdjustableTempo, AdjustableVolume, Controllable, Controller, Fade,
+            FinishAfter, IntoFloat, Pannable, Pausable, PitchShift, Prefetched, SetPaused,
+            Stoppable,
         },
         MemorySound,
     },
@@ -47,6 +48,69 @@ pub trait Sound: Send {
     /// and `SoundList`).
     fn next_sample(&mut self) -> Result<NextSample, crate::Error>;
 
+    /// Fill `buffer` with whole frames of samples.
+    ///
+    /// Returns the number of frames written and, if the buffer could not be
+    /// completely filled, the `NextSample` variant that stopped it (never
+    /// `Sample`). `None` means every frame of `buffer` was written. The same
+    /// rules as for [next_sample][Sound::next_sample] apply to the returned
+    /// variant (e.g. after `MetadataChanged` the channel count may differ).
+    /// Samples of a partially written frame may be left in the buffer but are
+    /// not counted.
+    ///
+    /// The length of `buffer` must be a multiple of the channel count and this
+    /// must only be called at the start of a frame. The default implementation
+    /// calls next_sample for each sample. Implementations can override this to
+    /// produce samples in bulk which avoids a dynamic call per sample when
+    /// pulled through a `Box<dyn Sound>`.
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        fill_buffer_from_next_sample(self, buffer)
+    }
+
+    /// Same as [next_sample][Sound::next_sample] but the sample is a 32-bit
+    /// float in the range -1.0 to 1.0.
+    ///
+    /// Sounds that produce floats natively (e.g. some decoders) or that process
+    /// samples (e.g. volume adjustment) should override this and
+    /// [fill_buffer_f32][Sound::fill_buffer_f32] so that samples are not
+    /// quantized to 16 bits in between. The default implementation converts the
+    /// result of next_sample.
+    fn next_sample_f32(&mut self) -> Result<NextSample<f32>, crate::Error> {
+        Ok(self.next_sample()?.map(utils::i16_to_f32))
+    }
+
+    /// Same as [fill_buffer][Sound::fill_buffer] but with 32-bit float samples.
+    /// See [next_sample_f32][Sound::next_sample_f32].
+    ///
+    /// The default implementation converts the samples from fill_buffer.
+    fn fill_buffer_f32(
+        &mut self,
+        buffer: &mut [f32],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        let channel_count = self.channel_count() as usize;
+        let frames_per_chunk = CONVERSION_CHUNK_LEN / channel_count;
+        if frames_per_chunk == 0 {
+            return fill_buffer_from_next_sample(self, buffer);
+        }
+        let mut chunk = [0_i16; CONVERSION_CHUNK_LEN];
+        let mut num_frames = 0;
+        for out in buffer.chunks_mut(frames_per_chunk * channel_count) {
+            let chunk = &mut chunk[..out.len()];
+            let (new_frames, next) = self.fill_buffer(chunk)?;
+            for (out, sample) in out.iter_mut().zip(&chunk[..new_frames * channel_count]) {
+                *out = utils::i16_to_f32(*sample);
+            }
+            num_frames += new_frames;
+            if next.is_some() {
+                return Ok((num_frames, next));
+            }
+        }
+        Ok((num_frames, None))
+    }
+
     /// Called whenever a new batch of audio samples is requested by the
     /// backend.
     ///
@@ -54,6 +118,23 @@ pub trait Sound: Send {
     /// but not for every single audio sample.
     fn on_start_of_batch(&mut self);
 
+    /// Returns how far into the sound playback currently is, if known.
+    ///
+    /// This is the time it would take to play all samples returned so far at
+    /// normal speed. Samples held back by wrappers (e.g. for resampling) are
+    /// not counted.
+    fn position(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Returns the total length of the sound, if known.
+    ///
+    /// Sounds that play indefinitely or whose length cannot be determined
+    /// without decoding all samples return None.
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+
     /// Returns the next sample for all channels.
     ///
     /// It is the callers responsibility to ensure this function is only called
@@ -113,6 +194,23 @@ pub trait Sound: Send {
         Ok(to_return)
     }
 
+    /// Decode this sound on a worker thread, keeping up to `buffer` of samples
+    /// decoded ahead. See [Prefetched].
+    fn prefetched(self, buffer: Duration) -> Result<Prefetched, crate::Error>
+    where
+        Self: Sized + 'static,
+    {
+        Prefetched::new(self, buffer)
+    }
+
+    /// Use this sound where a [FloatSound][crate::FloatSound] is expected.
+    fn into_float(self) -> IntoFloat<Self>
+    where
+        Self: Sized,
+    {
+        IntoFloat::new(self)
+    }
+
     /// Allow this sound to be controlled after it has started playing with a
     /// [`Controller`].
     ///
@@ -191,6 +289,56 @@ pub trait Sound: Send {
         AdjustableSpeed::new_with_speed(self, speed_adjustment)
     }
 
+    /// Allow the tempo of the sound to be adjustable with `set_tempo`.
+    ///
+    /// Unlike [Sound::with_adjustable_speed] this keeps the pitch.
+    fn with_adjustable_tempo(self) -> AdjustableTempo<Self>
+    where
+        Self: Sized,
+    {
+        AdjustableTempo::new(self)
+    }
+
+    /// Allow the tempo of the sound to be adjustable with `set_tempo` and set
+    /// the initial tempo.
+    ///
+    /// Unlike [Sound::with_adjustable_speed_of] this keeps the pitch.
+    fn with_adjustable_tempo_of(self, tempo: f32) -> AdjustableTempo<Self>
+    where
+        Self: Sized,
+    {
+        AdjustableTempo::new_with_tempo(self, tempo)
+    }
+
+    /// Allow the pitch of the sound to be adjustable in semitones with
+    /// `set_pitch` without changing its duration.
+    fn with_adjustable_pitch(self) -> PitchShift<Self>
+    where
+        Self: Sized,
+    {
+        PitchShift::new(self)
+    }
+
+    /// Allow the pitch of the sound to be adjustable in semitones with
+    /// `set_pitch` and set the initial pitch.
+    fn with_adjustable_pitch_of(self, semitones: f32) -> PitchShift<Self>
+    where
+        Self: Sized,
+    {
+        PitchShift::new_with_pitch(self, semitones)
+    }
+
+    /// Allow the sound to be placed between the left and right speakers with
+    /// `set_pan`. Starts centered. Mono sounds become stereo.
+    ///
+    /// See [Pannable].
+    fn pannable(self) -> Pannable<Self>
+    where
+        Self: Sized,
+    {
+        Pannable::new(self)
+    }
+
     /// Allow for the sound to be pausable with `set_paused`. Starts unpaused.
     fn pausable(self) -> Pausable<Self>
     where
@@ -218,6 +366,37 @@ pub trait Sound: Send {
         Stoppable::new(self)
     }
 
+    /// Allow the sound to be faded with `fade_to` and `fade_out_and_stop`.
+    /// Starts at full volume.
+    ///
+    /// See [Fade].
+    fn fadeable(self) -> Fade<Self>
+    where
+        Self: Sized,
+    {
+        Fade::new(self)
+    }
+
+    /// Fade the sound in from silence over `duration`. The returned sound can
+    /// also be faded with `fade_to` and `fade_out_and_stop`.
+    fn fade_in(self, duration: Duration) -> Fade<Self>
+    where
+        Self: Sized,
+    {
+        Fade::new_fade_in(self, duration)
+    }
+
+    /// Fade the sound to silence over its last `duration`. Requires the sound
+    /// to know its position and total duration.
+    fn fade_out_at_end(self, duration: Duration) -> Fade<Self>
+    where
+        Self: Sized,
+    {
+        let mut fade = Fade::new(self);
+        fade.set_fade_out_at_end(Some(duration));
+        fade
+    }
+
     /// Play the first `duration` of the sound, then finish even if samples
     /// remain.
     ///
@@ -273,12 +452,125 @@ pub trait Sound: Send {
     }
 }
 
+/// The number of samples converted at once by the default
+/// [Sound::fill_buffer_f32].
+const CONVERSION_CHUNK_LEN: usize = 512;
+
+/// The default implementation of [Sound::fill_buffer]. Available to
+/// implementations that only sometimes produce samples in bulk.
+pub(crate) fn fill_buffer_from_next_sample<S: Sound + ?Sized, T: SampleType>(
+    sound: &mut S,
+    buffer: &mut [T],
+) -> Result<(usize, Option<NextSample>), crate::Error> {
+    let channel_count = sound.channel_count() as usize;
+    debug_assert!(buffer.len().is_multiple_of(channel_count));
+    let mut num_frames = 0;
+    for frame in buffer.chunks_exact_mut(channel_count) {
+        for sample in frame {
+            match T::next_sample_of(sound)? {
+                NextSample::Sample(s) => *sample = s,
+                NextSample::MetadataChanged => {
+                    return Ok((num_frames, Some(NextSample::MetadataChanged)))
+                }
+                NextSample::Paused => return Ok((num_frames, Some(NextSample::Paused))),
+                NextSample::Finished => return Ok((num_frames, Some(NextSample::Finished))),
+            }
+        }
+        num_frames += 1;
+    }
+    Ok((num_frames, None))
+}
+
+/// The sample types a [Sound] can produce: i16 and f32. Allows code to be
+/// written once for both.
+pub(crate) trait SampleType: Copy + Default + Send + 'static {
+    fn to_f32(self) -> f32;
+
+    fn from_f32(value: f32) -> Self;
+
+    /// The mean of two samples.
+    fn average(a: Self, b: Self) -> Self;
+
+    /// The value `numerator / denominator` of the way from `first` to
+    /// `second`.
+    fn linear_interpolation(first: Self, second: Self, numerator: u32, denominator: u32) -> Self;
+
+    fn next_sample_of<S: Sound + ?Sized>(sound: &mut S) -> Result<NextSample<Self>, crate::Error>;
+
+    fn fill_buffer_of<S: Sound + ?Sized>(
+        sound: &mut S,
+        buffer: &mut [Self],
+    ) -> Result<(usize, Option<NextSample>), crate::Error>;
+}
+
+impl SampleType for i16 {
+    fn to_f32(self) -> f32 {
+        utils::i16_to_f32(self)
+    }
+
+    fn from_f32(value: f32) -> Self {
+        utils::f32_to_i16(value)
+    }
+
+    fn average(a: Self, b: Self) -> Self {
+        ((a as i32 + b as i32) / 2) as i16
+    }
+
+    fn linear_interpolation(first: Self, second: Self, numerator: u32, denominator: u32) -> Self {
+        (first as i64 + (second as i64 - first as i64) * numerator as i64 / denominator as i64)
+            as i16
+    }
+
+    fn next_sample_of<S: Sound + ?Sized>(sound: &mut S) -> Result<NextSample<Self>, crate::Error> {
+        sound.next_sample()
+    }
+
+    fn fill_buffer_of<S: Sound + ?Sized>(
+        sound: &mut S,
+        buffer: &mut [Self],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        sound.fill_buffer(buffer)
+    }
+}
+
+impl SampleType for f32 {
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+
+    fn average(a: Self, b: Self) -> Self {
+        (a + b) / 2.0
+    }
+
+    fn linear_interpolation(first: Self, second: Self, numerator: u32, denominator: u32) -> Self {
+        first + (second - first) * (numerator as f32 / denominator as f32)
+    }
+
+    fn next_sample_of<S: Sound + ?Sized>(sound: &mut S) -> Result<NextSample<Self>, crate::Error> {
+        sound.next_sample_f32()
+    }
+
+    fn fill_buffer_of<S: Sound + ?Sized>(
+        sound: &mut S,
+        buffer: &mut [Self],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        sound.fill_buffer_f32(buffer)
+    }
+}
+
 /// The result of [Sound::next_sample]
+///
+/// The sample type is i16 except for [Sound::next_sample_f32] and
+/// [FloatSound][crate::FloatSound].
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
-pub enum NextSample {
+pub enum NextSample<S = i16> {
     /// A sample for one channel. Channels are interleaved. The first sample is
     /// for the first channel and so forth and repeats (e.g. L-R-L-R-L-R).
-    Sample(i16),
+    Sample(S),
 
     /// The number of channels or the sample rate has changed. Continue to
     /// retrieve samples afterward. The next sample will always be for the
@@ -294,6 +586,19 @@ pub enum NextSample {
     Finished,
 }
 
+impl<S> NextSample<S> {
+    /// Convert the sample of a `Sample` variant with `f`. Other variants are
+    /// returned unchanged.
+    pub fn map<T>(self, f: impl FnOnce(S) -> T) -> NextSample<T> {
+        match self {
+            NextSample::Sample(s) => NextSample::Sample(f(s)),
+            NextSample::MetadataChanged => NextSample::MetadataChanged,
+            NextSample::Paused => NextSample::Paused,
+            NextSample::Finished => NextSample::Finished,
+        }
+    }
+}
+
 impl Sound for Box<dyn Sound> {
     fn on_start_of_batch(&mut self) {
         self.deref_mut().on_start_of_batch()
@@ -310,6 +615,32 @@ impl Sound for Box<dyn Sound> {
     fn next_sample(&mut self) -> Result<NextSample, crate::Error> {
         self.deref_mut().next_sample()
     }
+
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.deref_mut().fill_buffer(buffer)
+    }
+
+    fn next_sample_f32(&mut self) -> Result<NextSample<f32>, crate::Error> {
+        self.deref_mut().next_sample_f32()
+    }
+
+    fn fill_buffer_f32(
+        &mut self,
+        buffer: &mut [f32],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.deref_mut().fill_buffer_f32(buffer)
+    }
+
+    fn position(&self) -> Option<Duration> {
+        self.deref().position()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.deref().total_duration()
+    }
 }
 
 #[cfg(test)]
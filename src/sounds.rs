@@ -2,7 +2,9 @@
 pub mod decoders;
 pub mod wrappers;
 
+mod ducking;
 mod empty;
+mod master_stage;
 mod memory_sound;
 mod open_file;
 mod silence;
@@ -10,14 +12,21 @@ mod sine_wave;
 mod sound_list;
 mod sound_mixer;
 mod sounds_from_fn;
+mod voice_limit;
 
+pub use ducking::{DuckDetection, Ducking};
 pub use empty::Empty;
+pub use master_stage::{MasterEffect, MasterStage};
 pub use memory_sound::MemorySound;
 pub use memory_sound::UnsupportedMetadataChangeError;
 pub use open_file::open_file;
+pub use open_file::open_file_prefetched;
 pub use open_file::open_file_with_buffer_capacity;
+pub use open_file::open_reader;
 pub use silence::Silence;
 pub use sine_wave::SineWave;
 pub use sound_list::SoundList;
-pub use sound_mixer::SoundMixer;
+pub(crate) use sound_mixer::{PreparedSound, SharedFormat};
+pub use sound_mixer::{SoundMixer, StartTime};
 pub use sounds_from_fn::SoundsFromFn;
+pub use voice_limit::{VoiceLimit, VoiceStealing};
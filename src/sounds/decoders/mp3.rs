@@ -1,5 +1,7 @@
-use crate::Sound;
+use crate::sounds::wrappers::Seek;
+use crate::{utils, Sound};
 use std::io::Read;
+use std::time::Duration;
 
 // Enough for a single frame (maybe not for free format)
 // TODO we might want to make this configurable to allow for seeking.
@@ -87,6 +89,46 @@ where
         Ok(to_return)
     }
 
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(usize, Option<crate::NextSample>), crate::Error> {
+        if self.metadata_changed {
+            self.metadata_changed = false;
+            return Ok((0, Some(crate::NextSample::MetadataChanged)));
+        }
+        let channel_count = self.channel_count as usize;
+        let mut num_filled = 0;
+        while num_filled < buffer.len() {
+            if self.output_buffer_next_out_idx >= self.output_buffer_data_len {
+                match self.load_next_frame() {
+                    Ok(true) => (),
+                    Ok(false) => {
+                        return Ok((
+                            num_filled / channel_count,
+                            Some(crate::NextSample::Finished),
+                        ))
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+                if self.metadata_changed {
+                    self.metadata_changed = false;
+                    return Ok((
+                        num_filled / channel_count,
+                        Some(crate::NextSample::MetadataChanged),
+                    ));
+                }
+            }
+            let available =
+                &self.output_buffer[self.output_buffer_next_out_idx..self.output_buffer_data_len];
+            let num = available.len().min(buffer.len() - num_filled);
+            buffer[num_filled..num_filled + num].copy_from_slice(&available[..num]);
+            num_filled += num;
+            self.output_buffer_next_out_idx += num;
+        }
+        Ok((num_filled / channel_count, None))
+    }
+
     fn on_start_of_batch(&mut self) {}
 }
 
@@ -149,6 +191,65 @@ where
     }
 }
 
+/// MP3 files do not contain an index so seeking reads from the start of the
+/// data and skips over frames (without decoding them) until the requested
+/// position is reached.
+impl<R> Seek for Mp3Decoder<R>
+where
+    R: Read + std::io::Seek + Send,
+{
+    fn seek(&mut self, position: Duration) -> Result<Duration, crate::Error> {
+        self.reader.rewind()?;
+        *self.raw_decoder = rmp3::RawDecoder::new();
+        self.input_buffer_data_len = 0;
+        self.output_buffer_data_len = 0;
+        self.output_buffer_next_out_idx = 0;
+
+        let target_frame = utils::duration_to_num_samples(position, 1, self.sample_rate);
+        let mut frame_num = 0;
+        loop {
+            self.fill_input_buffer()?;
+            let peeked = self
+                .raw_decoder
+                .peek(&self.input_buffer[0..self.input_buffer_data_len]);
+            let Some((frame, input_bytes_to_skip)) = peeked else {
+                // Seeked past the end. The next call to next_sample will find
+                // no more frames and return Finished.
+                break;
+            };
+            let sample_count = match frame {
+                rmp3::Frame::Audio(audio) => audio.sample_count() as u64,
+                rmp3::Frame::Other(_) => 0,
+            };
+            // Decode the frame before the target so the bit reservoir of the
+            // target frame is populated.
+            if frame_num + 2 * sample_count > target_frame {
+                if !self.load_next_frame()? {
+                    break;
+                }
+                if frame_num + sample_count > target_frame {
+                    self.output_buffer_next_out_idx =
+                        (target_frame - frame_num) as usize * self.channel_count as usize;
+                    frame_num = target_frame;
+                    break;
+                }
+                self.output_buffer_data_len = 0;
+                self.output_buffer_next_out_idx = 0;
+            } else {
+                self.input_buffer
+                    .copy_within(input_bytes_to_skip..self.input_buffer_data_len, 0);
+                self.input_buffer_data_len -= input_bytes_to_skip;
+            }
+            frame_num += sample_count;
+        }
+        Ok(utils::num_samples_to_duration(
+            frame_num,
+            1,
+            self.sample_rate,
+        ))
+    }
+}
+
 #[cfg(test)]
 #[path = "./tests/mp3.rs"]
 mod tests;
@@ -1,16 +1,29 @@
 use crate::sound::NextSample;
-use crate::Sound;
-use qoaudio::{DecodeError, QoaDecoder as RawDecoder, QoaItem};
+use crate::sounds::wrappers::Seek;
+use crate::{utils, Sound};
+use qoaudio::{DecodeError, ProcessingMode, QoaDecoder as RawDecoder, QoaItem};
 use std::io::Read;
+use std::time::Duration;
+
+/// The number of samples per channel in every frame except the last.
+const SAMPLES_PER_CHANNEL_PER_FRAME: u64 =
+    (qoaudio::QOA_SLICE_LEN * qoaudio::MAX_SLICES_PER_CHANNEL_PER_FRAME) as u64;
 
 /// Decoder for the [QOA](https://qoaformat.org/) format.
 pub struct QoaDecoder<R>
 where
     R: Read + Send,
 {
-    raw_decoder: RawDecoder<R>,
+    // Only None if re-creating the raw decoder failed while seeking.
+    raw_decoder: Option<RawDecoder<PrefixedReader<R>>>,
     sample_rate: u32,
     channel_count: u16,
+    metadata_changed: bool,
+    /// Time played before the last change of sample rate.
+    position_offset: Duration,
+    /// Frames played since `position_offset`.
+    num_frames_read: u64,
+    next_channel_idx: u16,
 }
 
 impl<R> QoaDecoder<R>
@@ -19,7 +32,24 @@ where
 {
     /// Attempts to decode the data as QOA audio.
     pub fn new(data: R) -> Result<QoaDecoder<R>, DecodeError> {
-        let mut raw_decoder = RawDecoder::new(data)?;
+        let (raw_decoder, sample_rate, channel_count) =
+            Self::new_raw_decoder(PrefixedReader::new(data))?;
+
+        Ok(QoaDecoder {
+            raw_decoder: Some(raw_decoder),
+            sample_rate,
+            channel_count,
+            metadata_changed: false,
+            position_offset: Duration::ZERO,
+            num_frames_read: 0,
+            next_channel_idx: 0,
+        })
+    }
+
+    fn new_raw_decoder(
+        reader: PrefixedReader<R>,
+    ) -> Result<(RawDecoder<PrefixedReader<R>>, u32, u16), DecodeError> {
+        let mut raw_decoder = RawDecoder::new(reader)?;
 
         let QoaItem::FrameHeader(first_frame) = raw_decoder
             .next()
@@ -29,17 +59,34 @@ where
         };
         let sample_rate = first_frame.sample_rate;
         let channel_count = first_frame.num_channels as u16;
+        Ok((raw_decoder, sample_rate, channel_count))
+    }
 
-        Ok(QoaDecoder {
-            raw_decoder,
-            sample_rate,
-            channel_count,
-        })
+    /// Record a new channel count and sample rate from a frame header. Returns
+    /// true if either changed.
+    fn update_format(&mut self, channel_count: u16, sample_rate: u32) -> bool {
+        if channel_count == self.channel_count && sample_rate == self.sample_rate {
+            return false;
+        }
+        self.position_offset +=
+            utils::num_samples_to_duration(self.num_frames_read, 1, self.sample_rate);
+        self.num_frames_read = 0;
+        self.next_channel_idx = 0;
+        self.channel_count = channel_count;
+        self.sample_rate = sample_rate;
+        true
     }
 
     /// Return the wrapped Reader
+    ///
+    /// # Panics
+    ///
+    /// Panics if a previous call to seek returned an error.
     pub fn into_inner(self) -> R {
-        self.raw_decoder.into_inner()
+        self.raw_decoder
+            .expect("reader lost by a failed seek")
+            .into_inner()
+            .inner
     }
 }
 
@@ -56,30 +103,222 @@ where
     }
 
     fn next_sample(&mut self) -> Result<NextSample, crate::Error> {
+        if self.metadata_changed {
+            self.metadata_changed = false;
+            return Ok(NextSample::MetadataChanged);
+        }
         loop {
-            let Some(next_sample) = self.raw_decoder.next() else {
+            let Some(raw_decoder) = &mut self.raw_decoder else {
+                return Ok(NextSample::Finished);
+            };
+            let Some(next_sample) = raw_decoder.next() else {
                 return Ok(NextSample::Finished);
             };
             let next_sample = next_sample?;
 
             match next_sample {
-                QoaItem::Sample(s) => return Ok(NextSample::Sample(s)),
+                QoaItem::Sample(s) => {
+                    self.next_channel_idx += 1;
+                    if self.next_channel_idx >= self.channel_count {
+                        self.next_channel_idx = 0;
+                        self.num_frames_read += 1;
+                    }
+                    return Ok(NextSample::Sample(s));
+                }
                 QoaItem::FrameHeader(f) => {
+                    if self.update_format(f.num_channels.into(), f.sample_rate) {
+                        return Ok(NextSample::MetadataChanged);
+                    }
+                    // No metadata change. Continue and read next sample
+                    continue;
+                }
+            }
+        }
+    }
+
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        if self.metadata_changed {
+            self.metadata_changed = false;
+            return Ok((0, Some(NextSample::MetadataChanged)));
+        }
+        let Some(raw_decoder) = &mut self.raw_decoder else {
+            return Ok((0, Some(NextSample::Finished)));
+        };
+        let mut num_filled = 0;
+        let mut new_format = None;
+        let mut next = None;
+        while num_filled < buffer.len() {
+            match raw_decoder.next().transpose()? {
+                Some(QoaItem::Sample(s)) => {
+                    buffer[num_filled] = s;
+                    num_filled += 1;
+                }
+                Some(QoaItem::FrameHeader(f)) => {
+                    if f.num_channels as u16 != self.channel_count
+                        || f.sample_rate != self.sample_rate
+                    {
+                        new_format = Some((f.num_channels.into(), f.sample_rate));
+                        next = Some(NextSample::MetadataChanged);
+                        break;
+                    }
+                }
+                None => {
+                    next = Some(NextSample::Finished);
+                    break;
+                }
+            }
+        }
+        let num_frames = num_filled / self.channel_count as usize;
+        self.num_frames_read += num_frames as u64;
+        if let Some((channel_count, sample_rate)) = new_format {
+            self.update_format(channel_count, sample_rate);
+        }
+        Ok((num_frames, next))
+    }
+
+    fn on_start_of_batch(&mut self) {}
+
+    fn position(&self) -> Option<Duration> {
+        Some(
+            self.position_offset
+                + utils::num_samples_to_duration(self.num_frames_read, 1, self.sample_rate),
+        )
+    }
+
+    /// Only known if the file has a fixed number of samples.
+    fn total_duration(&self) -> Option<Duration> {
+        match self.raw_decoder.as_ref()?.mode() {
+            ProcessingMode::FixedSamples {
+                sample_rate,
+                samples,
+                ..
+            } => Some(utils::num_samples_to_duration(
+                *samples as u64,
+                1,
+                *sample_rate,
+            )),
+            ProcessingMode::Streaming => None,
+        }
+    }
+}
+
+/// If the file has a fixed number of samples the reader is moved directly to
+/// the frame containing `position`, otherwise the file is decoded from the
+/// start until `position` is reached.
+///
+/// If an error is returned the decoder may be left finished.
+impl<R> Seek for QoaDecoder<R>
+where
+    R: Read + std::io::Seek + Send,
+{
+    fn seek(&mut self, position: Duration) -> Result<Duration, crate::Error> {
+        let Some(raw_decoder) = self.raw_decoder.take() else {
+            return Ok(Duration::ZERO);
+        };
+        let mode = raw_decoder.mode().clone();
+        let mut reader = raw_decoder.into_inner();
+
+        let mut target_frame = utils::duration_to_num_samples(position, 1, self.sample_rate);
+        let mut frame_num;
+        match mode {
+            ProcessingMode::FixedSamples {
+                channels, samples, ..
+            } => {
+                target_frame = target_frame.min(samples as u64);
+                let qoa_frame_idx = (target_frame / SAMPLES_PER_CHANNEL_PER_FRAME)
+                    // The last frame is needed to seek to the very end
+                    .min(samples.saturating_sub(1) as u64 / SAMPLES_PER_CHANNEL_PER_FRAME);
+                let qoa_frame_size = qoaudio::QOA_HEADER_SIZE as u64
+                    + channels as u64 * (16 + 8 * qoaudio::MAX_SLICES_PER_CHANNEL_PER_FRAME as u64);
+                reader.inner.seek(std::io::SeekFrom::Start(
+                    qoaudio::QOA_HEADER_SIZE as u64 + qoa_frame_idx * qoa_frame_size,
+                ))?;
+                // The raw decoder expects to read the file header first.
+                let mut header = [0; qoaudio::QOA_HEADER_SIZE];
+                header[0..4].copy_from_slice(&qoaudio::QOA_MAGIC.to_be_bytes());
+                header[4..8].copy_from_slice(&samples.to_be_bytes());
+                reader.prefix = header;
+                reader.prefix_idx = 0;
+                frame_num = qoa_frame_idx * SAMPLES_PER_CHANNEL_PER_FRAME;
+            }
+            ProcessingMode::Streaming => {
+                reader.inner.rewind()?;
+                reader.prefix_idx = reader.prefix.len();
+                frame_num = 0;
+            }
+        }
+
+        let (raw_decoder, sample_rate, channel_count) = Self::new_raw_decoder(reader)?;
+        let raw_decoder = self.raw_decoder.insert(raw_decoder);
+        self.metadata_changed |=
+            sample_rate != self.sample_rate || channel_count != self.channel_count;
+        self.sample_rate = sample_rate;
+        self.channel_count = channel_count;
+
+        let mut channel_idx = 0;
+        while frame_num < target_frame {
+            match raw_decoder.next().transpose()? {
+                Some(QoaItem::Sample(_)) => {
+                    channel_idx += 1;
+                    if channel_idx == self.channel_count {
+                        channel_idx = 0;
+                        frame_num += 1;
+                    }
+                }
+                Some(QoaItem::FrameHeader(f)) => {
                     if f.num_channels as u16 != self.channel_count
                         || f.sample_rate != self.sample_rate
                     {
                         self.channel_count = f.num_channels.into();
                         self.sample_rate = f.sample_rate;
-                        return Ok(NextSample::MetadataChanged);
+                        self.metadata_changed = true;
                     }
-                    // No metadata change. Continue and read next sample
-                    continue;
                 }
+                None => break,
             }
         }
+        let position = utils::num_samples_to_duration(frame_num, 1, sample_rate);
+        self.position_offset = position;
+        self.num_frames_read = 0;
+        self.next_channel_idx = 0;
+        Ok(position)
+    }
+}
+
+/// A reader that returns `prefix` before reading from `inner`.
+///
+/// Used to give the raw decoder a file header after seeking `inner` to a frame
+/// in the middle of the file.
+struct PrefixedReader<R> {
+    prefix: [u8; qoaudio::QOA_HEADER_SIZE],
+    prefix_idx: usize,
+    inner: R,
+}
+
+impl<R> PrefixedReader<R> {
+    fn new(inner: R) -> Self {
+        PrefixedReader {
+            prefix: [0; qoaudio::QOA_HEADER_SIZE],
+            prefix_idx: qoaudio::QOA_HEADER_SIZE,
+            inner,
+        }
     }
+}
 
-    fn on_start_of_batch(&mut self) {}
+impl<R: Read> Read for PrefixedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.prefix_idx < self.prefix.len() {
+            let remaining = &self.prefix[self.prefix_idx..];
+            let num = remaining.len().min(buf.len());
+            buf[..num].copy_from_slice(&remaining[..num]);
+            self.prefix_idx += num;
+            return Ok(num);
+        }
+        self.inner.read(buf)
+    }
 }
 
 impl From<DecodeError> for crate::Error {
@@ -1,14 +1,18 @@
+use std::time::Duration;
+
+use crate::sounds::wrappers::Seek;
+use crate::utils;
 use crate::NextSample;
 use crate::Sound;
 use symphonia::core::audio::{AudioBuffer, AudioBufferRef, Channels, Signal};
 use symphonia::core::codecs::{Decoder, DecoderOptions, CODEC_TYPE_NULL};
 use symphonia::core::conv::FromSample;
 use symphonia::core::errors::Error;
-use symphonia::core::formats::{FormatOptions, FormatReader};
+use symphonia::core::formats::{FormatOptions, FormatReader, SeekMode, SeekTo};
 use symphonia::core::io::{MediaSource, MediaSourceStream};
 use symphonia::core::meta::{Limit, MetadataOptions};
 use symphonia::core::probe::Hint;
-use symphonia::core::sample::Sample;
+use symphonia::core::sample::{i24, u24, Sample};
 
 /// Decode formats using the Symphonia crate decoders.
 pub struct SymphoniaDecoder {
@@ -21,6 +25,9 @@ pub struct SymphoniaDecoder {
     track_id: u32,
     next_channel_idx: u16,
     next_sample_idx: usize,
+    /// The timestamp of the first frame of the last decoded packet.
+    packet_ts: u64,
+    metadata_changed: bool,
 }
 
 impl SymphoniaDecoder {
@@ -67,6 +74,8 @@ impl SymphoniaDecoder {
             track_id,
             next_channel_idx: 0,
             next_sample_idx: 0,
+            packet_ts: 0,
+            metadata_changed: false,
         };
         // Ignore metadata changed since no one has seen the old values
         let _ = decoder.decode_next_packet();
@@ -84,6 +93,61 @@ impl Sound for SymphoniaDecoder {
     }
 
     fn next_sample(&mut self) -> Result<NextSample, crate::Error> {
+        self.next_sample_of()
+    }
+
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.fill_buffer_of(buffer)
+    }
+
+    fn next_sample_f32(&mut self) -> Result<NextSample<f32>, crate::Error> {
+        self.next_sample_of()
+    }
+
+    fn fill_buffer_f32(
+        &mut self,
+        buffer: &mut [f32],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.fill_buffer_of(buffer)
+    }
+
+    fn on_start_of_batch(&mut self) {}
+
+    fn position(&self) -> Option<Duration> {
+        let mut num_frames = self.packet_ts + self.next_sample_idx as u64;
+        if self.next_channel_idx > 0 && self.next_channel_idx as usize >= self.channels.count() {
+            // The last frame was fully returned but next_sample_idx is only
+            // incremented on the following call.
+            num_frames += 1;
+        }
+        Some(utils::num_samples_to_duration(
+            num_frames,
+            1,
+            self.sample_rate,
+        ))
+    }
+
+    /// Known if the container reports the number of frames of the track.
+    fn total_duration(&self) -> Option<Duration> {
+        let num_frames = self.decoder.codec_params().n_frames?;
+        Some(utils::num_samples_to_duration(
+            num_frames,
+            1,
+            self.sample_rate,
+        ))
+    }
+}
+
+impl SymphoniaDecoder {
+    /// Samples are converted directly from the decoded format to `T`.
+    fn next_sample_of<T: OutputSample>(&mut self) -> Result<NextSample<T>, crate::Error> {
+        if self.metadata_changed {
+            self.metadata_changed = false;
+            return Ok(NextSample::MetadataChanged);
+        }
         if self.next_channel_idx >= self.channels.count().try_into().unwrap() {
             self.next_channel_idx = 0;
             self.next_sample_idx += 1;
@@ -110,10 +174,48 @@ impl Sound for SymphoniaDecoder {
         Ok(NextSample::Sample(sample))
     }
 
-    fn on_start_of_batch(&mut self) {}
-}
+    fn fill_buffer_of<T: OutputSample>(
+        &mut self,
+        buffer: &mut [T],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        if self.metadata_changed {
+            self.metadata_changed = false;
+            return Ok((0, Some(NextSample::MetadataChanged)));
+        }
+        let channel_count = self.channels.count();
+        if self.next_channel_idx as usize >= channel_count {
+            self.next_channel_idx = 0;
+            self.next_sample_idx += 1;
+        }
+        let total_frames = buffer.len() / channel_count;
+        let mut num_frames = 0;
+        while num_frames < total_frames {
+            if self.next_sample_idx >= self.decoder.last_decoded().frames() {
+                match self.decode_next_packet() {
+                    Ok(true) => return Ok((num_frames, Some(NextSample::MetadataChanged))),
+                    Ok(false) => (),
+                    Err(Error::IoError(err))
+                        if err.kind() == std::io::ErrorKind::UnexpectedEof
+                            && err.to_string() == "end of stream" =>
+                    {
+                        return Ok((num_frames, Some(NextSample::Finished)));
+                    }
+                    Err(e) => return Err(e.into()),
+                };
+            }
+            let buf_ref = self.decoder.last_decoded();
+            let num = (buf_ref.frames() - self.next_sample_idx).min(total_frames - num_frames);
+            copy_interleaved_from_ref(
+                &buf_ref,
+                self.next_sample_idx,
+                &mut buffer[num_frames * channel_count..(num_frames + num) * channel_count],
+            );
+            self.next_sample_idx += num;
+            num_frames += num;
+        }
+        Ok((num_frames, None))
+    }
 
-impl SymphoniaDecoder {
     fn decode_next_packet(&mut self) -> Result<bool, Error> {
         loop {
             let packet = self.format.next_packet()?;
@@ -142,6 +244,7 @@ impl SymphoniaDecoder {
 
             self.next_channel_idx = 0;
             self.next_sample_idx = 0;
+            self.packet_ts = packet.ts();
             let mut metadata_changed = false;
             if buf_ref.spec().channels != self.channels {
                 self.channels = buf_ref.spec().channels;
@@ -154,13 +257,89 @@ impl SymphoniaDecoder {
             return Ok(metadata_changed);
         }
     }
+
+    /// Position the decoder so the next sample returned is `Finished`.
+    fn seek_to_end(&mut self) -> Result<Duration, crate::Error> {
+        self.next_sample_idx = self.decoder.last_decoded().frames();
+        self.next_channel_idx = 0;
+        let end = self
+            .decoder
+            .codec_params()
+            .n_frames
+            .unwrap_or(self.packet_ts + self.next_sample_idx as u64);
+        Ok(utils::num_samples_to_duration(end, 1, self.sample_rate))
+    }
+}
+
+// Timestamps are assumed to be in units of frames, which is the case for the
+// audio formats supported by Symphonia.
+impl Seek for SymphoniaDecoder {
+    fn seek(&mut self, position: Duration) -> Result<Duration, crate::Error> {
+        let seeked_to = match self.format.seek(
+            SeekMode::Accurate,
+            SeekTo::TimeStamp {
+                ts: utils::duration_to_num_samples(position, 1, self.sample_rate),
+                track_id: self.track_id,
+            },
+        ) {
+            Ok(seeked_to) => seeked_to,
+            Err(Error::SeekError(symphonia::core::errors::SeekErrorKind::OutOfRange)) => {
+                return self.seek_to_end();
+            }
+            Err(Error::IoError(err)) if err.kind() == std::io::ErrorKind::UnexpectedEof => {
+                return self.seek_to_end();
+            }
+            Err(e) => return Err(e.into()),
+        };
+        self.decoder.reset();
+
+        // Decode until we reach the packet containing the requested frame.
+        loop {
+            match self.decode_next_packet() {
+                Ok(metadata_changed) => self.metadata_changed |= metadata_changed,
+                Err(Error::IoError(err)) if err.kind() == std::io::ErrorKind::UnexpectedEof => {
+                    return self.seek_to_end();
+                }
+                Err(e) => return Err(e.into()),
+            }
+            let num_frames = self.decoder.last_decoded().frames() as u64;
+            if self.packet_ts + num_frames > seeked_to.required_ts {
+                self.next_sample_idx =
+                    seeked_to.required_ts.saturating_sub(self.packet_ts) as usize;
+                break;
+            }
+        }
+        Ok(utils::num_samples_to_duration(
+            self.packet_ts + self.next_sample_idx as u64,
+            1,
+            self.sample_rate,
+        ))
+    }
 }
 
-pub fn extract_sample_from_ref(
+/// A sample type that Symphonia can convert all of its sample formats to.
+trait OutputSample:
+    FromSample<u8>
+    + FromSample<u16>
+    + FromSample<u24>
+    + FromSample<u32>
+    + FromSample<i8>
+    + FromSample<i16>
+    + FromSample<i24>
+    + FromSample<i32>
+    + FromSample<f32>
+    + FromSample<f64>
+{
+}
+
+impl OutputSample for i16 {}
+impl OutputSample for f32 {}
+
+fn extract_sample_from_ref<T: OutputSample>(
     buffer: &AudioBufferRef,
     channel_idx: u16,
     sample_idx: usize,
-) -> i16 {
+) -> T {
     match buffer {
         AudioBufferRef::U8(buffer) => extract_sample(buffer, channel_idx, sample_idx),
         AudioBufferRef::U16(buffer) => extract_sample(buffer, channel_idx, sample_idx),
@@ -175,17 +354,53 @@ pub fn extract_sample_from_ref(
     }
 }
 
-pub fn extract_sample<S: Sample>(
+fn extract_sample<S: Sample, T: FromSample<S>>(
     buffer: &AudioBuffer<S>,
     channel_idx: u16,
     sample_idx: usize,
-) -> i16
-where
-    i16: FromSample<S>,
-{
+) -> T {
     FromSample::from_sample(buffer.chan(channel_idx as usize)[sample_idx])
 }
 
+/// Copy samples starting at `first_frame` into `out` interleaved by channel
+/// until `out` is full.
+fn copy_interleaved_from_ref<T: OutputSample>(
+    buffer: &AudioBufferRef,
+    first_frame: usize,
+    out: &mut [T],
+) {
+    match buffer {
+        AudioBufferRef::U8(buffer) => copy_interleaved(buffer, first_frame, out),
+        AudioBufferRef::U16(buffer) => copy_interleaved(buffer, first_frame, out),
+        AudioBufferRef::U24(buffer) => copy_interleaved(buffer, first_frame, out),
+        AudioBufferRef::U32(buffer) => copy_interleaved(buffer, first_frame, out),
+        AudioBufferRef::S8(buffer) => copy_interleaved(buffer, first_frame, out),
+        AudioBufferRef::S16(buffer) => copy_interleaved(buffer, first_frame, out),
+        AudioBufferRef::S24(buffer) => copy_interleaved(buffer, first_frame, out),
+        AudioBufferRef::S32(buffer) => copy_interleaved(buffer, first_frame, out),
+        AudioBufferRef::F32(buffer) => copy_interleaved(buffer, first_frame, out),
+        AudioBufferRef::F64(buffer) => copy_interleaved(buffer, first_frame, out),
+    }
+}
+
+fn copy_interleaved<S: Sample, T: FromSample<S>>(
+    buffer: &AudioBuffer<S>,
+    first_frame: usize,
+    out: &mut [T],
+) {
+    let channel_count = buffer.spec().channels.count();
+    for channel_idx in 0..channel_count {
+        let channel = &buffer.chan(channel_idx)[first_frame..];
+        for (out, sample) in out[channel_idx..]
+            .iter_mut()
+            .step_by(channel_count)
+            .zip(channel)
+        {
+            *out = FromSample::from_sample(*sample);
+        }
+    }
+}
+
 impl From<Error> for crate::Error {
     fn from(value: Error) -> Self {
         match value {
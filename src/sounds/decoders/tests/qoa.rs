@@ -34,3 +34,59 @@ fn samples_of_test_file() -> std::io::Result<()> {
     assert_eq!(decoder.next_sample().unwrap(), NextSample::Finished);
     Ok(())
 }
+
+#[test]
+fn seek() {
+    let mut decoder = QoaDecoder::new(std::io::Cursor::new(SINE_WAVE_FILE)).unwrap();
+    let position = crate::utils::num_samples_to_duration(10, 1, 44100);
+    assert_eq!(decoder.seek(position).unwrap(), position);
+    assert_eq!(decoder.next_sample().unwrap(), NextSample::Sample(31976)); // 11
+    assert_eq!(decoder.next_sample().unwrap(), NextSample::Sample(32767)); // 12
+    assert_eq!(decoder.seek(Duration::ZERO).unwrap(), Duration::ZERO);
+    assert_eq!(decoder.next_sample().unwrap(), NextSample::Sample(422)); // 1
+    assert_eq!(decoder.next_sample().unwrap(), NextSample::Sample(4779)); // 2
+    decoder.seek(Duration::from_secs(10)).unwrap();
+    assert_eq!(decoder.next_sample().unwrap(), NextSample::Finished);
+}
+
+#[test]
+fn position_and_total_duration() {
+    let mut decoder = QoaDecoder::new(std::io::Cursor::new(SINE_WAVE_FILE)).unwrap();
+    assert_eq!(
+        decoder.total_duration(),
+        Some(crate::utils::num_samples_to_duration(4411, 1, 44100))
+    );
+    assert_eq!(decoder.position(), Some(Duration::ZERO));
+    for _ in 0..441 {
+        decoder.next_sample().unwrap();
+    }
+    assert_eq!(decoder.position(), Some(Duration::from_millis(10)));
+    decoder.seek(Duration::from_millis(50)).unwrap();
+    assert_eq!(decoder.position(), Some(Duration::from_millis(50)));
+}
+
+#[test]
+fn fill_buffer_matches_next_sample() {
+    let mut expected = Vec::new();
+    let mut decoder = new_decoder();
+    while let NextSample::Sample(s) = decoder.next_sample().unwrap() {
+        expected.push(s);
+    }
+    let mut decoder = new_decoder();
+    let mut samples = Vec::new();
+    let mut buffer = [0; 1000];
+    loop {
+        let (num_frames, next) = decoder.fill_buffer(&mut buffer).unwrap();
+        samples.extend_from_slice(&buffer[..num_frames]);
+        match next {
+            None => (),
+            Some(NextSample::Finished) => break,
+            Some(next) => panic!("unexpected {next:?}"),
+        }
+    }
+    assert_eq!(samples, expected);
+}
+
+fn new_decoder() -> QoaDecoder<std::io::Cursor<&'static [u8]>> {
+    QoaDecoder::new(std::io::Cursor::new(SINE_WAVE_FILE)).unwrap()
+}
@@ -48,3 +48,88 @@ fn samples_of_test_file() -> std::io::Result<()> {
     assert_eq!(decoder.next_sample().unwrap(), NextSample::Finished);
     Ok(())
 }
+
+#[test]
+fn seek() {
+    let mut decoder =
+        SymphoniaDecoder::new(Box::new(std::io::Cursor::new(SINE_WAVE_FILE)), None).unwrap();
+    let mut expected = Vec::new();
+    for _ in 0..1200 {
+        expected.push(decoder.next_sample().unwrap());
+    }
+    let position = crate::utils::num_samples_to_duration(1106, 1, 44100);
+    assert_eq!(decoder.seek(position).unwrap(), position);
+    assert_eq!(decoder.next_sample().unwrap(), expected[1106]);
+    assert_eq!(decoder.next_sample().unwrap(), expected[1107]);
+    assert_eq!(decoder.seek(Duration::ZERO).unwrap(), Duration::ZERO);
+    for expected in &expected[..20] {
+        assert_eq!(&decoder.next_sample().unwrap(), expected);
+    }
+    decoder.seek(Duration::from_secs(10)).unwrap();
+    assert_eq!(decoder.next_sample().unwrap(), NextSample::Finished);
+}
+
+#[test]
+fn position_and_total_duration() {
+    let mut decoder =
+        SymphoniaDecoder::new(Box::new(std::io::Cursor::new(SINE_WAVE_FILE)), None).unwrap();
+    assert_eq!(
+        decoder.total_duration(),
+        Some(crate::utils::num_samples_to_duration(5760, 1, 44100))
+    );
+    assert_eq!(decoder.position(), Some(Duration::ZERO));
+    for _ in 0..441 {
+        decoder.next_sample().unwrap();
+    }
+    assert_eq!(decoder.position(), Some(Duration::from_millis(10)));
+    decoder.seek(Duration::from_millis(50)).unwrap();
+    assert_eq!(decoder.position(), Some(Duration::from_millis(50)));
+}
+
+#[test]
+fn fill_buffer_matches_next_sample() {
+    let mut expected = Vec::new();
+    let mut decoder = new_decoder();
+    while let NextSample::Sample(s) = decoder.next_sample().unwrap() {
+        expected.push(s);
+    }
+    let mut decoder = new_decoder();
+    let mut samples = Vec::new();
+    let mut buffer = [0; 1000];
+    loop {
+        let (num_frames, next) = decoder.fill_buffer(&mut buffer).unwrap();
+        samples.extend_from_slice(&buffer[..num_frames]);
+        match next {
+            None => (),
+            Some(NextSample::Finished) => break,
+            Some(next) => panic!("unexpected {next:?}"),
+        }
+    }
+    assert_eq!(samples, expected);
+}
+
+fn new_decoder() -> SymphoniaDecoder {
+    SymphoniaDecoder::new(Box::new(std::io::Cursor::new(SINE_WAVE_FILE)), None).unwrap()
+}
+
+#[test]
+fn f32_samples_match_i16_samples() {
+    let mut decoder = new_decoder();
+    let mut float_decoder = new_decoder();
+    let mut buffer = [0.0; 1000];
+    loop {
+        let (num_frames, next) = float_decoder.fill_buffer_f32(&mut buffer).unwrap();
+        for float_sample in &buffer[..num_frames] {
+            let NextSample::Sample(s) = decoder.next_sample().unwrap() else {
+                panic!("expected a sample");
+            };
+            assert!((float_sample - s as f32 / 32768.0).abs() < 1.0 / 32768.0);
+        }
+        match next {
+            None => (),
+            Some(NextSample::Finished) => break,
+            Some(next) => panic!("unexpected {next:?}"),
+        }
+    }
+    assert_eq!(decoder.next_sample().unwrap(), NextSample::Finished);
+}
@@ -1,7 +1,9 @@
 use std::io::Read;
+use std::time::Duration;
 
 use crate::sound::NextSample;
-use crate::Sound;
+use crate::sounds::wrappers::Seek;
+use crate::{utils, Sound};
 
 use hound::{SampleFormat, WavReader};
 
@@ -15,6 +17,7 @@ where
     reader: WavReader<R>,
     sample_rate: u32,
     channel_count: u16,
+    num_samples_read: u64,
 }
 
 impl<R> WavDecoder<R>
@@ -33,6 +36,7 @@ where
             reader,
             sample_rate,
             channel_count,
+            num_samples_read: 0,
         })
     }
 
@@ -55,60 +59,175 @@ where
     }
 
     fn next_sample(&mut self) -> Result<NextSample, crate::Error> {
+        self.next_sample_of()
+    }
+
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.fill_buffer_of(buffer)
+    }
+
+    fn next_sample_f32(&mut self) -> Result<NextSample<f32>, crate::Error> {
+        self.next_sample_of()
+    }
+
+    fn fill_buffer_f32(
+        &mut self,
+        buffer: &mut [f32],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.fill_buffer_of(buffer)
+    }
+
+    fn on_start_of_batch(&mut self) {}
+
+    fn position(&self) -> Option<Duration> {
+        Some(utils::num_samples_to_duration(
+            self.num_samples_read,
+            self.channel_count,
+            self.sample_rate,
+        ))
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(utils::num_samples_to_duration(
+            self.reader.duration() as u64,
+            1,
+            self.sample_rate,
+        ))
+    }
+}
+
+impl<R> WavDecoder<R>
+where
+    R: Read + Send,
+{
+    fn next_sample_of<O: OutputSample>(&mut self) -> Result<NextSample<O>, crate::Error> {
+        let mut sample = [O::default()];
+        let (num_filled, result) = self.fill_samples(&mut sample);
+        self.num_samples_read += num_filled as u64;
+        match (num_filled, result?) {
+            (1, _) => Ok(NextSample::Sample(sample[0])),
+            _ => Ok(NextSample::Finished),
+        }
+    }
+
+    fn fill_buffer_of<O: OutputSample>(
+        &mut self,
+        buffer: &mut [O],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        let (num_filled, result) = self.fill_samples(buffer);
+        self.num_samples_read += num_filled as u64;
+        let finished = result?;
+        let num_frames = num_filled / self.channel_count as usize;
+        Ok((num_frames, finished.then_some(NextSample::Finished)))
+    }
+
+    /// Returns the number of samples written and whether the samples ran out.
+    fn fill_samples<O: OutputSample>(&mut self, buffer: &mut [O]) -> (usize, hound::Result<bool>) {
         let spec = self.reader.spec();
-        let maybe_sample = match (spec.sample_format, spec.bits_per_sample) {
-            (SampleFormat::Float, 32) => self
-                .reader
-                .samples()
-                .next()
-                .map(|value| value.map(f32_to_i16)),
-            (SampleFormat::Int, 8) => self
-                .reader
-                .samples()
-                .next()
-                .map(|value| value.map(i8_to_i16)),
-            (SampleFormat::Int, 16) => self.reader.samples().next(),
-            (SampleFormat::Int, 24) => self
-                .reader
-                .samples()
-                .next()
-                .map(|value| value.map(i24_to_i16)),
-            (SampleFormat::Int, 32) => self
-                .reader
-                .samples()
-                .next()
-                .map(|value| value.map(i32_to_i16)),
+        match (spec.sample_format, spec.bits_per_sample) {
+            (SampleFormat::Float, 32) => fill_from(self.reader.samples(), buffer, O::from_f32),
+            (SampleFormat::Int, 8) => fill_from(self.reader.samples(), buffer, O::from_i8),
+            (SampleFormat::Int, 16) => fill_from(self.reader.samples(), buffer, O::from_i16),
+            (SampleFormat::Int, 24) => fill_from(self.reader.samples(), buffer, O::from_i24),
+            (SampleFormat::Int, 32) => fill_from(self.reader.samples(), buffer, O::from_i32),
             (sample_format, bits_per_sample) => {
                 unimplemented!("wav spec: {:?}, {}", sample_format, bits_per_sample)
             }
-        };
-        match maybe_sample {
-            Some(Ok(sample)) => Ok(NextSample::Sample(sample)),
-            Some(Err(e)) => Err(e.into()),
-            None => Ok(NextSample::Finished),
         }
     }
+}
 
-    fn on_start_of_batch(&mut self) {}
+impl<R> Seek for WavDecoder<R>
+where
+    R: Read + std::io::Seek + Send,
+{
+    fn seek(&mut self, position: Duration) -> Result<Duration, crate::Error> {
+        let frame = utils::duration_to_num_samples(position, 1, self.sample_rate)
+            .min(self.reader.duration() as u64) as u32;
+        self.reader.seek(frame)?;
+        self.num_samples_read = frame as u64 * self.channel_count as u64;
+        Ok(utils::num_samples_to_duration(
+            frame as u64,
+            1,
+            self.sample_rate,
+        ))
+    }
 }
 
-// Lossy
-fn f32_to_i16(f: f32) -> i16 {
-    (f.max(-1.0).min(1.0) * i16::MAX as f32) as i16
+/// Fill `buffer` from `samples`. Returns the number of samples written and
+/// whether the samples ran out.
+fn fill_from<S, O>(
+    mut samples: impl Iterator<Item = hound::Result<S>>,
+    buffer: &mut [O],
+    convert: impl Fn(S) -> O,
+) -> (usize, hound::Result<bool>) {
+    for (idx, out) in buffer.iter_mut().enumerate() {
+        match samples.next() {
+            Some(Ok(sample)) => *out = convert(sample),
+            Some(Err(e)) => return (idx, Err(e)),
+            None => return (idx, Ok(true)),
+        }
+    }
+    (buffer.len(), Ok(false))
 }
 
-fn i8_to_i16(i: i8) -> i16 {
-    i as i16 * 256
+/// A sample type WAV samples can be converted to.
+trait OutputSample: Copy + Default {
+    fn from_f32(f: f32) -> Self;
+    fn from_i8(i: i8) -> Self;
+    fn from_i16(i: i16) -> Self;
+    fn from_i24(i: i32) -> Self;
+    fn from_i32(i: i32) -> Self;
 }
 
-// Lossy
-fn i24_to_i16(i: i32) -> i16 {
-    (i >> 8) as i16
+impl OutputSample for i16 {
+    // Lossy
+    fn from_f32(f: f32) -> Self {
+        (f.max(-1.0).min(1.0) * i16::MAX as f32) as i16
+    }
+
+    fn from_i8(i: i8) -> Self {
+        i as i16 * 256
+    }
+
+    fn from_i16(i: i16) -> Self {
+        i
+    }
+
+    // Lossy
+    fn from_i24(i: i32) -> Self {
+        (i >> 8) as i16
+    }
+
+    // Lossy
+    fn from_i32(i: i32) -> Self {
+        (i >> 16) as i16
+    }
 }
 
-// Lossy
-fn i32_to_i16(i: i32) -> i16 {
-    (i >> 16) as i16
+impl OutputSample for f32 {
+    fn from_f32(f: f32) -> Self {
+        f
+    }
+
+    fn from_i8(i: i8) -> Self {
+        i as f32 / 128.0
+    }
+
+    fn from_i16(i: i16) -> Self {
+        i as f32 / 32768.0
+    }
+
+    fn from_i24(i: i32) -> Self {
+        i as f32 / 8_388_608.0
+    }
+
+    fn from_i32(i: i32) -> Self {
+        i as f32 / 2_147_483_648.0
+    }
 }
 
 impl From<hound::Error> for crate::Error {
@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use crate::events::SoundId;
+use crate::utils;
+
+/// Lower the volume of a `target` sound in a [SoundMixer][super::SoundMixer]
+/// while a `trigger` sound in the same mixer is active, e.g. music while
+/// narration plays.
+///
+/// Either can be a [Bus][crate::manager::Bus] by using its
+/// [id][crate::manager::Bus::id].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ducking {
+    /// The sound that lowers the target.
+    pub trigger: SoundId,
+    /// The sound whose volume is lowered.
+    pub target: SoundId,
+    /// How much the target is lowered in decibels, e.g. -12.0.
+    pub depth_db: f32,
+    /// How long it takes to lower the target fully.
+    pub attack: Duration,
+    /// How long it takes the target to come back once the trigger stops.
+    pub release: Duration,
+    /// When the trigger counts as active.
+    pub detection: DuckDetection,
+}
+
+impl Ducking {
+    /// Lower `target` by `depth_db` while `trigger` is playing with an attack
+    /// of 50ms and a release of 500ms.
+    pub fn new(trigger: SoundId, target: SoundId, depth_db: f32) -> Self {
+        Ducking {
+            trigger,
+            target,
+            depth_db,
+            attack: Duration::from_millis(50),
+            release: Duration::from_millis(500),
+            detection: DuckDetection::Playing,
+        }
+    }
+}
+
+/// How a [Ducking] trigger is detected.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DuckDetection {
+    /// The trigger is active while it is producing samples, i.e. it is not
+    /// paused or finished. Silence within the trigger still counts as active.
+    #[default]
+    Playing,
+    /// The trigger is active while its peak level is at least `threshold_db`
+    /// and for `hold` afterwards so that short gaps, such as between words,
+    /// do not release the target.
+    Level {
+        /// The level in decibels relative to full scale, e.g. -40.0.
+        threshold_db: f32,
+        /// How long the trigger stays active after the level drops.
+        hold: Duration,
+    },
+}
+
+/// The state of a [Ducking] in a mixer.
+pub(crate) struct DuckState {
+    pub(crate) ducking: Ducking,
+    /// The current gain of the target.
+    pub(crate) gain: f32,
+    /// Set once removed. The target is released before the state is dropped.
+    pub(crate) removed: bool,
+    hold_frames_remaining: u64,
+}
+
+impl DuckState {
+    pub(crate) fn new(ducking: Ducking) -> Self {
+        DuckState {
+            ducking,
+            gain: 1.0,
+            removed: false,
+            hold_frames_remaining: 0,
+        }
+    }
+
+    /// Move the gain on by `num_frames` given whether the trigger produced
+    /// any samples and the peak level of those samples.
+    pub(crate) fn advance(&mut self, num_frames: u64, playing: bool, peak: f32, sample_rate: u32) {
+        let active = match self.ducking.detection {
+            DuckDetection::Playing => playing,
+            DuckDetection::Level { threshold_db, hold } => {
+                if playing && peak >= utils::db_to_gain(threshold_db) {
+                    self.hold_frames_remaining =
+                        utils::duration_to_num_samples(hold, 1, sample_rate);
+                    true
+                } else {
+                    self.hold_frames_remaining =
+                        self.hold_frames_remaining.saturating_sub(num_frames);
+                    self.hold_frames_remaining > 0
+                }
+            }
+        };
+
+        let depth = utils::db_to_gain(self.ducking.depth_db).min(1.0);
+        let (target, ramp) = if active {
+            (depth, self.ducking.attack)
+        } else {
+            (1.0, self.ducking.release)
+        };
+        let ramp_frames = utils::duration_to_num_samples(ramp, 1, sample_rate);
+        if ramp_frames == 0 {
+            self.gain = target;
+            return;
+        }
+        // A full ramp always moves between the depth and 1.0.
+        let step = (1.0 - depth) / ramp_frames as f32 * num_frames as f32;
+        if self.gain < target {
+            self.gain = (self.gain + step).min(target);
+        } else {
+            self.gain = (self.gain - step).max(target);
+        }
+    }
+}
@@ -0,0 +1,342 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::utils;
+
+/// Processing applied to the final output of a [SoundMixer][super::SoundMixer]
+/// so that loud mixes do not hard clip.
+///
+/// Samples are mixed as f32 so values above 1.0 are kept until the master
+/// stage. Anything the stage leaves above 1.0 is saturated when converted to
+/// i16 and clipped by most audio backends when output as f32.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MasterStage {
+    /// Output the mix unchanged. Values above 1.0 hard clip.
+    None,
+    /// Scale the mix by a fixed gain in decibels, usually negative, to leave
+    /// room for several sounds to play at once. Louder mixes still hard clip.
+    Headroom {
+        /// The gain in decibels. -6.0 halves the output.
+        gain_db: f32,
+    },
+    /// Leave samples below `threshold` unchanged and smoothly bend samples
+    /// above it so they approach but never exceed 1.0. Adds no latency but
+    /// distorts loud peaks.
+    SoftClip {
+        /// Where the curve starts, between 0.0 and 1.0.
+        threshold: f32,
+    },
+    /// A peak limiter that lowers the gain just before a peak would exceed
+    /// `threshold` and then recovers over `release`. The output is delayed by
+    /// `lookahead` so the gain can be lowered in time. Distorts less than
+    /// [MasterStage::SoftClip] at the cost of the added latency.
+    Limiter {
+        /// The highest output value, between 0.0 and 1.0.
+        threshold: f32,
+        /// How far ahead peaks are detected. Also the added latency.
+        lookahead: Duration,
+        /// How long the gain takes to recover after a peak.
+        release: Duration,
+    },
+}
+
+impl MasterStage {
+    /// A [MasterStage::Limiter] with a threshold of 0.95, 5ms lookahead and
+    /// 50ms release.
+    pub fn limiter() -> Self {
+        MasterStage::Limiter {
+            threshold: 0.95,
+            lookahead: Duration::from_millis(5),
+            release: Duration::from_millis(50),
+        }
+    }
+}
+
+/// A [MasterStage::SoftClip] with a threshold of 0.95.
+impl Default for MasterStage {
+    fn default() -> Self {
+        MasterStage::SoftClip { threshold: 0.95 }
+    }
+}
+
+/// The state of a [MasterStage] for a specific channel count and sample rate.
+pub(crate) enum MasterProcessor {
+    None,
+    Gain(f32),
+    SoftClip(f32),
+    Limiter(Box<Limiter>),
+}
+
+impl MasterProcessor {
+    pub(crate) fn new(stage: &MasterStage, channel_count: u16, sample_rate: u32) -> Self {
+        match stage {
+            MasterStage::None => MasterProcessor::None,
+            MasterStage::Headroom { gain_db } => MasterProcessor::Gain(utils::db_to_gain(*gain_db)),
+            MasterStage::SoftClip { threshold } => {
+                MasterProcessor::SoftClip(threshold.clamp(0.0, MAX_SOFT_CLIP_THRESHOLD))
+            }
+            MasterStage::Limiter {
+                threshold,
+                lookahead,
+                release,
+            } => MasterProcessor::Limiter(Box::new(Limiter::new(
+                threshold.clamp(0.0, 1.0),
+                *lookahead,
+                *release,
+                channel_count,
+                sample_rate,
+            ))),
+        }
+    }
+
+    /// Process the next sample of the interleaved output.
+    pub(crate) fn process(&mut self, sample: f32) -> f32 {
+        match self {
+            MasterProcessor::None => sample,
+            MasterProcessor::Gain(gain) => sample * *gain,
+            MasterProcessor::SoftClip(threshold) => soft_clip(sample, *threshold),
+            MasterProcessor::Limiter(limiter) => limiter.process(sample),
+        }
+    }
+
+    /// Whether samples are delayed.
+    pub(crate) fn has_latency(&self) -> bool {
+        matches!(self, MasterProcessor::Limiter(_))
+    }
+
+    /// The number of samples that still need to be processed with silent
+    /// input before everything passed in has been output.
+    pub(crate) fn samples_until_drained(&self) -> usize {
+        match self {
+            MasterProcessor::Limiter(limiter) => limiter.samples_until_drained(),
+            _ => 0,
+        }
+    }
+
+    /// Forget all delayed samples.
+    pub(crate) fn reset(&mut self) {
+        if let MasterProcessor::Limiter(limiter) = self {
+            limiter.reset();
+        }
+    }
+}
+
+/// Keeps the knee of the curve from being infinitely sharp.
+const MAX_SOFT_CLIP_THRESHOLD: f32 = 0.999;
+
+/// Linear below `threshold`, then a tanh curve with a matching slope that
+/// approaches 1.0.
+fn soft_clip(sample: f32, threshold: f32) -> f32 {
+    let magnitude = sample.abs();
+    if magnitude <= threshold {
+        return sample;
+    }
+    let range = 1.0 - threshold;
+    let clipped = threshold + range * ((magnitude - threshold) / range).tanh();
+    clipped.copysign(sample)
+}
+
+/// A lookahead peak limiter working on interleaved samples.
+///
+/// The gain needed by each sample is held for the lookahead window, released
+/// exponentially and then smoothed with a moving average over the lookahead.
+/// Since every gain in the average is at most the gain needed by a peak at
+/// the end of the window, the peak never exceeds the threshold when it leaves
+/// the delay line.
+pub(crate) struct Limiter {
+    threshold: f32,
+    release_coefficient: f32,
+    /// Input samples waiting to be output.
+    delay: Vec<f32>,
+    /// The envelope values being averaged.
+    envelope_history: Vec<f32>,
+    envelope_sum: f64,
+    /// The index into `delay` and `envelope_history` of the oldest value.
+    position: usize,
+    /// Candidates for the lowest needed gain in the window as (sample index,
+    /// gain) with increasing gains.
+    window_min: VecDeque<(u64, f32)>,
+    envelope: f32,
+    sample_index: u64,
+    /// The number of silent samples since the last non-silent input.
+    silent_samples: usize,
+}
+
+impl Limiter {
+    fn new(
+        threshold: f32,
+        lookahead: Duration,
+        release: Duration,
+        channel_count: u16,
+        sample_rate: u32,
+    ) -> Self {
+        let length =
+            (utils::duration_to_num_samples(lookahead, channel_count, sample_rate) as usize).max(1);
+        let release_samples = utils::duration_to_num_samples(release, channel_count, sample_rate);
+        let release_coefficient = if release_samples == 0 {
+            1.0
+        } else {
+            1.0 - (-1.0 / release_samples as f32).exp()
+        };
+        let mut limiter = Limiter {
+            threshold,
+            release_coefficient,
+            delay: vec![0.0; length],
+            envelope_history: vec![1.0; length],
+            envelope_sum: 0.0,
+            position: 0,
+            window_min: VecDeque::with_capacity(length + 1),
+            envelope: 1.0,
+            sample_index: 0,
+            silent_samples: 0,
+        };
+        limiter.reset();
+        limiter
+    }
+
+    fn reset(&mut self) {
+        let length = self.delay.len();
+        self.delay.fill(0.0);
+        self.envelope_history.fill(1.0);
+        self.envelope_sum = length as f64;
+        self.position = 0;
+        self.window_min.clear();
+        self.envelope = 1.0;
+        self.silent_samples = length;
+    }
+
+    fn samples_until_drained(&self) -> usize {
+        self.delay.len().saturating_sub(self.silent_samples)
+    }
+
+    fn process(&mut self, sample: f32) -> f32 {
+        let length = self.delay.len();
+        let magnitude = sample.abs();
+        let needed_gain = if magnitude > self.threshold {
+            self.threshold / magnitude
+        } else {
+            1.0
+        };
+        if sample == 0.0 {
+            self.silent_samples = self.silent_samples.saturating_add(1);
+        } else {
+            self.silent_samples = 0;
+        }
+
+        // The window includes the sample leaving the delay line this call.
+        while matches!(self.window_min.back(), Some((_, gain)) if *gain >= needed_gain) {
+            self.window_min.pop_back();
+        }
+        self.window_min.push_back((self.sample_index, needed_gain));
+        while matches!(self.window_min.front(), Some((idx, _)) if idx + (length as u64) < self.sample_index)
+        {
+            self.window_min.pop_front();
+        }
+        let held = self.window_min.front().map_or(1.0, |(_, gain)| *gain);
+        self.sample_index += 1;
+
+        if held < self.envelope {
+            self.envelope = held;
+        } else {
+            self.envelope += (held - self.envelope) * self.release_coefficient;
+        }
+
+        self.envelope_sum += (self.envelope - self.envelope_history[self.position]) as f64;
+        self.envelope_history[self.position] = self.envelope;
+        let gain = (self.envelope_sum / length as f64) as f32;
+
+        let output = self.delay[self.position];
+        self.delay[self.position] = sample;
+        self.position += 1;
+        if self.position == length {
+            self.position = 0;
+        }
+        output * gain.min(1.0)
+    }
+}
+
+/// User processing inserted on the mix of a [SoundMixer][super::SoundMixer]
+/// before its volume and [MasterStage] are applied.
+///
+/// See [Manager::add_master_effect][crate::manager::Manager::add_master_effect].
+pub trait MasterEffect: Send {
+    /// Called when the effect is added and whenever the output channel count
+    /// or sample rate changes.
+    fn set_channel_count_and_sample_rate(&mut self, _channel_count: u16, _sample_rate: u32) {}
+
+    /// Process whole interleaved frames in place.
+    fn process(&mut self, frames: &mut [f32]);
+}
+
+/// How long changes of the master gain are ramped over to avoid clicks.
+const MASTER_GAIN_RAMP: Duration = Duration::from_millis(10);
+
+/// The master volume of a mixer ramped per frame.
+pub(crate) struct MasterGain {
+    gain: f32,
+    target: f32,
+    step: f32,
+    ramp_frames_remaining: u64,
+    ramp_frames: u64,
+}
+
+impl MasterGain {
+    pub(crate) fn new(sample_rate: u32) -> Self {
+        MasterGain {
+            gain: 1.0,
+            target: 1.0,
+            step: 0.0,
+            ramp_frames_remaining: 0,
+            ramp_frames: utils::duration_to_num_samples(MASTER_GAIN_RAMP, 1, sample_rate),
+        }
+    }
+
+    pub(crate) fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.ramp_frames = utils::duration_to_num_samples(MASTER_GAIN_RAMP, 1, sample_rate);
+    }
+
+    /// Ramp to `target` from the current gain.
+    pub(crate) fn set_target(&mut self, target: f32) {
+        self.target = target;
+        self.ramp_frames_remaining = self.ramp_frames;
+        if self.ramp_frames_remaining == 0 {
+            self.gain = target;
+        } else {
+            self.step = (target - self.gain) / self.ramp_frames_remaining as f32;
+        }
+    }
+
+    /// The gain of the next frame.
+    pub(crate) fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Whether the gain is 0.0 and will stay there.
+    pub(crate) fn is_silent(&self) -> bool {
+        self.gain == 0.0 && self.ramp_frames_remaining == 0
+    }
+
+    pub(crate) fn advance_frame(&mut self) {
+        if self.ramp_frames_remaining > 0 {
+            self.ramp_frames_remaining -= 1;
+            self.gain = if self.ramp_frames_remaining == 0 {
+                self.target
+            } else {
+                self.gain + self.step
+            };
+        }
+    }
+
+    /// Apply the gain to whole interleaved frames.
+    pub(crate) fn apply(&mut self, frames: &mut [f32], channel_count: usize) {
+        if self.ramp_frames_remaining == 0 && self.gain == 1.0 {
+            return;
+        }
+        for frame in frames.chunks_exact_mut(channel_count) {
+            for sample in frame.iter_mut() {
+                *sample *= self.gain;
+            }
+            self.advance_frame();
+        }
+    }
+}
@@ -1,6 +1,6 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use crate::{NextSample, Sound};
+use crate::{sounds::wrappers::Seek, utils, NextSample, Sound};
 
 /// A Sound that stores all samples on the heap.
 ///
@@ -131,7 +131,66 @@ impl Sound for MemorySound {
         }
     }
 
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        let channel_count = self.channel_count as usize;
+        let mut num_filled = 0;
+        while num_filled < buffer.len() {
+            let remaining = self.samples.get(self.next_sample..).unwrap_or_default();
+            if remaining.is_empty() {
+                if self.should_loop && !self.samples.is_empty() {
+                    self.next_sample = 0;
+                    continue;
+                }
+                return Ok((num_filled / channel_count, Some(NextSample::Finished)));
+            }
+            let num = remaining.len().min(buffer.len() - num_filled);
+            buffer[num_filled..num_filled + num].copy_from_slice(&remaining[..num]);
+            num_filled += num;
+            self.next_sample += num;
+        }
+        Ok((num_filled / channel_count, None))
+    }
+
     fn on_start_of_batch(&mut self) {}
+
+    /// When looping, the position within the current loop.
+    fn position(&self) -> Option<Duration> {
+        Some(utils::num_samples_to_duration(
+            self.next_sample as u64,
+            self.channel_count,
+            self.sample_rate,
+        ))
+    }
+
+    /// None when looping since the sound never finishes.
+    fn total_duration(&self) -> Option<Duration> {
+        if self.should_loop {
+            return None;
+        }
+        Some(utils::num_samples_to_duration(
+            self.samples.len() as u64,
+            self.channel_count,
+            self.sample_rate,
+        ))
+    }
+}
+
+impl Seek for MemorySound {
+    fn seek(&mut self, position: Duration) -> Result<Duration, crate::Error> {
+        let channel_count = self.channel_count as usize;
+        let num_frames = self.samples.len() / channel_count;
+        let frame = utils::duration_to_num_samples(position, 1, self.sample_rate)
+            .min(num_frames as u64) as usize;
+        self.next_sample = frame * channel_count;
+        Ok(utils::num_samples_to_duration(
+            frame as u64,
+            1,
+            self.sample_rate,
+        ))
+    }
 }
 
 impl AsRef<[i16]> for MemorySound {
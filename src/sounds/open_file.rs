@@ -1,8 +1,16 @@
 use crate::Sound;
-use std::{fs::File, io::BufReader};
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom},
+    time::Duration,
+};
+
+/// The number of bytes at the start of a file needed to detect its format.
+const HEADER_LEN: usize = 12;
 
 /// Create a Sound that reads from a file with the correct decoder based on the
-/// file extension.
+/// content of the file or, if the format cannot be detected from the content,
+/// the file extension. See [open_reader].
 ///
 /// If the file type is not able to be decoded than an
 /// [std::io::ErrorKind::Unsupported] is returned.
@@ -12,11 +20,12 @@ use std::{fs::File, io::BufReader};
 /// The returned Sound reads using File. This is generally not recommended
 /// on the renderer thread as reading from a file could block the renderer.
 /// Consider convert the sound to a memory_sound which is stored entirely in RAM
-/// (and can be cloned cheaply).
+/// (and can be cloned cheaply) or use [open_file_prefetched] to read the file
+/// on a worker thread.
 pub fn open_file<P: AsRef<std::path::Path>>(path: P) -> Result<Box<dyn Sound>, crate::Error> {
     let file = File::open(path.as_ref())?;
     let reader = BufReader::new(file);
-    open_file_with_reader(path.as_ref(), reader)
+    open_reader(reader, extension(path.as_ref()))
 }
 
 /// Same as `open_file` but with an explicit BufReader capacity.
@@ -26,19 +35,46 @@ pub fn open_file_with_buffer_capacity<P: AsRef<std::path::Path>>(
 ) -> Result<Box<dyn Sound>, crate::Error> {
     let file = File::open(path.as_ref())?;
     let reader = BufReader::with_capacity(buffer_capacity, file);
-    open_file_with_reader(path.as_ref(), reader)
+    open_reader(reader, extension(path.as_ref()))
 }
 
-fn open_file_with_reader(
-    path: &std::path::Path,
-    reader: BufReader<File>,
+/// Same as `open_file` but the file is read and decoded on a worker thread
+/// which keeps up to `buffer` of samples decoded ahead so that the renderer is
+/// never blocked. See [Prefetched][crate::sounds::wrappers::Prefetched].
+///
+/// [Prefetched::DEFAULT_BUFFER][crate::sounds::wrappers::Prefetched::DEFAULT_BUFFER]
+/// is a reasonable choice for `buffer`.
+pub fn open_file_prefetched<P: AsRef<std::path::Path>>(
+    path: P,
+    buffer: Duration,
 ) -> Result<Box<dyn Sound>, crate::Error> {
-    let extension = path
-        .extension()
-        .unwrap_or_default()
-        .to_str()
-        .unwrap_or_default()
-        .to_lowercase();
+    Ok(Box::new(open_file(path)?.prefetched(buffer)?))
+}
+
+/// Create a Sound that decodes `reader` with the correct decoder based on
+/// its content, e.g. to play sounds from an archive or embedded in the binary.
+///
+/// The format is detected from the first bytes read from the current position
+/// of `reader`, which is restored afterwards. QOA, WAV, MP3, FLAC, Ogg and
+/// MP4 are detected. `hint` is the file extension such as `"mp3"` used when
+/// the format cannot be detected.
+///
+/// If the format is not able to be decoded than an
+/// [std::io::ErrorKind::Unsupported] is returned.
+///
+/// Like [open_file], decoding from a reader that blocks is not recommended on
+/// the renderer thread. Use [Sound::prefetched] to decode on a worker thread.
+pub fn open_reader<R>(mut reader: R, hint: Option<&str>) -> Result<Box<dyn Sound>, crate::Error>
+where
+    R: Read + Seek + Send + 'static,
+{
+    let extension = match sniff(&mut reader)? {
+        Some(format) => format.to_owned(),
+        None => hint
+            .unwrap_or_default()
+            .trim_start_matches('.')
+            .to_lowercase(),
+    };
     let decoder: Box<dyn Sound> = match extension.as_ref() {
         #[cfg(feature = "rmp3-mp3")]
         "mp3" => Box::new(super::decoders::Mp3Decoder::new(reader)),
@@ -49,13 +85,13 @@ fn open_file_with_reader(
         "_SILENCE_NEVER_MATCH_" => {
             println!(
                 "Included to satisfy unused warnings when all features are off: {:?}",
-                reader
+                reader.stream_position()
             );
             Box::new(crate::sounds::Silence::new(1, 1000))
         }
         #[cfg(feature = "symphonia")]
         _ => Box::new(super::decoders::SymphoniaDecoder::new(
-            Box::new(reader.into_inner()),
+            Box::new(SyncReader::new(reader)?),
             Some(&extension),
         )?),
         #[cfg(not(feature = "symphonia"))]
@@ -63,3 +99,102 @@ fn open_file_with_reader(
     };
     Ok(decoder)
 }
+
+fn extension(path: &std::path::Path) -> Option<&str> {
+    path.extension().and_then(|extension| extension.to_str())
+}
+
+/// The extension of the format of `reader` detected from its first bytes.
+/// The position of `reader` is left unchanged.
+fn sniff<R: Read + Seek>(reader: &mut R) -> Result<Option<&'static str>, std::io::Error> {
+    let start = reader.stream_position()?;
+    let mut header = [0; HEADER_LEN];
+    let mut len = 0;
+    while len < HEADER_LEN {
+        match reader.read(&mut header[len..]) {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => (),
+            Err(e) => return Err(e),
+        }
+    }
+    reader.seek(SeekFrom::Start(start))?;
+    Ok(detect_format(&header[..len]))
+}
+
+/// The extension of the format whose magic bytes `header` starts with.
+fn detect_format(header: &[u8]) -> Option<&'static str> {
+    match header {
+        [b'q', b'o', b'a', b'f', ..] => Some("qoa"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => Some("wav"),
+        [b'f', b'L', b'a', b'C', ..] => Some("flac"),
+        [b'O', b'g', b'g', b'S', ..] => Some("ogg"),
+        [_, _, _, _, b'f', b't', b'y', b'p', ..] => Some("mp4"),
+        [b'I', b'D', b'3', ..] => Some("mp3"),
+        // An MPEG audio frame sync. A layer of 0 is ADTS AAC instead.
+        [0xFF, second, ..] if second & 0xE0 == 0xE0 && second & 0x06 != 0 => Some("mp3"),
+        _ => None,
+    }
+}
+
+/// Makes a reader that is only Send usable as a symphonia MediaSource, which
+/// must be Sync. The lock is never taken since the reader is only accessed
+/// through `&mut self`.
+#[cfg(feature = "symphonia")]
+struct SyncReader<R> {
+    reader: std::sync::Mutex<R>,
+    /// The number of bytes from the position the reader was at when created
+    /// to its end.
+    len: u64,
+}
+
+#[cfg(feature = "symphonia")]
+impl<R: Read> Read for SyncReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.reader().read(buf)
+    }
+}
+
+#[cfg(feature = "symphonia")]
+impl<R: Seek> Seek for SyncReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.reader().seek(pos)
+    }
+}
+
+#[cfg(feature = "symphonia")]
+impl<R: Seek> SyncReader<R> {
+    fn new(mut reader: R) -> Result<Self, std::io::Error> {
+        let start = reader.stream_position()?;
+        let end = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(start))?;
+        Ok(SyncReader {
+            reader: std::sync::Mutex::new(reader),
+            len: end.saturating_sub(start),
+        })
+    }
+}
+
+#[cfg(feature = "symphonia")]
+impl<R> SyncReader<R> {
+    fn reader(&mut self) -> &mut R {
+        // A panic while reading cannot leave the reader in a state that
+        // matters more than any other failed read.
+        self.reader.get_mut().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(feature = "symphonia")]
+impl<R: Read + Seek + Send> symphonia::core::io::MediaSource for SyncReader<R> {
+    fn is_seekable(&self) -> bool {
+        true
+    }
+
+    fn byte_len(&self) -> Option<u64> {
+        Some(self.len)
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/open_file.rs"]
+mod tests;
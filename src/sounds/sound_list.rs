@@ -1,4 +1,6 @@
-use crate::sound::NextSample;
+use crate::deferred::Task;
+use crate::events::{EventSender, SoundId};
+use crate::sound::{NextSample, SampleType};
 use crate::sounds::wrappers::{AddSound, ClearSounds};
 use crate::Sound;
 
@@ -9,10 +11,15 @@ use crate::Sound;
 ///
 /// If an Error is returned from a Sound it is dropped and the error is
 /// propagated to the caller. Calling next_sound again would continue
-/// with the next Sound in the list.
+/// with the next Sound in the list. If an [EventSender] has been set with
+/// [SoundList::set_event_sender], the error is instead reported to it and the
+/// next Sound starts playing immediately.
 pub struct SoundList {
     sounds: Vec<Box<dyn Sound>>,
+    /// The identifier of each sound in `sounds`.
+    ids: Vec<SoundId>,
     was_empty: bool,
+    events: Option<EventSender>,
 }
 
 impl SoundList {
@@ -20,16 +27,31 @@ impl SoundList {
     pub fn new() -> Self {
         SoundList {
             sounds: Vec::new(),
+            ids: Vec::new(),
             was_empty: false,
+            events: None,
         }
     }
 
+    /// Report errors and finished sounds to `events` instead of returning
+    /// errors from next_sample. With the sender of a
+    /// [Manager][crate::manager::Manager] finished sounds are also dropped off
+    /// the renderer thread.
+    pub fn set_event_sender(&mut self, events: EventSender) {
+        self.events = Some(events);
+    }
+
     /// Add a Sound to be played after any existing sounds have `Finished`.
-    pub fn add(&mut self, sound: Box<dyn Sound>) {
+    ///
+    /// Returns the identifier used for the sound in events.
+    pub fn add(&mut self, sound: Box<dyn Sound>) -> SoundId {
         if self.sounds.is_empty() {
             self.was_empty = true;
         }
+        let id = SoundId::new();
         self.sounds.push(sound);
+        self.ids.push(id);
+        id
     }
 
     /// Inserts a sound at position `index`, shifting all elements after it to
@@ -38,28 +60,60 @@ impl SoundList {
     /// Panics
     ///
     /// Panics if `index > len`.
-    pub fn insert(&mut self, index: usize, sound: Box<dyn Sound>) {
+    pub fn insert(&mut self, index: usize, sound: Box<dyn Sound>) -> SoundId {
         if self.sounds.is_empty() {
             self.was_empty = true;
         }
-        self.sounds.insert(index, sound)
+        let id = SoundId::new();
+        self.sounds.insert(index, sound);
+        self.ids.insert(index, id);
+        id
     }
 
     /// Stop all sounds including the currently playing one.
     pub fn clear(&mut self) {
         self.sounds.clear();
+        self.ids.clear();
+    }
+
+    /// Remove the current sound and report what happened to it. With the
+    /// event sender of a Manager this happens on its worker instead of the
+    /// renderer thread.
+    fn remove_first(&mut self, error: Option<crate::Error>) {
+        let sound = self.sounds.remove(0);
+        let id = self.ids.remove(0);
+        let Some(events) = &self.events else {
+            return;
+        };
+        let deferred = events.deferred();
+        deferred.send(match error {
+            Some(error) => Task::Error(events.clone(), id, error),
+            None => Task::Finished(events.clone(), id),
+        });
+        deferred.drop_sound(sound);
     }
 
     /// Returns the number of sounds currently in the list.
     pub fn len(&self) -> usize {
         self.sounds.len()
     }
+
+    /// Returns true if there are no sounds currently in the list.
+    pub fn is_empty(&self) -> bool {
+        self.sounds.is_empty()
+    }
 }
 
 impl From<Vec<Box<dyn Sound>>> for SoundList {
     fn from(sounds: Vec<Box<dyn Sound>>) -> Self {
         let was_empty = sounds.is_empty();
-        SoundList { sounds, was_empty }
+        let ids = sounds.iter().map(|_| SoundId::new()).collect();
+        SoundList {
+            sounds,
+            ids,
+            was_empty,
+            events: None,
+        }
     }
 }
 
@@ -102,6 +156,16 @@ impl Sound for SoundList {
     }
 
     fn next_sample(&mut self) -> Result<NextSample, crate::Error> {
+        self.next_sample_of()
+    }
+
+    fn next_sample_f32(&mut self) -> Result<NextSample<f32>, crate::Error> {
+        self.next_sample_of()
+    }
+}
+
+impl SoundList {
+    fn next_sample_of<T: SampleType>(&mut self) -> Result<NextSample<T>, crate::Error> {
         let Some(next_sound) = self.sounds.first_mut() else {
             return Ok(NextSample::Finished);
         };
@@ -109,29 +173,38 @@ impl Sound for SoundList {
             self.was_empty = false;
             return Ok(NextSample::MetadataChanged);
         }
-        let next_sample = match next_sound.next_sample() {
+        let next_sample = match T::next_sample_of(next_sound) {
             Ok(s) => s,
-            Err(e) => {
+            Err(e) if self.events.is_none() => {
                 self.sounds.remove(0);
+                self.ids.remove(0);
                 return Err(e);
             }
+            Err(e) => {
+                self.remove_first(Some(e));
+                return Ok(self.after_removal());
+            }
         };
 
         let ret = match next_sample {
             NextSample::Sample(_) | NextSample::MetadataChanged | NextSample::Paused => next_sample,
             NextSample::Finished => {
-                self.sounds.remove(0);
-                if self.sounds.is_empty() {
-                    NextSample::Finished
-                } else {
-                    // The next sample might have different metadata. Instead of
-                    // normalizing here let downstream normalize.
-                    NextSample::MetadataChanged
-                }
+                self.remove_first(None);
+                self.after_removal()
             }
         };
         Ok(ret)
     }
+
+    fn after_removal<T>(&self) -> NextSample<T> {
+        if self.sounds.is_empty() {
+            NextSample::Finished
+        } else {
+            // The next sample might have different metadata. Instead of
+            // normalizing here let downstream normalize.
+            NextSample::MetadataChanged
+        }
+    }
 }
 
 impl AddSound for SoundList {
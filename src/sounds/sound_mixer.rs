@@ -1,24 +1,361 @@
-use super::wrappers::{AddSound, ChannelCountConverter, ClearSounds, SampleRateConverter};
+use super::ducking::{DuckState, Ducking};
+use super::master_stage::{MasterEffect, MasterGain, MasterProcessor, MasterStage};
+use super::voice_limit::{VoiceLimit, VoiceStealing};
+use super::wrappers::{
+    AddSound, ChannelCountConverter, ClearSounds, Controllable, Controller, ResamplerQuality,
+    SampleRateConverter, SetPaused, SetVolume,
+};
+use crate::deferred::{Deferred, Task};
+use crate::events::{EventSender, SharedSoundState, SoundId, SoundState};
 use crate::sound::NextSample;
-use crate::Sound;
+use crate::{utils, Sound};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 type MixedSound = SampleRateConverter<ChannelCountConverter<Box<dyn Sound>>>;
 
+/// The number of sounds each list of a mixer holds before it has to allocate.
+const ENTRY_CAPACITY: usize = 64;
+
+/// The number of master effects a mixer holds before it has to allocate.
+const EFFECT_CAPACITY: usize = 16;
+
+/// The number of duckings a mixer holds before it has to allocate.
+const DUCKING_CAPACITY: usize = 16;
+
+struct Entry {
+    id: SoundId,
+    sound: MixedSound,
+    /// Multiplier applied to the samples of the sound when mixing.
+    volume: f32,
+    /// Paused by [SoundMixer::set_sound_paused]. Such entries stay in
+    /// `paused_sounds` until they are resumed.
+    paused: bool,
+    /// Shared with the handles of the sound if there are any.
+    state: Option<Arc<SharedSoundState>>,
+    /// Overrides the resampler quality of the mixer for this sound.
+    quality: Option<ResamplerQuality>,
+    /// Set if the sound is a nested mixer. See [PreparedSound::bus].
+    bus: Option<Controller<SoundMixer>>,
+    /// The gain from [Ducking] at the start and end of the current mix.
+    duck_gain: f32,
+    duck_target: f32,
+    /// Whether the sound produced samples since ducking was last updated and
+    /// the peak level of those samples.
+    produced: bool,
+    peak: f32,
+    /// Voices with a lower priority are stolen first.
+    priority: i32,
+    /// When the sound was added, to steal the oldest voice.
+    order: u64,
+    /// Whether the sound has produced any samples.
+    started: bool,
+    /// The peak level of the sound in the current batch.
+    batch_peak: f32,
+    /// A quick fade of a stolen or resumed voice.
+    fade: Option<VoiceFade>,
+    /// Stolen and kept as a virtual voice. Such entries stay in
+    /// `paused_sounds` until there is room for them again.
+    virtual_voice: bool,
+    /// The frame of the mixer clock a scheduled sound starts at.
+    start_frame: u64,
+    /// The number of frames into the current buffer the sound starts at.
+    start_offset: usize,
+}
+
+/// When a sound added with [SoundMixer::add_scheduled] starts.
+///
+/// Delays are measured from the start of the batch in which the mixer
+/// receives the sound, which for the [Manager][crate::manager::Manager] is the
+/// next batch rendered by the backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartTime {
+    /// At a frame of the mixer clock. See [SoundMixer::frame]. Frames in the
+    /// past start right away.
+    Frame(u64),
+    /// A number of frames after the sound is received.
+    AfterFrames(u64),
+    /// A duration after the sound is received, converted to frames at the
+    /// output sample rate.
+    After(Duration),
+}
+
+/// What happens once a [VoiceFade] completes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FadeEnd {
+    Stop,
+    Virtualize,
+    Play,
+}
+
+/// How long stolen and resumed voices are faded over.
+const VOICE_FADE: Duration = Duration::from_millis(5);
+
+/// A quick linear fade of a voice.
+struct VoiceFade {
+    gain: f32,
+    /// The change of the gain per frame.
+    step: f32,
+    end: FadeEnd,
+}
+
+impl VoiceFade {
+    fn gain_at(&self, frame: usize) -> f32 {
+        (self.gain + self.step * frame as f32).clamp(0.0, 1.0)
+    }
+}
+
+impl Entry {
+    fn new(id: SoundId, sound: MixedSound) -> Self {
+        Entry {
+            id,
+            sound,
+            volume: 1.0,
+            paused: false,
+            state: None,
+            quality: None,
+            bus: None,
+            duck_gain: 1.0,
+            duck_target: 1.0,
+            produced: false,
+            peak: 0.0,
+            priority: 0,
+            order: 0,
+            started: false,
+            batch_peak: 0.0,
+            fade: None,
+            virtual_voice: false,
+            start_frame: 0,
+            start_offset: 0,
+        }
+    }
+
+    /// The multiplier of the sound at `frame` frames into a buffer of
+    /// `total_frames`.
+    fn gain_at(&self, frame: usize, total_frames: usize) -> f32 {
+        let duck_gain = self.duck_gain
+            + (self.duck_target - self.duck_gain) * frame as f32 / total_frames as f32;
+        let fade_gain = self.fade.as_ref().map_or(1.0, |fade| fade.gain_at(frame));
+        self.volume * duck_gain * fade_gain
+    }
+
+    /// Whether [Entry::gain_at] is the same for the whole buffer.
+    fn gain_is_constant(&self) -> bool {
+        self.duck_gain == self.duck_target && self.fade.is_none()
+    }
+
+    /// Advance the fade by `num_frames`. Returns how it ended if the voice
+    /// should be removed.
+    fn advance_fade(&mut self, num_frames: usize) -> Option<FadeEnd> {
+        let fade = self.fade.as_mut()?;
+        fade.gain = fade.gain_at(num_frames);
+        let done = match fade.end {
+            FadeEnd::Play => fade.gain >= 1.0,
+            FadeEnd::Stop | FadeEnd::Virtualize => fade.gain <= 0.0,
+        };
+        if !done {
+            return None;
+        }
+        let end = fade.end;
+        self.fade = None;
+        (end != FadeEnd::Play).then_some(end)
+    }
+
+    /// The number of frames until a stolen voice has faded out.
+    fn frames_until_silent(&self) -> Option<usize> {
+        match &self.fade {
+            Some(fade) if fade.step < 0.0 => Some((fade.gain / -fade.step).ceil() as usize),
+            _ => None,
+        }
+    }
+
+    /// Whether the entry counts towards the [VoiceLimit].
+    fn is_voice(&self) -> bool {
+        let stolen = matches!(
+            &self.fade,
+            Some(VoiceFade {
+                end: FadeEnd::Stop | FadeEnd::Virtualize,
+                ..
+            })
+        );
+        self.bus.is_none() && !self.paused && !self.virtual_voice && !stolen
+    }
+
+    fn set_state(&self, state: SoundState) {
+        if let Some(shared) = &self.state {
+            shared.set(state);
+        }
+    }
+}
+
+/// A sound converted to the output format of a mixer before it is sent to
+/// the mixer so that the renderer thread does not have to allocate when it is
+/// added. See [SoundMixer::add_prepared].
+pub(crate) struct PreparedSound(Box<Entry>);
+
+impl PreparedSound {
+    /// Convert `sound` to the current output format of the mixer of `format`.
+    pub(crate) fn new(sound: Box<dyn Sound>, id: SoundId, format: &SharedFormat) -> Self {
+        let (channel_count, sample_rate, quality) = format.load();
+        let sound = wrap(sound, channel_count, sample_rate, quality);
+        PreparedSound(Box::new(Entry::new(id, sound)))
+    }
+
+    /// A nested mixer controlled by `controller` whose output format follows
+    /// the mixer it is added to. It is kept when that mixer is cleared and its
+    /// own sounds are cleared instead.
+    pub(crate) fn bus(
+        bus: Controllable<SoundMixer>,
+        controller: Controller<SoundMixer>,
+        id: SoundId,
+        format: &SharedFormat,
+    ) -> Self {
+        let mut prepared = Self::new(Box::new(bus), id, format);
+        prepared.0.bus = Some(controller);
+        prepared
+    }
+
+    /// Keep the state of the sound up to date in `state`.
+    pub(crate) fn with_state(mut self, state: Arc<SharedSoundState>) -> Self {
+        self.0.state = Some(state);
+        self
+    }
+}
+
+/// The output format and resampler quality of a mixer shared with the
+/// threads that prepare sounds for it.
+pub(crate) struct SharedFormat(AtomicU64);
+
+impl SharedFormat {
+    fn new(channel_count: u16, sample_rate: u32, quality: ResamplerQuality) -> Self {
+        let format = SharedFormat(AtomicU64::new(0));
+        format.store(channel_count, sample_rate, quality);
+        format
+    }
+
+    fn store(&self, channel_count: u16, sample_rate: u32, quality: ResamplerQuality) {
+        let quality = match quality {
+            ResamplerQuality::Linear => 0,
+            ResamplerQuality::Sinc => 1,
+        };
+        self.0.store(
+            channel_count as u64 | (sample_rate as u64) << 16 | quality << 48,
+            Ordering::Relaxed,
+        );
+    }
+
+    pub(crate) fn load(&self) -> (u16, u32, ResamplerQuality) {
+        let format = self.0.load(Ordering::Relaxed);
+        let quality = match format >> 48 {
+            0 => ResamplerQuality::Linear,
+            _ => ResamplerQuality::Sinc,
+        };
+        (format as u16, (format >> 16) as u32, quality)
+    }
+}
+
+fn wrap(
+    sound: Box<dyn Sound>,
+    channel_count: u16,
+    sample_rate: u32,
+    quality: ResamplerQuality,
+) -> MixedSound {
+    SampleRateConverter::with_quality(
+        ChannelCountConverter::new(sound, channel_count),
+        sample_rate,
+        quality,
+    )
+}
+
+/// Why a sound is being removed from `sounds`.
+enum Removal {
+    Paused,
+    Finished,
+    Error(crate::Error),
+}
+
 /// Mix multiple sounds together to be played simultaneously.
 ///
 /// The [Manager][crate::manager::Manager] contains a SoundMixer so you might
 /// not need to crate one yourself but instead add multiple sounds on the
 /// Manager.
 ///
-/// If a Sound returns an Error from next_sample, the error is logged and the
-/// Sound is dropped but other sounds keep playing.
+/// Samples are summed as f32 so intermediate values never clip. The mix is
+/// then passed through a [MasterStage] which by default soft clips so that
+/// loud mixes do not hard clip. See [SoundMixer::set_master_stage].
+///
+/// The mix goes through a master chain of [effects][MasterEffect], then the
+/// master volume and mute, then the master stage. Volume, mute and pause
+/// changes are ramped over 10ms. Pausing the mixer pauses all sounds.
+///
+/// If a Sound returns an Error from next_sample, the error is logged and
+/// reported to the [event sender][SoundMixer::event_sender] and the Sound is
+/// dropped but other sounds keep playing. Sounds that finish are reported as
+/// well.
+///
+/// Rendering does not allocate, free or lock once the mix buffers have grown
+/// to the batch size as long as each list of sounds (playing, paused and
+/// scheduled) holds at most 64 sounds and there are at most 16 master effects
+/// and 16 duckings. The mixer of a
+/// [Manager][crate::manager::Manager] drops removed sounds and reports events
+/// on a background thread and sounds are converted to the output format
+/// before they are sent to it. Changing the output format or the master
+/// stage allocates.
+///
+/// Each added sound is identified by a [SoundId] which can be used to pause,
+/// stop or change the volume of that sound.
+///
+/// Sounds with a different sample rate are resampled with linear interpolation
+/// unless a different [ResamplerQuality] is set for the mixer or the sound.
+// Entries are boxed so that removed ones can be handed to the deferred worker
+// without allocating.
+#[allow(clippy::vec_box)]
 pub struct SoundMixer {
-    sounds: Vec<MixedSound>,
-    paused_sounds: Vec<MixedSound>,
+    sounds: Vec<Box<Entry>>,
+    paused_sounds: Vec<Box<Entry>>,
     output_channel_count: u16,
     output_sample_rate: u32,
     metadata_changed: bool,
     next_output_channel_idx: u16,
+    /// Samples of a single sound for fill_buffer before they are mixed in.
+    scratch: Vec<f32>,
+    /// The mixed output for fill_buffer before it is converted to i16.
+    mixed: Vec<f32>,
+    events: EventSender,
+    resampler_quality: ResamplerQuality,
+    master_stage: MasterStage,
+    master: MasterProcessor,
+    volume: f32,
+    muted: bool,
+    paused: bool,
+    master_gain: MasterGain,
+    effects: Vec<Box<dyn MasterEffect>>,
+    /// The processed frame being returned by next_sample when there are
+    /// effects, and how much of it has been read.
+    effect_frame: Vec<f32>,
+    effect_frame_read: usize,
+    ducks: Vec<DuckState>,
+    /// Whether any sound is ducked so ducking needs to be updated.
+    ducked: bool,
+    voice_limit: Option<VoiceLimit>,
+    /// The order of the next added sound.
+    next_order: u64,
+    /// Whether any voice is fading.
+    voices_fading: bool,
+    /// Sounds waiting for their start frame.
+    scheduled: Vec<Box<Entry>>,
+    /// The frame of the mixer clock that is rendered next.
+    frame: u64,
+    /// The frame the mixer clock is set to at the start of each batch.
+    followed_frame: Option<Arc<AtomicU64>>,
+    format: Arc<SharedFormat>,
+    /// Set for the mixers of a Manager so that removed sounds are dropped and
+    /// events are sent off the renderer thread.
+    deferred: Deferred,
+    /// The format of the parent mixer of a bus which this mixer switches to
+    /// at the start of each batch.
+    parent_format: Option<Arc<SharedFormat>>,
 }
 
 impl SoundMixer {
@@ -26,15 +363,623 @@ impl SoundMixer {
     /// rate that all added sounds will be converted to.
     pub fn new(output_channel_count: u16, output_sample_rate: u32) -> Self {
         SoundMixer {
-            sounds: Vec::new(),
-            paused_sounds: Vec::new(),
+            sounds: Vec::with_capacity(ENTRY_CAPACITY),
+            paused_sounds: Vec::with_capacity(ENTRY_CAPACITY),
             output_channel_count,
             output_sample_rate,
             metadata_changed: false,
             next_output_channel_idx: 0,
+            scratch: Vec::new(),
+            mixed: Vec::new(),
+            events: EventSender::new(),
+            resampler_quality: ResamplerQuality::default(),
+            master: MasterProcessor::new(
+                &MasterStage::default(),
+                output_channel_count,
+                output_sample_rate,
+            ),
+            master_stage: MasterStage::default(),
+            volume: 1.0,
+            muted: false,
+            paused: false,
+            master_gain: MasterGain::new(output_sample_rate),
+            effects: Vec::with_capacity(EFFECT_CAPACITY),
+            effect_frame: Vec::with_capacity(output_channel_count as usize),
+            effect_frame_read: 0,
+            ducks: Vec::with_capacity(DUCKING_CAPACITY),
+            ducked: false,
+            voice_limit: None,
+            next_order: 0,
+            voices_fading: false,
+            scheduled: Vec::with_capacity(ENTRY_CAPACITY),
+            frame: 0,
+            followed_frame: None,
+            format: Arc::new(SharedFormat::new(
+                output_channel_count,
+                output_sample_rate,
+                ResamplerQuality::default(),
+            )),
+            deferred: Deferred::inline(),
+            parent_format: None,
+        }
+    }
+
+    /// The output format shared with the threads that prepare sounds for this
+    /// mixer.
+    pub(crate) fn shared_format(&self) -> Arc<SharedFormat> {
+        self.format.clone()
+    }
+
+    /// Drop removed sounds and send events on `deferred`.
+    pub(crate) fn set_deferred(&mut self, deferred: Deferred) {
+        self.events.set_deferred(deferred.clone());
+        self.deferred = deferred;
+    }
+
+    /// Switch to the channel count and sample rate of `parent` at the start
+    /// of each batch after it changes. Used by buses so that their parent does
+    /// not have to send them a command.
+    pub(crate) fn follow_format(&mut self, parent: Arc<SharedFormat>) {
+        self.parent_format = Some(parent);
+    }
+
+    /// Set the mixer clock to `frame` at the start of each batch. Used by the
+    /// mixers of a Manager so that their clock keeps counting while nothing
+    /// is output, see [Controllable::set_frame].
+    pub(crate) fn follow_frame(&mut self, frame: Arc<AtomicU64>) {
+        self.followed_frame = Some(frame);
+    }
+
+    /// The sender errors and finished sounds are reported to. Subscribe to it
+    /// to receive the events.
+    pub fn event_sender(&self) -> &EventSender {
+        &self.events
+    }
+
+    /// Add a Sound identified by `id` in the events of this mixer.
+    ///
+    /// [AddSound::add] uses a new unique identifier.
+    pub fn add_with_id(&mut self, sound: Box<dyn Sound>, id: SoundId) {
+        self.add_prepared(PreparedSound::new(sound, id, &self.format));
+    }
+
+    /// Add a sound prepared for this mixer. If the output format changed since
+    /// it was prepared it is converted again.
+    pub(crate) fn add_prepared(&mut self, sound: PreparedSound) {
+        let entry = self.conform(sound.0);
+        self.push_entry(entry);
+    }
+
+    /// Add a prepared sound that starts at `start`. Its state is Paused until
+    /// it starts.
+    pub(crate) fn add_prepared_scheduled(&mut self, sound: PreparedSound, start: StartTime) {
+        let start_frame = match start {
+            StartTime::Frame(frame) => frame,
+            StartTime::AfterFrames(frames) => self.frame + frames,
+            StartTime::After(delay) => {
+                self.frame + utils::duration_to_num_samples(delay, 1, self.output_sample_rate)
+            }
+        };
+        let mut entry = self.conform(sound.0);
+        entry.start_frame = start_frame;
+        entry.set_state(SoundState::Paused);
+        self.scheduled.push(entry);
+    }
+
+    /// Convert `entry` to the output format if it was prepared for another.
+    fn conform(&mut self, mut entry: Box<Entry>) -> Box<Entry> {
+        if entry.sound.channel_count() == self.output_channel_count
+            && entry.sound.sample_rate() == self.output_sample_rate
+        {
+            let quality = entry.quality.unwrap_or(self.resampler_quality);
+            if entry.sound.quality() != quality {
+                entry.sound.set_quality(quality);
+            }
+            return entry;
+        }
+        self.rewrap(entry)
+    }
+
+    /// Wrap the sound of `entry` again for the current output format.
+    fn rewrap(&self, entry: Box<Entry>) -> Box<Entry> {
+        let entry = *entry;
+        let sound = self.wrap(entry.sound.into_inner().into_inner(), entry.quality);
+        Box::new(Entry { sound, ..entry })
+    }
+
+    /// Add a Sound that starts at the exact frame given by `start` even within
+    /// a batch.
+    pub fn add_scheduled(&mut self, sound: Box<dyn Sound>, id: SoundId, start: StartTime) {
+        self.add_prepared_scheduled(PreparedSound::new(sound, id, &self.format), start);
+    }
+
+    /// The mixer clock: the number of frames output so far. It does not
+    /// advance while the mixer is [paused][SetPaused] or has nothing to play.
+    /// When wrapped in a [Controllable] this is the same as
+    /// [Controllable::frame].
+    ///
+    /// The mixer of a [Manager][crate::manager::Manager] instead counts every
+    /// frame handed to the backend, including silence, so it matches
+    /// [OutputClock::frames][crate::manager::OutputClock::frames].
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Start the scheduled sounds that start before `end_frame`.
+    fn start_scheduled(&mut self, end_frame: u64) {
+        let mut idx = 0;
+        while idx < self.scheduled.len() {
+            if self.scheduled[idx].start_frame < end_frame {
+                let mut entry = self.scheduled.swap_remove(idx);
+                entry.start_offset = entry.start_frame.saturating_sub(self.frame) as usize;
+                if !entry.paused {
+                    entry.set_state(SoundState::Playing);
+                }
+                self.push_entry(entry);
+            } else {
+                idx += 1;
+            }
+        }
+    }
+
+    /// Find the sound `id` whether it is playing, paused or scheduled.
+    fn entry_mut(&mut self, id: SoundId) -> Option<&mut Entry> {
+        self.sounds
+            .iter_mut()
+            .chain(self.paused_sounds.iter_mut())
+            .chain(self.scheduled.iter_mut())
+            .find(|e| e.id == id)
+            .map(|e| &mut **e)
+    }
+
+    /// Report errors and finished sounds to `events` instead of a new sender.
+    /// Existing subscriptions to the previous sender no longer receive events.
+    pub fn set_event_sender(&mut self, mut events: EventSender) {
+        // Keep sending events where set_deferred asked for.
+        events.set_deferred(self.deferred.clone());
+        self.events = events;
+    }
+
+    fn wrap(&self, sound: Box<dyn Sound>, quality: Option<ResamplerQuality>) -> MixedSound {
+        wrap(
+            sound,
+            self.output_channel_count,
+            self.output_sample_rate,
+            quality.unwrap_or(self.resampler_quality),
+        )
+    }
+
+    fn push_entry(&mut self, mut entry: Box<Entry>) {
+        if !entry.started && entry.order == 0 {
+            self.next_order += 1;
+            entry.order = self.next_order;
+        }
+        if entry.paused {
+            entry.start_offset = 0;
+            self.paused_sounds.push(entry);
+        } else {
+            self.sounds.push(entry);
+        }
+    }
+
+    /// Pause or resume the sound `id`. Does nothing if the sound is not in this
+    /// mixer. Must only be called when the next sample is for the first
+    /// channel in the frame.
+    pub fn set_sound_paused(&mut self, id: SoundId, paused: bool) {
+        if paused {
+            if let Some(idx) = self.sounds.iter().position(|e| e.id == id) {
+                let entry = self.sounds.swap_remove(idx);
+                self.paused_sounds.push(entry);
+            }
+        }
+        if let Some(entry) = self.scheduled.iter_mut().find(|e| e.id == id) {
+            entry.paused = paused;
+        }
+        if let Some(entry) = self.paused_sounds.iter_mut().find(|e| e.id == id) {
+            entry.paused = paused;
+            // Virtual voices stay silent until the voice limit has room.
+            entry.set_state(if paused || entry.virtual_voice {
+                SoundState::Paused
+            } else {
+                SoundState::Playing
+            });
+        }
+    }
+
+    /// Remove the sound `id` and report it as finished to the
+    /// [event sender][SoundMixer::event_sender]. Does nothing if the sound is
+    /// not in this mixer.
+    pub fn stop_sound(&mut self, id: SoundId) {
+        let removed = [
+            &mut self.sounds,
+            &mut self.paused_sounds,
+            &mut self.scheduled,
+        ]
+        .into_iter()
+        .find_map(|sounds| {
+            let idx = sounds.iter().position(|e| e.id == id)?;
+            Some(sounds.swap_remove(idx))
+        });
+        if let Some(entry) = removed {
+            self.finish(entry);
+        }
+    }
+
+    /// Set the priority of the sound `id` for the [VoiceLimit]. Voices with a
+    /// lower priority are stolen first. Sounds start with a priority of 0.
+    /// Does nothing if the sound is not in this mixer.
+    pub fn set_sound_priority(&mut self, id: SoundId, priority: i32) {
+        if let Some(entry) = self.entry_mut(id) {
+            entry.priority = priority;
+        }
+    }
+
+    /// Limit how many sounds play at once. `None`, the default, plays all
+    /// sounds. The limit is applied at the start of each batch.
+    pub fn set_voice_limit(&mut self, limit: Option<VoiceLimit>) {
+        self.voice_limit = limit;
+    }
+
+    /// The limit of how many sounds play at once.
+    pub fn voice_limit(&self) -> Option<VoiceLimit> {
+        self.voice_limit
+    }
+
+    /// Steal voices above the limit and resume virtual voices if there is
+    /// room for them.
+    fn enforce_voice_limit(&mut self) {
+        let Some(limit) = self.voice_limit else {
+            return;
+        };
+        let fade_frames =
+            (utils::duration_to_num_samples(VOICE_FADE, 1, self.output_sample_rate)).max(1) as f32;
+        let num_voices = self
+            .sounds
+            .iter()
+            .chain(&self.paused_sounds)
+            .filter(|e| e.is_voice())
+            .count();
+
+        for _ in limit.max_voices..num_voices {
+            let victim = self
+                .sounds
+                .iter_mut()
+                .chain(self.paused_sounds.iter_mut())
+                .filter(|e| e.is_voice())
+                .min_by(|a, b| {
+                    a.priority
+                        .cmp(&b.priority)
+                        .then_with(|| match limit.stealing {
+                            VoiceStealing::Oldest => a.order.cmp(&b.order),
+                            VoiceStealing::Quietest => {
+                                let level = |e: &Entry| {
+                                    if e.started {
+                                        e.batch_peak
+                                    } else {
+                                        f32::INFINITY
+                                    }
+                                };
+                                level(a).total_cmp(&level(b))
+                            }
+                        })
+                })
+                .expect("there are more voices than the limit");
+            let end = if limit.virtual_voices {
+                FadeEnd::Virtualize
+            } else {
+                FadeEnd::Stop
+            };
+            // Voices that have not played yet do not need to fade.
+            let gain = if victim.started {
+                victim.fade.as_ref().map_or(1.0, |fade| fade.gain)
+            } else {
+                0.0
+            };
+            victim.fade = Some(VoiceFade {
+                gain,
+                step: -1.0 / fade_frames,
+                end,
+            });
+            self.voices_fading = true;
+        }
+        // Voices that did not need to fade are removed right away.
+        self.finish_voice_fades(0);
+
+        if limit.virtual_voices {
+            for _ in num_voices..limit.max_voices {
+                let Some(entry) = self
+                    .paused_sounds
+                    .iter_mut()
+                    .filter(|e| e.virtual_voice && !e.paused)
+                    .max_by(|a, b| a.priority.cmp(&b.priority).then(b.order.cmp(&a.order)))
+                else {
+                    break;
+                };
+                entry.virtual_voice = false;
+                entry.set_state(SoundState::Playing);
+                entry.fade = Some(VoiceFade {
+                    gain: 0.0,
+                    step: 1.0 / fade_frames,
+                    end: FadeEnd::Play,
+                });
+                self.voices_fading = true;
+            }
+        }
+
+        for entry in self.sounds.iter_mut().chain(&mut self.paused_sounds) {
+            entry.batch_peak = 0.0;
+        }
+    }
+
+    /// Advance the fades of voices by `num_frames` and stop or virtualize the
+    /// voices whose fade out has completed.
+    fn finish_voice_fades(&mut self, num_frames: usize) {
+        if !self.voices_fading {
+            return;
+        }
+        self.voices_fading = false;
+        for in_sounds in [false, true] {
+            let mut idx = 0;
+            loop {
+                let entries = if in_sounds {
+                    &mut self.sounds
+                } else {
+                    &mut self.paused_sounds
+                };
+                let Some(entry) = entries.get_mut(idx) else {
+                    break;
+                };
+                match entry.advance_fade(num_frames) {
+                    Some(FadeEnd::Virtualize) => {
+                        let mut entry = entries.swap_remove(idx);
+                        entry.virtual_voice = true;
+                        entry.set_state(SoundState::Paused);
+                        self.paused_sounds.push(entry);
+                    }
+                    Some(_) => {
+                        let entry = entries.swap_remove(idx);
+                        self.finish(entry);
+                    }
+                    None => {
+                        self.voices_fading |= entry.fade.is_some();
+                        idx += 1;
+                    }
+                }
+            }
         }
     }
 
+    /// Set the volume multiplier of the sound `id`. Does nothing if the sound
+    /// is not in this mixer.
+    pub fn set_sound_volume(&mut self, id: SoundId, volume: f32) {
+        if let Some(entry) = self.entry_mut(id) {
+            entry.volume = volume;
+        }
+    }
+
+    /// Set the processing applied to the mixed output. Takes effect
+    /// immediately and discards any samples delayed by a previous
+    /// [MasterStage::Limiter].
+    pub fn set_master_stage(&mut self, stage: MasterStage) {
+        self.master =
+            MasterProcessor::new(&stage, self.output_channel_count, self.output_sample_rate);
+        self.master_stage = stage;
+    }
+
+    /// The processing applied to the mixed output.
+    pub fn master_stage(&self) -> &MasterStage {
+        &self.master_stage
+    }
+
+    /// The master volume multiplier. See [SetVolume].
+    pub fn volume(&self) -> f32 {
+        self.volume
+    }
+
+    /// Silence the output without changing the volume. Sounds keep playing.
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+        self.update_master_gain();
+    }
+
+    /// Whether the output is muted.
+    pub fn is_muted(&self) -> bool {
+        self.muted
+    }
+
+    /// Whether the mixer is paused. See [SetPaused].
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Insert `effect` at the end of the master chain. Adding more than 16
+    /// effects allocates.
+    pub fn add_master_effect(&mut self, mut effect: Box<dyn MasterEffect>) {
+        effect
+            .set_channel_count_and_sample_rate(self.output_channel_count, self.output_sample_rate);
+        self.effects.push(effect);
+    }
+
+    /// Remove all effects from the master chain. The mixer of a
+    /// [Manager][crate::manager::Manager] drops them on a background thread.
+    pub fn clear_master_effects(&mut self) {
+        for effect in self.effects.drain(..) {
+            self.deferred.drop_effect(effect);
+        }
+    }
+
+    /// Lower the volume of one sound while another plays. See [Ducking].
+    /// Replaces any ducking with the same trigger and target. Adding more than
+    /// 16 duckings allocates.
+    pub fn add_ducking(&mut self, ducking: Ducking) {
+        let existing = self.ducks.iter_mut().find(|duck| {
+            duck.ducking.trigger == ducking.trigger && duck.ducking.target == ducking.target
+        });
+        match existing {
+            Some(duck) => {
+                duck.ducking = ducking;
+                duck.removed = false;
+            }
+            None => self.ducks.push(DuckState::new(ducking)),
+        }
+    }
+
+    /// Stop `trigger` from ducking `target`. The target returns to its volume
+    /// over the release of the ducking.
+    pub fn remove_ducking(&mut self, trigger: SoundId, target: SoundId) {
+        for duck in &mut self.ducks {
+            if duck.ducking.trigger == trigger && duck.ducking.target == target {
+                duck.removed = true;
+            }
+        }
+    }
+
+    /// Update the ducked gains for the next `num_frames` frames based on what
+    /// the triggers produced since the last update.
+    fn update_ducking(&mut self, num_frames: u64) {
+        if self.ducks.is_empty() && !self.ducked {
+            return;
+        }
+        for duck in &mut self.ducks {
+            let trigger = self
+                .sounds
+                .iter()
+                .chain(&self.paused_sounds)
+                .find(|entry| entry.id == duck.ducking.trigger);
+            let (playing, peak) = match trigger {
+                Some(entry) if !duck.removed => (entry.produced, entry.peak),
+                _ => (false, 0.0),
+            };
+            duck.advance(num_frames, playing, peak, self.output_sample_rate);
+        }
+        self.ducks
+            .retain(|duck| !(duck.removed && duck.gain == 1.0));
+
+        self.ducked = false;
+        for entry in self.sounds.iter_mut().chain(&mut self.paused_sounds) {
+            entry.produced = false;
+            entry.peak = 0.0;
+            entry.duck_gain = entry.duck_target;
+            entry.duck_target = self
+                .ducks
+                .iter()
+                .filter(|duck| duck.ducking.target == entry.id)
+                .map(|duck| duck.gain)
+                .product();
+            self.ducked |= entry.duck_gain != 1.0 || entry.duck_target != 1.0;
+        }
+    }
+
+    fn update_master_gain(&mut self) {
+        let target = if self.muted || self.paused {
+            0.0
+        } else {
+            self.volume
+        };
+        self.master_gain.set_target(target);
+    }
+
+    /// Whether a pause has faded out and everything delayed has been output.
+    fn pause_complete(&self) -> bool {
+        self.paused && self.master_gain.is_silent() && self.master.samples_until_drained() == 0
+    }
+
+    /// Run mixed frames through the master chain.
+    fn process_master(&mut self, frames: &mut [f32]) {
+        for effect in &mut self.effects {
+            effect.process(frames);
+        }
+        self.master_gain
+            .apply(frames, self.output_channel_count as usize);
+        for sample in frames.iter_mut() {
+            *sample = self.master.process(*sample);
+        }
+    }
+
+    /// next_sample when there are effects, which work on whole frames.
+    fn next_sample_from_frame(&mut self) -> Result<NextSample<f32>, crate::Error> {
+        if self.effect_frame_read >= self.effect_frame.len() {
+            let mut frame = std::mem::take(&mut self.effect_frame);
+            frame.resize(self.output_channel_count as usize, 0.0);
+            let (num_frames, next) = self.fill_buffer_f32(&mut frame)?;
+            if num_frames == 0 {
+                frame.clear();
+            }
+            self.effect_frame = frame;
+            self.effect_frame_read = 0;
+            if num_frames == 0 {
+                let next = next.expect("a frame was requested");
+                return Ok(next.map(|_| unreachable!()));
+            }
+        }
+        let sample = self.effect_frame[self.effect_frame_read];
+        self.effect_frame_read += 1;
+        Ok(NextSample::Sample(sample))
+    }
+
+    /// Set the resampler quality used for sounds that do not have their own
+    /// quality set with [SoundMixer::set_sound_resampler_quality].
+    pub fn set_resampler_quality(&mut self, quality: ResamplerQuality) {
+        self.resampler_quality = quality;
+        self.format
+            .store(self.output_channel_count, self.output_sample_rate, quality);
+        for entry in self.sounds.iter_mut().chain(self.paused_sounds.iter_mut()) {
+            if entry.quality.is_none() {
+                entry.sound.set_quality(quality);
+            }
+        }
+    }
+
+    /// The resampler quality used for sounds without their own quality.
+    pub fn resampler_quality(&self) -> ResamplerQuality {
+        self.resampler_quality
+    }
+
+    /// Set the resampler quality of the sound `id` regardless of the quality of
+    /// the mixer. Does nothing if the sound is not in this mixer.
+    pub fn set_sound_resampler_quality(&mut self, id: SoundId, quality: ResamplerQuality) {
+        if let Some(entry) = self.entry_mut(id) {
+            entry.quality = Some(quality);
+            entry.sound.set_quality(quality);
+        }
+    }
+
+    /// Returns true if the sound `id` is playing, paused or scheduled in this
+    /// mixer.
+    pub fn contains_sound(&self, id: SoundId) -> bool {
+        self.sounds
+            .iter()
+            .chain(self.paused_sounds.iter())
+            .chain(self.scheduled.iter())
+            .any(|e| e.id == id)
+    }
+
+    /// Remove the sound at `idx` of `sounds`.
+    fn remove(&mut self, idx: usize, removal: Removal) {
+        let entry = self.sounds.swap_remove(idx);
+        match removal {
+            Removal::Paused => {
+                entry.set_state(SoundState::Paused);
+                self.paused_sounds.push(entry);
+            }
+            Removal::Finished => self.finish(entry),
+            Removal::Error(error) => {
+                entry.set_state(SoundState::Errored);
+                self.deferred
+                    .send(Task::Error(self.events.clone(), entry.id, error));
+                self.deferred.drop(entry);
+            }
+        }
+    }
+
+    /// Report that `entry` finished and drop it.
+    fn finish(&self, entry: Box<Entry>) {
+        entry.set_state(SoundState::Finished);
+        self.deferred
+            .send(Task::Finished(self.events.clone(), entry.id));
+        self.deferred.drop(entry);
+    }
+
     /// Set the output channel count and sample rate.
     /// Added sounds will be converted to the output values. Must only be called
     /// when the next sample is for the first channel in the frame.
@@ -47,18 +992,37 @@ impl SoundMixer {
 
         self.output_channel_count = output_channel_count;
         self.output_sample_rate = output_sample_rate;
+        self.format.store(
+            output_channel_count,
+            output_sample_rate,
+            self.resampler_quality,
+        );
+        self.master =
+            MasterProcessor::new(&self.master_stage, output_channel_count, output_sample_rate);
+        self.master_gain.set_sample_rate(output_sample_rate);
+        self.effect_frame.clear();
+        self.effect_frame.reserve(output_channel_count as usize);
+        self.effect_frame_read = 0;
+        for effect in &mut self.effects {
+            effect.set_channel_count_and_sample_rate(output_channel_count, output_sample_rate);
+        }
 
-        // Now re-wrap all the sounds with the new values.
-
-        // Move all sounds to a single vec for simplicity
-        self.sounds.append(&mut self.paused_sounds);
-
-        let mut old = Vec::new();
-        std::mem::swap(&mut self.sounds, &mut old);
-        for mixed_sound in old {
-            let inner = mixed_sound.into_inner().into_inner();
-            // add will rewrap the sound
-            self.add(inner);
+        // Now re-wrap all the sounds with the new values. Draining keeps the
+        // capacity of the lists. Nested buses follow the new format at the
+        // start of their next batch, see SoundMixer::follow_format.
+        let old: Vec<_> = self
+            .sounds
+            .drain(..)
+            .chain(self.paused_sounds.drain(..))
+            .collect();
+        for entry in old {
+            let entry = self.rewrap(entry);
+            self.push_entry(entry);
+        }
+        let scheduled: Vec<_> = self.scheduled.drain(..).collect();
+        for entry in scheduled {
+            let entry = self.rewrap(entry);
+            self.scheduled.push(entry);
         }
     }
 }
@@ -73,32 +1037,93 @@ impl Sound for SoundMixer {
     }
 
     fn on_start_of_batch(&mut self) {
-        // Attempt to grab from paused sounds again
-        self.sounds.append(&mut self.paused_sounds);
+        if let Some(frame) = &self.followed_frame {
+            self.frame = frame.load(Ordering::Relaxed);
+        }
+        if let Some(parent) = &self.parent_format {
+            let (channel_count, sample_rate, _) = parent.load();
+            if channel_count != self.output_channel_count || sample_rate != self.output_sample_rate
+            {
+                self.set_output_channel_count_and_sample_rate(channel_count, sample_rate);
+            }
+        }
+        self.enforce_voice_limit();
 
-        for sound in &mut self.sounds {
-            sound.on_start_of_batch();
+        // Attempt to grab from paused sounds again unless they were paused
+        // explicitly or are virtual
+        let mut idx = 0;
+        while idx < self.paused_sounds.len() {
+            if self.paused_sounds[idx].paused || self.paused_sounds[idx].virtual_voice {
+                idx += 1;
+            } else {
+                let entry = self.paused_sounds.swap_remove(idx);
+                entry.set_state(SoundState::Playing);
+                self.sounds.push(entry);
+            }
+        }
+
+        for entry in &mut self.sounds {
+            entry.sound.on_start_of_batch();
         }
     }
 
     /// Guaranteed to not return an Error.
     fn next_sample(&mut self) -> Result<crate::sound::NextSample, crate::Error> {
+        Ok(self.next_sample_f32()?.map(utils::f32_to_i16))
+    }
+
+    /// Guaranteed to not return an Error.
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        let mut mixed = std::mem::take(&mut self.mixed);
+        mixed.resize(buffer.len(), 0.0);
+        let result = self.fill_buffer_f32(&mut mixed);
+        for (out, s) in buffer.iter_mut().zip(&mixed) {
+            *out = utils::f32_to_i16(*s);
+        }
+        self.mixed = mixed;
+        result
+    }
+
+    /// Guaranteed to not return an Error.
+    fn next_sample_f32(&mut self) -> Result<NextSample<f32>, crate::Error> {
         if self.metadata_changed {
             assert!(self.next_output_channel_idx == 0);
             self.metadata_changed = false;
             return Ok(NextSample::MetadataChanged);
         }
+        if !self.effects.is_empty() || self.effect_frame_read < self.effect_frame.len() {
+            return self.next_sample_from_frame();
+        }
+        if self.next_output_channel_idx == 0 {
+            if self.pause_complete() {
+                return Ok(NextSample::Paused);
+            }
+            self.update_ducking(1);
+            self.finish_voice_fades(1);
+            if !self.scheduled.is_empty() {
+                self.start_scheduled(self.frame + 1);
+            }
+        }
 
-        let mut output: i16 = 0;
-
-        let mut to_remove = Vec::new();
+        let mut output: f32 = 0.0;
 
-        for (idx, sound) in self.sounds.iter_mut().enumerate() {
-            loop {
-                match sound.next_sample() {
+        let mut idx = 0;
+        while idx < self.sounds.len() {
+            let entry = &mut self.sounds[idx];
+            // None if the sound should keep playing, otherwise why it is
+            // removed.
+            let removal = loop {
+                match entry.sound.next_sample_f32() {
                     Ok(NextSample::Sample(s)) => {
-                        output = output.saturating_add(s);
-                        break;
+                        output += s * entry.gain_at(0, 1);
+                        entry.produced = true;
+                        entry.started = true;
+                        entry.peak = entry.peak.max(s.abs());
+                        entry.batch_peak = entry.batch_peak.max(s.abs() * entry.volume);
+                        break None;
                     }
                     Ok(NextSample::MetadataChanged) => {
                         // We know that the channel_count and sample_rate haven't changed because
@@ -111,40 +1136,39 @@ impl Sound for SoundMixer {
                             // In the rare case we see MetadataChange not on
                             // the first channel, lets pause the sound until the
                             // next batch to avoid de-syncing the channels.
-                            to_remove.push((idx, true));
-                            break;
+                            break Some(Removal::Paused);
                         }
                     }
-                    Ok(NextSample::Paused) => {
-                        to_remove.push((idx, true));
-                        break;
-                    }
-                    Ok(NextSample::Finished) => {
-                        to_remove.push((idx, false));
-                        break;
-                    }
-                    Err(e) => {
-                        // TODO probably want to let applications subscribe to be notified of these
-                        // errors
-                        log::error!("dropping sound in SoundMixer which returned error: {}", e);
-                        to_remove.push((idx, false));
-                        break;
-                    }
+                    Ok(NextSample::Paused) => break Some(Removal::Paused),
+                    Ok(NextSample::Finished) => break Some(Removal::Finished),
+                    Err(e) => break Some(Removal::Error(e)),
                 }
+            };
+            match removal {
+                None => idx += 1,
+                Some(removal) => self.remove(idx, removal),
             }
         }
 
-        for (idx, paused) in to_remove.into_iter().rev() {
-            let sound = self.sounds.swap_remove(idx);
-            if paused {
-                self.paused_sounds.push(sound);
-            }
-            // otherwise drop finished sound
-        }
-
+        let mid_frame = self.next_output_channel_idx != 0;
+        let gain = self.master_gain.gain();
         self.next_output_channel_idx += 1;
         if self.next_output_channel_idx == self.output_channel_count {
             self.next_output_channel_idx = 0;
+            self.master_gain.advance_frame();
+            self.frame += 1;
+        }
+
+        if self.sounds.is_empty()
+            && (self.master.samples_until_drained() > 0 || !self.scheduled.is_empty())
+        {
+            // Output what the master stage is still holding back or silence
+            // until scheduled sounds start.
+            return Ok(NextSample::Sample(self.master.process(0.0)));
+        }
+        if self.sounds.is_empty() && mid_frame && self.master.has_latency() {
+            // Finish the frame the master stage was draining.
+            return Ok(NextSample::Sample(self.master.process(0.0)));
         }
 
         match (self.sounds.is_empty(), self.paused_sounds.is_empty()) {
@@ -154,31 +1178,186 @@ impl Sound for SoundMixer {
             // is changed to a Paused by the wrapper.
             (true, true) => {
                 self.next_output_channel_idx = 0;
+                self.master.reset();
                 Ok(NextSample::Finished)
             }
             (true, false) => {
                 self.next_output_channel_idx = 0;
+                self.master.reset();
                 Ok(NextSample::Paused)
             }
-            (false, _) => Ok(NextSample::Sample(output)),
+            (false, _) => Ok(NextSample::Sample(self.master.process(output * gain))),
+        }
+    }
+
+    /// Guaranteed to not return an Error.
+    fn fill_buffer_f32(
+        &mut self,
+        buffer: &mut [f32],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        if self.metadata_changed {
+            assert!(self.next_output_channel_idx == 0);
+            self.metadata_changed = false;
+            return Ok((0, Some(NextSample::MetadataChanged)));
+        }
+        debug_assert_eq!(self.next_output_channel_idx, 0);
+        if self.pause_complete() {
+            return Ok((0, Some(NextSample::Paused)));
+        }
+        self.update_ducking((buffer.len() / self.output_channel_count as usize) as u64);
+
+        let channel_count = self.output_channel_count as usize;
+        let total_frames = buffer.len() / channel_count;
+        if !self.scheduled.is_empty() {
+            self.start_scheduled(self.frame + total_frames as u64);
+        }
+        self.frame += total_frames as u64;
+        buffer.fill(0.0);
+        self.scratch.resize(buffer.len(), 0.0);
+        // The most frames written by a sound that stopped before filling the
+        // buffer.
+        let mut max_frames_of_stopped = 0;
+
+        let mut idx = 0;
+        while idx < self.sounds.len() {
+            let entry = &mut self.sounds[idx];
+            // Sounds scheduled to start within this buffer start at their
+            // exact frame.
+            let mut num_frames = std::mem::take(&mut entry.start_offset);
+            // None if the sound should keep playing, otherwise why it is
+            // removed.
+            let removal = loop {
+                // A voice fading out is not rendered once silent.
+                let end_frame = entry
+                    .frames_until_silent()
+                    .map_or(total_frames, |frames| frames.min(total_frames))
+                    .max(num_frames);
+                let scratch =
+                    &mut self.scratch[num_frames * channel_count..end_frame * channel_count];
+                match entry.sound.fill_buffer_f32(scratch) {
+                    Ok((new_frames, next)) => {
+                        let output = &mut buffer[num_frames * channel_count..];
+                        let new_samples = &scratch[..new_frames * channel_count];
+                        if entry.gain_is_constant() {
+                            let gain = entry.gain_at(0, total_frames);
+                            for (out, s) in output.iter_mut().zip(new_samples) {
+                                *out += *s * gain;
+                            }
+                        } else {
+                            // Ramp the gain over the buffer.
+                            let frames = output
+                                .chunks_exact_mut(channel_count)
+                                .zip(new_samples.chunks_exact(channel_count));
+                            for (frame_idx, (out, frame)) in frames.enumerate() {
+                                let gain = entry.gain_at(num_frames + frame_idx, total_frames);
+                                for (out, s) in out.iter_mut().zip(frame) {
+                                    *out += *s * gain;
+                                }
+                            }
+                        }
+                        if new_frames > 0 {
+                            let peak = new_samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+                            entry.produced = true;
+                            entry.started = true;
+                            entry.peak = entry.peak.max(peak);
+                            entry.batch_peak = entry.batch_peak.max(peak * entry.volume);
+                        }
+                        num_frames += new_frames;
+                        match next {
+                            None => break None,
+                            Some(NextSample::Sample(_)) => unreachable!(),
+                            // Whole frames are always returned so we can just
+                            // continue. See next_sample.
+                            Some(NextSample::MetadataChanged) => (),
+                            Some(NextSample::Paused) => break Some(Removal::Paused),
+                            Some(NextSample::Finished) => break Some(Removal::Finished),
+                        }
+                    }
+                    Err(e) => break Some(Removal::Error(e)),
+                }
+            };
+            match removal {
+                None => idx += 1,
+                Some(removal) => {
+                    max_frames_of_stopped = max_frames_of_stopped.max(num_frames);
+                    self.remove(idx, removal);
+                }
+            }
+        }
+
+        self.finish_voice_fades(total_frames);
+
+        if !self.sounds.is_empty() || !self.scheduled.is_empty() {
+            self.process_master(buffer);
+            return Ok((total_frames, None));
+        }
+
+        let mut num_frames = max_frames_of_stopped;
+        self.process_master(&mut buffer[..num_frames * channel_count]);
+        // Output what the master stage is still holding back.
+        while num_frames < total_frames && self.master.samples_until_drained() > 0 {
+            self.process_master(
+                &mut buffer[num_frames * channel_count..(num_frames + 1) * channel_count],
+            );
+            num_frames += 1;
+        }
+        if num_frames == total_frames {
+            return Ok((total_frames, None));
+        }
+        self.master.reset();
+        self.frame -= (total_frames - num_frames) as u64;
+        if self.paused_sounds.is_empty() {
+            // See next_sample for why we are finished
+            Ok((num_frames, Some(NextSample::Finished)))
+        } else {
+            Ok((num_frames, Some(NextSample::Paused)))
         }
     }
 }
 
 impl AddSound for SoundMixer {
     fn add(&mut self, sound: Box<dyn Sound>) {
-        self.sounds.push(SampleRateConverter::new(
-            ChannelCountConverter::new(sound, self.output_channel_count),
-            self.output_sample_rate,
-        ));
+        self.add_with_id(sound, SoundId::new());
     }
 }
 
 impl ClearSounds for SoundMixer {
-    /// Remove all audio sounds.
+    /// Remove all audio sounds. Buses are kept but their sounds are cleared.
     fn clear(&mut self) {
-        self.sounds.clear();
-        self.paused_sounds.clear();
+        for sounds in [
+            &mut self.sounds,
+            &mut self.paused_sounds,
+            &mut self.scheduled,
+        ] {
+            let mut idx = 0;
+            while idx < sounds.len() {
+                if let Some(bus) = &mut sounds[idx].bus {
+                    bus.try_call(|bus: &mut SoundMixer| bus.clear());
+                    idx += 1;
+                } else {
+                    let entry = sounds.swap_remove(idx);
+                    entry.set_state(SoundState::Finished);
+                    self.deferred.drop(entry);
+                }
+            }
+        }
+    }
+}
+
+/// Sets the master volume. Changes are ramped over 10ms.
+impl SetVolume for SoundMixer {
+    fn set_volume(&mut self, multiplier: f32) {
+        self.volume = multiplier;
+        self.update_master_gain();
+    }
+}
+
+/// Pausing fades out over 10ms and then returns `Paused` without pulling
+/// samples from any sound until unpaused.
+impl SetPaused for SoundMixer {
+    fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        self.update_master_gain();
     }
 }
 
@@ -1,4 +1,9 @@
-use crate::{NextSample, Sound};
+use crate::{
+    deferred::Task,
+    events::{EventSender, SoundId},
+    sound::SampleType,
+    NextSample, Sound,
+};
 
 type SoundGenerator = Box<dyn FnMut() -> Option<Box<dyn Sound>> + Send>;
 
@@ -8,15 +13,20 @@ type SoundGenerator = Box<dyn FnMut() -> Option<Box<dyn Sound>> + Send>;
 /// returned finished. After `SoundsFromFn` returns None
 /// this sound returns Finished. If an Error is returned from next_sound
 /// that sound is dropped and the Error is returned. If next_sound is called
-/// again SoundsFromFn is called again.
+/// again SoundsFromFn is called again. If an [EventSender] has been set with
+/// [SoundsFromFn::set_event_sender], the error is instead reported to it and
+/// the next sound is generated and played immediately.
 ///
 /// This can be used to create sounds that loop forever without storing all
 /// samples in memory.
 pub struct SoundsFromFn {
     generator: SoundGenerator,
     current: Option<Box<dyn Sound>>,
+    /// The identifier of `current` in events.
+    current_id: SoundId,
     current_channel_count: u16,
     current_sample_rate: u32,
+    events: Option<EventSender>,
 }
 
 impl SoundsFromFn {
@@ -40,13 +50,40 @@ impl SoundsFromFn {
         let mut to_return = Self {
             generator,
             current,
+            current_id: SoundId::new(),
             current_channel_count: 0,
             current_sample_rate: 0,
+            events: None,
         };
         to_return.update_metadata();
         to_return
     }
 
+    /// Report errors and finished sounds to `events` instead of returning
+    /// errors from next_sample. With the sender of a
+    /// [Manager][crate::manager::Manager] finished sounds are also dropped off
+    /// the renderer thread.
+    pub fn set_event_sender(&mut self, events: EventSender) {
+        self.events = Some(events);
+    }
+
+    /// The identifier used in events for the sound currently playing.
+    pub fn current_id(&self) -> SoundId {
+        self.current_id
+    }
+
+    fn generate(&mut self) {
+        if let Some(old) = self.current.take() {
+            match &self.events {
+                Some(events) => events.deferred().drop_sound(old),
+                None => drop(old),
+            }
+        }
+        self.current = (self.generator)();
+        self.current_id = SoundId::new();
+        self.update_metadata();
+    }
+
     fn update_metadata(&mut self) {
         self.current_channel_count = self.channel_count();
         self.current_sample_rate = self.sample_rate();
@@ -75,18 +112,35 @@ impl Sound for SoundsFromFn {
     }
 
     fn next_sample(&mut self) -> Result<NextSample, crate::Error> {
+        self.next_sample_of()
+    }
+
+    fn next_sample_f32(&mut self) -> Result<NextSample<f32>, crate::Error> {
+        self.next_sample_of()
+    }
+}
+
+impl SoundsFromFn {
+    fn next_sample_of<T: SampleType>(&mut self) -> Result<NextSample<T>, crate::Error> {
         loop {
             let Some(current) = &mut self.current else {
                 return Ok(NextSample::Finished);
             };
-            let sample = current.next_sample();
+            let sample = T::next_sample_of(current);
+            let mut failed = false;
             let sample = match sample {
                 Ok(s) => s,
                 Err(e) => {
-                    self.current = None;
-                    self.current = (self.generator)();
-                    self.update_metadata();
-                    return Err(e);
+                    let Some(events) = &self.events else {
+                        self.generate();
+                        return Err(e);
+                    };
+                    events
+                        .deferred()
+                        .send(Task::Error(events.clone(), self.current_id, e));
+                    failed = true;
+                    // Continue with the next sound as if this one finished.
+                    NextSample::Finished
                 }
             };
             match sample {
@@ -98,9 +152,12 @@ impl Sound for SoundsFromFn {
                 NextSample::Finished => {
                     let old_channel_count = self.current_channel_count;
                     let old_sample_rate = self.current_sample_rate;
-                    self.current = None;
-                    self.current = (self.generator)();
-                    self.update_metadata();
+                    if let (Some(events), false) = (&self.events, failed) {
+                        events
+                            .deferred()
+                            .send(Task::Finished(events.clone(), self.current_id));
+                    }
+                    self.generate();
                     if self.current.is_none() {
                         return Ok(NextSample::Finished);
                     }
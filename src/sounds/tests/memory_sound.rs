@@ -83,3 +83,51 @@ fn loop_forever() {
     assert_eq!(sound.next_sample().unwrap(), NextSample::Sample(2));
     assert_eq!(sound.next_sample().unwrap(), NextSample::Sample(1));
 }
+
+#[test]
+fn seek() {
+    let mut sound = MemorySound::from_samples(Arc::new(vec![1, 2, 3, 4, 5, 6]), 2, 1000);
+    assert_eq!(
+        sound.seek(Duration::from_millis(1)).unwrap(),
+        Duration::from_millis(1)
+    );
+    assert_eq!(sound.next_sample().unwrap(), NextSample::Sample(3));
+    assert_eq!(sound.seek(Duration::ZERO).unwrap(), Duration::ZERO);
+    assert_eq!(sound.next_sample().unwrap(), NextSample::Sample(1));
+    // Seeking past the end positions the sound at its end
+    assert_eq!(
+        sound.seek(Duration::from_secs(1)).unwrap(),
+        Duration::from_millis(3)
+    );
+    assert_eq!(sound.next_sample().unwrap(), NextSample::Finished);
+}
+
+#[test]
+fn position_and_total_duration() {
+    let mut sound = MemorySound::from_samples(Arc::new(vec![1, 2, 3, 4, 5, 6]), 2, 1000);
+    assert_eq!(sound.total_duration(), Some(Duration::from_millis(3)));
+    assert_eq!(sound.position(), Some(Duration::ZERO));
+    sound.next_sample().unwrap();
+    sound.next_sample().unwrap();
+    assert_eq!(sound.position(), Some(Duration::from_millis(1)));
+    sound.set_looping(true);
+    assert_eq!(sound.total_duration(), None);
+}
+
+#[test]
+fn fill_buffer() {
+    let mut sound = MemorySound::from_samples(Arc::new(vec![1, 2, 3, 4, 5, 6]), 2, 1000);
+    let mut buffer = [0; 4];
+    assert_eq!(sound.fill_buffer(&mut buffer).unwrap(), (2, None));
+    assert_eq!(buffer, [1, 2, 3, 4]);
+    assert_eq!(
+        sound.fill_buffer(&mut buffer).unwrap(),
+        (1, Some(NextSample::Finished))
+    );
+    assert_eq!(buffer[..2], [5, 6]);
+
+    sound.set_looping(true);
+    let mut buffer = [0; 10];
+    assert_eq!(sound.fill_buffer(&mut buffer).unwrap(), (5, None));
+    assert_eq!(buffer, [1, 2, 3, 4, 5, 6, 1, 2, 3, 4]);
+}
@@ -0,0 +1,87 @@
+use super::*;
+use crate::NextSample;
+use std::io::Cursor;
+
+const QOA_FILE: &[u8] =
+    include_bytes!("../decoders/tests/audiocheck.net_sin_1000Hz_0dBFS_0.1s.qoa");
+const WAV_FILE: &[u8] =
+    include_bytes!("../decoders/tests/audiocheck.net_sin_1000Hz_0dBFS_0.1s.wav");
+const MP3_FILE: &[u8] =
+    include_bytes!("../decoders/tests/audiocheck.net_sin_1000Hz_0dBFS_0.1s.mp3");
+
+#[test]
+fn detects_formats_from_magic_bytes() {
+    assert_eq!(detect_format(QOA_FILE), Some("qoa"));
+    assert_eq!(detect_format(WAV_FILE), Some("wav"));
+    assert_eq!(detect_format(MP3_FILE), Some("mp3"));
+    assert_eq!(detect_format(b"fLaC\0\0\0\x22"), Some("flac"));
+    assert_eq!(detect_format(b"OggS\0\x02"), Some("ogg"));
+    assert_eq!(detect_format(b"\0\0\0\x20ftypM4A "), Some("mp4"));
+    assert_eq!(detect_format(b"ID3\x04\0"), Some("mp3"));
+    assert_eq!(detect_format(&[0xFF, 0xFB, 0x90, 0x64]), Some("mp3"));
+}
+
+#[test]
+fn unknown_or_short_headers_are_not_detected() {
+    assert_eq!(detect_format(b""), None);
+    assert_eq!(detect_format(b"qoa"), None);
+    assert_eq!(detect_format(b"RIFF\0\0\0\0AVI "), None);
+    // ADTS AAC
+    assert_eq!(detect_format(&[0xFF, 0xF1, 0x50, 0x80]), None);
+    assert_eq!(detect_format(b"hello world!"), None);
+}
+
+#[test]
+fn sniffing_keeps_the_position() {
+    let mut data = b"junk".to_vec();
+    data.extend_from_slice(QOA_FILE);
+    let mut reader = Cursor::new(data);
+    reader.set_position(4);
+    assert_eq!(sniff(&mut reader).unwrap(), Some("qoa"));
+    assert_eq!(reader.position(), 4);
+}
+
+fn first_sample(sound: &mut dyn Sound) -> NextSample {
+    loop {
+        match sound.next_sample().unwrap() {
+            NextSample::MetadataChanged => continue,
+            next => return next,
+        }
+    }
+}
+
+#[cfg(feature = "qoa")]
+#[test]
+fn content_wins_over_the_hint() {
+    let mut sound = open_reader(Cursor::new(QOA_FILE), Some("mp3")).unwrap();
+    assert_eq!(sound.sample_rate(), 44100);
+    assert_eq!(sound.channel_count(), 1);
+    assert!(matches!(first_sample(&mut *sound), NextSample::Sample(_)));
+}
+
+#[cfg(feature = "symphonia")]
+#[test]
+fn reader_without_hint() {
+    for data in [WAV_FILE, MP3_FILE] {
+        let mut sound = open_reader(Cursor::new(data), None).unwrap();
+        assert_eq!(sound.sample_rate(), 44100);
+        assert!(matches!(first_sample(&mut *sound), NextSample::Sample(_)));
+    }
+}
+
+#[test]
+fn unknown_format_without_hint_is_unsupported() {
+    assert!(open_reader(Cursor::new(b"not audio at all".to_vec()), None).is_err());
+}
+
+#[cfg(feature = "qoa")]
+#[test]
+fn file_with_the_wrong_extension() {
+    let path = std::env::temp_dir().join(format!("awedio-open-file-{}.bin", std::process::id()));
+    std::fs::write(&path, QOA_FILE).unwrap();
+    let sound = open_file(&path);
+    std::fs::remove_file(&path).unwrap();
+    let mut sound = sound.unwrap();
+    assert_eq!(sound.sample_rate(), 44100);
+    assert!(matches!(first_sample(&mut *sound), NextSample::Sample(_)));
+}
@@ -27,3 +27,45 @@ fn empty_gives_metadata_changed_on_next() {
     assert_eq!(list.next_sample().unwrap(), NextSample::Sample(6));
     assert_eq!(list.next_sample().unwrap(), NextSample::Finished);
 }
+
+#[test]
+fn errors_are_propagated_without_event_sender() {
+    let mut list = SoundList::new();
+    list.add(Box::new(crate::tests::ErrorSound));
+    list.add(Box::new(MemorySound::from_samples(
+        Arc::new(vec![1]),
+        1,
+        1000,
+    )));
+    assert_eq!(list.next_sample().unwrap(), NextSample::MetadataChanged);
+    assert!(list.next_sample().is_err());
+    assert_eq!(list.next_sample().unwrap(), NextSample::Sample(1));
+}
+
+#[test]
+fn errors_are_reported_to_event_sender() {
+    use crate::events::{EventSender, SoundEvent};
+
+    let events = EventSender::new();
+    let receiver = events.subscribe();
+    let mut list = SoundList::new();
+    list.set_event_sender(events);
+    let failing = list.add(Box::new(crate::tests::ErrorSound));
+    let second = list.add(Box::new(MemorySound::from_samples(
+        Arc::new(vec![1]),
+        1,
+        1000,
+    )));
+    assert_eq!(list.next_sample().unwrap(), NextSample::MetadataChanged);
+    assert_eq!(list.next_sample().unwrap(), NextSample::MetadataChanged);
+    assert!(matches!(
+        receiver.try_recv().unwrap(),
+        SoundEvent::Error { id, .. } if id == failing
+    ));
+    assert_eq!(list.next_sample().unwrap(), NextSample::Sample(1));
+    assert_eq!(list.next_sample().unwrap(), NextSample::Finished);
+    assert!(matches!(
+        receiver.try_recv().unwrap(),
+        SoundEvent::Finished { id } if id == second
+    ));
+}
@@ -1,8 +1,15 @@
 use super::*;
 use crate::{
-    sounds::SoundList,
+    events::SoundId,
+    events::{SharedSoundState, SoundState},
+    sounds::{
+        wrappers::SetVolume, DuckDetection, Ducking, MasterEffect, MasterStage, SoundList,
+        StartTime, VoiceLimit, VoiceStealing,
+    },
     tests::{ConstantValueSound, DEFAULT_CHANNEL_COUNT, DEFAULT_SAMPLE_RATE},
 };
+use std::sync::Arc;
+use std::time::Duration;
 
 #[test]
 fn additional_silent_sounds_do_not_affect_first() {
@@ -53,3 +60,675 @@ fn empty_sound_list_not_same_sample_rate() {
     mixer.on_start_of_batch();
     assert_eq!(mixer.next_sample().unwrap(), NextSample::Sample(5));
 }
+
+#[test]
+fn fill_buffer_mixes_and_finishes() {
+    let first = ConstantValueSound::new(5);
+    let second = crate::sounds::MemorySound::from_samples(
+        std::sync::Arc::new(vec![1, 2, 3, 4]),
+        DEFAULT_CHANNEL_COUNT,
+        DEFAULT_SAMPLE_RATE,
+    );
+    let mut mixer = SoundMixer::new(DEFAULT_CHANNEL_COUNT, DEFAULT_SAMPLE_RATE);
+    mixer.add(Box::new(first));
+    mixer.add(Box::new(second.clone()));
+    let mut buffer = [0; 8];
+    assert_eq!(mixer.fill_buffer(&mut buffer).unwrap(), (4, None));
+    assert_eq!(buffer, [6, 7, 8, 9, 5, 5, 5, 5]);
+
+    let mut mixer = SoundMixer::new(DEFAULT_CHANNEL_COUNT, DEFAULT_SAMPLE_RATE);
+    mixer.add(Box::new(second));
+    assert_eq!(
+        mixer.fill_buffer(&mut buffer).unwrap(),
+        (2, Some(NextSample::Finished))
+    );
+    assert_eq!(buffer[..4], [1, 2, 3, 4]);
+}
+
+#[test]
+fn fill_buffer_converts_channels() {
+    let mono = crate::sounds::MemorySound::from_samples(
+        std::sync::Arc::new(vec![1, 2]),
+        1,
+        DEFAULT_SAMPLE_RATE,
+    );
+    let mut mixer = SoundMixer::new(2, DEFAULT_SAMPLE_RATE);
+    mixer.add(Box::new(mono));
+    let mut buffer = [0; 4];
+    assert_eq!(mixer.fill_buffer(&mut buffer).unwrap(), (2, None));
+    assert_eq!(buffer, [1, 1, 2, 2]);
+}
+
+#[test]
+fn intermediate_sums_do_not_clip() {
+    let mut mixer = SoundMixer::new(DEFAULT_CHANNEL_COUNT, DEFAULT_SAMPLE_RATE);
+    mixer.add(Box::new(ConstantValueSound::new(30000)));
+    mixer.add(Box::new(ConstantValueSound::new(30000)));
+    mixer.add(Box::new(ConstantValueSound::new(-30000)));
+    assert_eq!(mixer.next_sample().unwrap(), NextSample::Sample(30000));
+    assert_eq!(mixer.next_sample().unwrap(), NextSample::Sample(30000));
+    let mut buffer = [0; 4];
+    assert_eq!(mixer.fill_buffer(&mut buffer).unwrap(), (2, None));
+    assert_eq!(buffer, [30000; 4]);
+}
+
+#[test]
+fn final_output_saturates_without_master_stage() {
+    let mut mixer = SoundMixer::new(DEFAULT_CHANNEL_COUNT, DEFAULT_SAMPLE_RATE);
+    mixer.set_master_stage(MasterStage::None);
+    mixer.add(Box::new(ConstantValueSound::new(30000)));
+    mixer.add(Box::new(ConstantValueSound::new(30000)));
+    assert_eq!(mixer.next_sample().unwrap(), NextSample::Sample(i16::MAX));
+    assert_eq!(mixer.next_sample().unwrap(), NextSample::Sample(i16::MAX));
+    let mut buffer = [0.0; 2];
+    assert_eq!(mixer.fill_buffer_f32(&mut buffer).unwrap(), (1, None));
+    assert_eq!(buffer, [60000.0 / 32768.0; 2]);
+}
+
+#[test]
+fn default_master_stage_soft_clips() {
+    let mut mixer = SoundMixer::new(DEFAULT_CHANNEL_COUNT, DEFAULT_SAMPLE_RATE);
+    mixer.add(Box::new(ConstantValueSound::new(16000)));
+    mixer.add(Box::new(ConstantValueSound::new(16000)));
+    let mut buffer = [0.0; 2];
+    assert_eq!(mixer.fill_buffer_f32(&mut buffer).unwrap(), (1, None));
+    let input = 32000.0 / 32768.0;
+    assert!(buffer[0] > 0.95 && buffer[0] < input);
+    assert_eq!(buffer[0], buffer[1]);
+
+    mixer.add(Box::new(ConstantValueSound::new(30000)));
+    assert_eq!(mixer.fill_buffer_f32(&mut buffer).unwrap(), (1, None));
+    assert!(buffer[0] <= 1.0 && buffer[0] > 0.99);
+}
+
+#[test]
+fn headroom_scales_output() {
+    let mut mixer = SoundMixer::new(DEFAULT_CHANNEL_COUNT, DEFAULT_SAMPLE_RATE);
+    mixer.set_master_stage(MasterStage::Headroom { gain_db: -6.0 });
+    mixer.add(Box::new(ConstantValueSound::new(10000)));
+    let expected = utils::f32_to_i16(utils::i16_to_f32(10000) * utils::db_to_gain(-6.0));
+    assert_eq!(mixer.next_sample().unwrap(), NextSample::Sample(expected));
+}
+
+#[test]
+fn limiter_delays_and_limits_peaks() {
+    let mut mixer = SoundMixer::new(DEFAULT_CHANNEL_COUNT, 1000);
+    mixer.set_master_stage(MasterStage::Limiter {
+        threshold: 0.5,
+        lookahead: Duration::from_millis(4),
+        release: Duration::from_millis(10),
+    });
+    mixer.add(Box::new(ConstantValueSound::new(8000)));
+    // Four frames of lookahead delay the output.
+    let mut buffer = [1.0; 8];
+    assert_eq!(mixer.fill_buffer_f32(&mut buffer).unwrap(), (4, None));
+    assert_eq!(buffer, [0.0; 8]);
+    let quiet = 8000.0 / 32768.0;
+    assert_eq!(mixer.fill_buffer_f32(&mut buffer).unwrap(), (4, None));
+    assert_eq!(buffer, [quiet; 8]);
+
+    // Loud samples never exceed the threshold, including the ones before
+    // them that are still quiet.
+    mixer.add(Box::new(ConstantValueSound::new(30000)));
+    let mut buffer = [0.0; 200];
+    assert_eq!(mixer.fill_buffer_f32(&mut buffer).unwrap(), (100, None));
+    assert!(buffer.iter().all(|s| *s <= 0.5 + 1e-6));
+    assert!(buffer[..8].iter().all(|s| *s < quiet));
+    assert!(buffer[190] > 0.49);
+}
+
+#[test]
+fn limiter_outputs_delayed_samples_before_finishing() {
+    let mut mixer = SoundMixer::new(DEFAULT_CHANNEL_COUNT, 1000);
+    mixer.set_master_stage(MasterStage::Limiter {
+        threshold: 1.0,
+        lookahead: Duration::from_millis(2),
+        release: Duration::from_millis(10),
+    });
+    mixer.add(Box::new(crate::sounds::MemorySound::from_samples(
+        std::sync::Arc::new(vec![1000, 2000, 3000, 4000]),
+        DEFAULT_CHANNEL_COUNT,
+        1000,
+    )));
+    let mut buffer = [0; 10];
+    assert_eq!(
+        mixer.fill_buffer(&mut buffer).unwrap(),
+        (4, Some(NextSample::Finished))
+    );
+    assert_eq!(buffer[..8], [0, 0, 0, 0, 1000, 2000, 3000, 4000]);
+
+    mixer.add(Box::new(crate::sounds::MemorySound::from_samples(
+        std::sync::Arc::new(vec![1000, 2000]),
+        DEFAULT_CHANNEL_COUNT,
+        1000,
+    )));
+    let samples: Vec<_> = std::iter::from_fn(|| match mixer.next_sample().unwrap() {
+        NextSample::Sample(s) => Some(s),
+        _ => None,
+    })
+    .collect();
+    assert_eq!(samples, [0, 0, 0, 0, 1000, 2000]);
+}
+
+#[test]
+fn master_volume_and_mute_are_ramped() {
+    // 10 frames of ramp at this sample rate.
+    let mut mixer = SoundMixer::new(1, 1000);
+    mixer.set_master_stage(MasterStage::None);
+    mixer.add(Box::new(ConstantValueSound::new(10000)));
+    mixer.set_volume(0.5);
+    let mut buffer = [0; 12];
+    assert_eq!(mixer.fill_buffer(&mut buffer).unwrap(), (12, None));
+    assert_eq!(buffer[0], 10000);
+    assert!(buffer.windows(2).all(|w| w[1] <= w[0]));
+    assert_eq!(buffer[10..], [5000, 5000]);
+
+    mixer.set_muted(true);
+    assert!(mixer.is_muted());
+    assert_eq!(mixer.fill_buffer(&mut buffer).unwrap(), (12, None));
+    assert_eq!(buffer[10..], [0, 0]);
+    mixer.set_muted(false);
+    for _ in 0..10 {
+        mixer.next_sample().unwrap();
+    }
+    assert_eq!(mixer.next_sample().unwrap(), NextSample::Sample(5000));
+}
+
+#[test]
+fn pausing_mixer_fades_out_and_pauses() {
+    let mut mixer = SoundMixer::new(1, 1000);
+    mixer.set_master_stage(MasterStage::None);
+    mixer.add(Box::new(crate::sounds::MemorySound::from_samples(
+        std::sync::Arc::new(vec![1000; 30]),
+        1,
+        1000,
+    )));
+    mixer.set_paused(true);
+    let mut buffer = [0; 10];
+    assert_eq!(mixer.fill_buffer(&mut buffer).unwrap(), (10, None));
+    assert_eq!(buffer[0], 1000);
+    assert_eq!(
+        mixer.fill_buffer(&mut buffer).unwrap(),
+        (0, Some(NextSample::Paused))
+    );
+    assert_eq!(mixer.next_sample().unwrap(), NextSample::Paused);
+
+    mixer.set_paused(false);
+    assert_eq!(mixer.fill_buffer(&mut buffer).unwrap(), (10, None));
+    assert_eq!(buffer[0], 0);
+    // The remaining 10 samples of the sound were kept while paused.
+    assert_eq!(mixer.fill_buffer(&mut buffer).unwrap(), (10, None));
+    assert_eq!(buffer, [1000; 10]);
+    assert_eq!(
+        mixer.fill_buffer(&mut buffer).unwrap(),
+        (0, Some(NextSample::Finished))
+    );
+}
+
+struct Double;
+
+impl MasterEffect for Double {
+    fn process(&mut self, frames: &mut [f32]) {
+        for sample in frames {
+            *sample *= 2.0;
+        }
+    }
+}
+
+#[test]
+fn master_effects_process_the_mix() {
+    let mut mixer = SoundMixer::new(DEFAULT_CHANNEL_COUNT, DEFAULT_SAMPLE_RATE);
+    mixer.add(Box::new(ConstantValueSound::new(100)));
+    mixer.add(Box::new(ConstantValueSound::new(20)));
+    mixer.add_master_effect(Box::new(Double));
+    mixer.add_master_effect(Box::new(Double));
+    let mut buffer = [0; 4];
+    assert_eq!(mixer.fill_buffer(&mut buffer).unwrap(), (2, None));
+    assert_eq!(buffer, [480; 4]);
+    assert_eq!(mixer.next_sample().unwrap(), NextSample::Sample(480));
+    assert_eq!(mixer.next_sample().unwrap(), NextSample::Sample(480));
+
+    mixer.clear_master_effects();
+    assert_eq!(mixer.next_sample().unwrap(), NextSample::Sample(120));
+}
+
+fn samples(values: Vec<i16>) -> Box<dyn Sound> {
+    Box::new(crate::sounds::MemorySound::from_samples(
+        std::sync::Arc::new(values),
+        1,
+        1000,
+    ))
+}
+
+#[test]
+fn ducking_while_trigger_plays() {
+    let mut mixer = SoundMixer::new(1, 1000);
+    mixer.set_master_stage(MasterStage::None);
+    let target = SoundId::new();
+    let trigger = SoundId::new();
+    mixer.add_with_id(Box::new(ConstantValueSound::new(1000)), target);
+    mixer.add_ducking(Ducking {
+        attack: Duration::from_millis(10),
+        release: Duration::from_millis(20),
+        ..Ducking::new(trigger, target, utils::gain_to_db(0.5))
+    });
+    let mut buffer = [0; 10];
+    assert_eq!(mixer.fill_buffer(&mut buffer).unwrap(), (10, None));
+    assert_eq!(buffer, [1000; 10]);
+
+    // Silence still counts as playing.
+    mixer.add_with_id(samples(vec![0; 30]), trigger);
+    assert_eq!(mixer.fill_buffer(&mut buffer).unwrap(), (10, None));
+    assert_eq!(buffer, [1000; 10]);
+    assert_eq!(mixer.fill_buffer(&mut buffer).unwrap(), (10, None));
+    assert_eq!(buffer[0], 1000);
+    assert!(buffer.windows(2).all(|w| w[1] < w[0]));
+    assert_eq!(mixer.fill_buffer(&mut buffer).unwrap(), (10, None));
+    assert_eq!(buffer, [500; 10]);
+
+    // The trigger finishes so the target is released over 20ms.
+    let output: Vec<_> = (0..25)
+        .map(|_| match mixer.next_sample().unwrap() {
+            NextSample::Sample(s) => s,
+            other => panic!("unexpected {other:?}"),
+        })
+        .collect();
+    assert_eq!(output[..2], [500, 500]);
+    assert!(output.windows(2).all(|w| w[1] >= w[0]));
+    assert!(output[20] < 1000);
+    assert_eq!(output[22..], [1000; 3]);
+}
+
+#[test]
+fn ducking_by_level() {
+    let mut mixer = SoundMixer::new(1, 1000);
+    mixer.set_master_stage(MasterStage::None);
+    let target = SoundId::new();
+    let trigger = SoundId::new();
+    mixer.add_with_id(Box::new(ConstantValueSound::new(1000)), target);
+    mixer.add_with_id(
+        samples([vec![0; 10], vec![16384; 10], vec![0; 30]].concat()),
+        trigger,
+    );
+    mixer.add_ducking(Ducking {
+        attack: Duration::ZERO,
+        release: Duration::ZERO,
+        detection: DuckDetection::Level {
+            threshold_db: -12.0,
+            hold: Duration::from_millis(5),
+        },
+        ..Ducking::new(trigger, target, utils::gain_to_db(0.5))
+    });
+    let mut buffer = [0; 10];
+    assert_eq!(mixer.fill_buffer(&mut buffer).unwrap(), (10, None));
+    assert_eq!(buffer, [1000; 10]);
+    mixer.fill_buffer(&mut buffer).unwrap();
+    // Ducked after the loud samples while they are held. Ducking ramps over
+    // each buffer so it starts at the end of the first.
+    let mut buffer = [0; 1];
+    let mut output = Vec::new();
+    for _ in 0..20 {
+        mixer.fill_buffer(&mut buffer).unwrap();
+        output.push(buffer[0]);
+    }
+    assert_eq!(output[..6], [1000, 500, 500, 500, 500, 500]);
+    assert_eq!(output[6..], [1000; 14]);
+
+    // Removing the ducking releases the target.
+    mixer.add_ducking(Ducking::new(trigger, target, -6.0));
+    mixer.remove_ducking(trigger, target);
+    mixer.fill_buffer(&mut buffer).unwrap();
+    assert_eq!(buffer[0], 1000);
+}
+
+fn limited_mixer(limit: VoiceLimit) -> SoundMixer {
+    let mut mixer = SoundMixer::new(1, 1000);
+    mixer.set_master_stage(MasterStage::None);
+    mixer.set_voice_limit(Some(limit));
+    mixer
+}
+
+fn next_batch(mixer: &mut SoundMixer) -> [i16; 10] {
+    let mut buffer = [0; 10];
+    mixer.on_start_of_batch();
+    mixer.fill_buffer(&mut buffer).unwrap();
+    buffer
+}
+
+#[test]
+fn voice_limit_steals_oldest_voice_with_fade() {
+    let mut mixer = limited_mixer(VoiceLimit::new(2));
+    mixer.add(Box::new(ConstantValueSound::new(100)));
+    mixer.add(Box::new(ConstantValueSound::new(10)));
+    assert_eq!(next_batch(&mut mixer), [110; 10]);
+
+    mixer.add(Box::new(ConstantValueSound::new(1)));
+    let buffer = next_batch(&mut mixer);
+    // The oldest voice fades out over 5 frames.
+    assert_eq!(buffer[0], 111);
+    assert!(buffer[..6].windows(2).all(|w| w[1] < w[0]));
+    assert_eq!(buffer[5..], [11; 5]);
+    assert_eq!(next_batch(&mut mixer), [11; 10]);
+}
+
+#[test]
+fn voice_limit_steals_lowest_priority() {
+    let mut mixer = limited_mixer(VoiceLimit::new(2));
+    let important = SoundId::new();
+    let other = SoundId::new();
+    mixer.add_with_id(Box::new(ConstantValueSound::new(100)), important);
+    mixer.add_with_id(Box::new(ConstantValueSound::new(10)), other);
+    mixer.set_sound_priority(important, 10);
+    mixer.set_sound_priority(other, 5);
+    assert_eq!(next_batch(&mut mixer), [110; 10]);
+
+    // A new voice with a lower priority than all others never plays.
+    mixer.add(Box::new(ConstantValueSound::new(1)));
+    assert_eq!(next_batch(&mut mixer), [110; 10]);
+
+    let new = SoundId::new();
+    mixer.add_with_id(Box::new(ConstantValueSound::new(1)), new);
+    mixer.set_sound_priority(new, 5);
+    next_batch(&mut mixer);
+    assert_eq!(next_batch(&mut mixer), [101; 10]);
+}
+
+#[test]
+fn voice_limit_steals_quietest() {
+    let mut mixer = limited_mixer(VoiceLimit {
+        stealing: VoiceStealing::Quietest,
+        ..VoiceLimit::new(2)
+    });
+    mixer.add(Box::new(ConstantValueSound::new(10)));
+    mixer.add(Box::new(ConstantValueSound::new(100)));
+    assert_eq!(next_batch(&mut mixer), [110; 10]);
+    mixer.add(Box::new(ConstantValueSound::new(1)));
+    next_batch(&mut mixer);
+    assert_eq!(next_batch(&mut mixer), [101; 10]);
+}
+
+#[test]
+fn virtual_voices_resume_where_they_were() {
+    let mut mixer = limited_mixer(VoiceLimit {
+        virtual_voices: true,
+        ..VoiceLimit::new(1)
+    });
+    let background = SoundId::new();
+    let state = Arc::new(SharedSoundState::new(SoundState::Playing));
+    let format = mixer.shared_format();
+    mixer.add_prepared(
+        PreparedSound::new(samples((1..=40).collect()), background, &format)
+            .with_state(state.clone()),
+    );
+    assert_eq!(next_batch(&mut mixer), [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+
+    let alert = SoundId::new();
+    mixer.add_with_id(samples(vec![1000; 15]), alert);
+    mixer.set_sound_priority(alert, 1);
+    let buffer = next_batch(&mut mixer);
+    assert!(buffer[0] > 1000);
+    assert_eq!(buffer[5..], [1000; 5]);
+    assert_eq!(state.get(), SoundState::Paused);
+
+    // The alert finishes and the background resumes with a fade in from
+    // after the samples rendered while it faded out.
+    let buffer = next_batch(&mut mixer);
+    assert_eq!(buffer[..5], [1000; 5]);
+    assert_eq!(buffer[5..], [0; 5]);
+    let buffer = next_batch(&mut mixer);
+    assert_eq!(state.get(), SoundState::Playing);
+    assert_eq!(buffer[0], 0);
+    assert_eq!(buffer[5..], [21, 22, 23, 24, 25]);
+}
+
+#[test]
+fn resuming_a_virtual_voice_waits_for_room() {
+    let mut mixer = limited_mixer(VoiceLimit {
+        virtual_voices: true,
+        ..VoiceLimit::new(1)
+    });
+    let background = SoundId::new();
+    let state = Arc::new(SharedSoundState::new(SoundState::Playing));
+    let format = mixer.shared_format();
+    mixer.add_prepared(
+        PreparedSound::new(samples((1..=40).collect()), background, &format)
+            .with_state(state.clone()),
+    );
+    next_batch(&mut mixer);
+    let alert = SoundId::new();
+    mixer.add_with_id(samples(vec![1000; 25]), alert);
+    mixer.set_sound_priority(alert, 1);
+    next_batch(&mut mixer);
+    assert_eq!(state.get(), SoundState::Paused);
+
+    mixer.set_sound_paused(background, true);
+    mixer.set_sound_paused(background, false);
+    assert_eq!(state.get(), SoundState::Paused);
+    assert_eq!(next_batch(&mut mixer), [1000; 10]);
+    assert_eq!(state.get(), SoundState::Paused);
+
+    // Paused while virtual so it is not resumed when the alert finishes.
+    mixer.set_sound_paused(background, true);
+    let buffer = next_batch(&mut mixer);
+    assert_eq!(buffer[5..], [0; 5]);
+    assert_eq!(next_batch(&mut mixer), [0; 10]);
+    assert_eq!(state.get(), SoundState::Paused);
+
+    mixer.set_sound_paused(background, false);
+    next_batch(&mut mixer);
+    assert_eq!(state.get(), SoundState::Playing);
+}
+
+#[test]
+fn scheduled_sound_starts_at_exact_frame() {
+    let mut mixer = SoundMixer::new(1, 1000);
+    mixer.set_master_stage(MasterStage::None);
+    mixer.add(Box::new(ConstantValueSound::new(100)));
+    let id = SoundId::new();
+    let state = Arc::new(SharedSoundState::new(SoundState::Paused));
+    let format = mixer.shared_format();
+    mixer.add_prepared_scheduled(
+        PreparedSound::new(Box::new(ConstantValueSound::new(10)), id, &format)
+            .with_state(state.clone()),
+        StartTime::Frame(13),
+    );
+    assert_eq!(next_batch(&mut mixer), [100; 10]);
+    assert_eq!(mixer.frame(), 10);
+    assert_eq!(state.get(), SoundState::Paused);
+    assert!(mixer.contains_sound(id));
+
+    assert_eq!(
+        next_batch(&mut mixer),
+        [100, 100, 100, 110, 110, 110, 110, 110, 110, 110]
+    );
+    assert_eq!(state.get(), SoundState::Playing);
+}
+
+#[test]
+fn scheduled_sound_plays_silence_until_it_starts() {
+    let mut mixer = SoundMixer::new(1, 1000);
+    mixer.add_scheduled(
+        samples(vec![1, 2, 3]),
+        SoundId::new(),
+        StartTime::AfterFrames(5),
+    );
+    let mut buffer = [0; 10];
+    mixer.on_start_of_batch();
+    assert_eq!(
+        mixer.fill_buffer(&mut buffer).unwrap(),
+        (8, Some(NextSample::Finished))
+    );
+    assert_eq!(buffer[..8], [0, 0, 0, 0, 0, 1, 2, 3]);
+    assert_eq!(mixer.frame(), 8);
+}
+
+#[test]
+fn scheduled_sound_starts_at_exact_sample() {
+    let mut mixer = SoundMixer::new(2, 1000);
+    let id = SoundId::new();
+    mixer.add_scheduled(
+        Box::new(crate::sounds::MemorySound::from_samples(
+            Arc::new(vec![5, 6]),
+            2,
+            1000,
+        )),
+        id,
+        StartTime::After(Duration::from_millis(2)),
+    );
+    let mut output = Vec::new();
+    while let NextSample::Sample(s) = mixer.next_sample().unwrap() {
+        output.push(s);
+    }
+    assert_eq!(output, [0, 0, 0, 0, 5, 6]);
+    assert_eq!(mixer.frame(), 3);
+}
+
+#[test]
+fn stop_and_pause_scheduled_sounds() {
+    let mut mixer = SoundMixer::new(1, 1000);
+    let stopped = SoundId::new();
+    let paused = SoundId::new();
+    mixer.add_scheduled(samples(vec![1; 20]), stopped, StartTime::AfterFrames(2));
+    mixer.add_scheduled(samples(vec![10; 20]), paused, StartTime::AfterFrames(2));
+    mixer.stop_sound(stopped);
+    assert!(!mixer.contains_sound(stopped));
+    mixer.set_sound_paused(paused, true);
+    assert_eq!(next_batch(&mut mixer), [0; 10]);
+
+    mixer.set_sound_paused(paused, false);
+    assert_eq!(next_batch(&mut mixer), [10; 10]);
+}
+
+#[test]
+fn errors_and_finished_sounds_are_reported() {
+    let mut mixer = SoundMixer::new(DEFAULT_CHANNEL_COUNT, DEFAULT_SAMPLE_RATE);
+    let events = mixer.event_sender().subscribe();
+    let failing = crate::events::SoundId::new();
+    let finishing = crate::events::SoundId::new();
+    mixer.add_with_id(Box::new(crate::tests::ErrorSound), failing);
+    mixer.add_with_id(
+        Box::new(crate::sounds::MemorySound::from_samples(
+            std::sync::Arc::new(vec![1, 2]),
+            DEFAULT_CHANNEL_COUNT,
+            DEFAULT_SAMPLE_RATE,
+        )),
+        finishing,
+    );
+    mixer.add(Box::new(ConstantValueSound::new(5)));
+    let mut buffer = [0; 4];
+    assert_eq!(mixer.fill_buffer(&mut buffer).unwrap(), (2, None));
+    assert_eq!(buffer, [6, 7, 5, 5]);
+
+    let mut received = events.try_iter().collect::<Vec<_>>();
+    received.sort_by_key(|event| event.id());
+    assert_eq!(received.len(), 2);
+    assert!(matches!(received[0], crate::events::SoundEvent::Error { id, .. } if id == failing));
+    assert!(matches!(received[1], crate::events::SoundEvent::Finished { id } if id == finishing));
+}
+
+#[test]
+fn replaced_event_sender_keeps_deferred() {
+    let mut mixer = SoundMixer::new(DEFAULT_CHANNEL_COUNT, DEFAULT_SAMPLE_RATE);
+    assert!(mixer.event_sender().deferred().is_inline());
+    mixer.set_deferred(crate::deferred::Deferred::worker());
+    mixer.set_event_sender(crate::events::EventSender::new());
+    assert!(!mixer.event_sender().deferred().is_inline());
+}
+
+#[test]
+fn stopped_sounds_are_reported_as_finished() {
+    let mut mixer = SoundMixer::new(DEFAULT_CHANNEL_COUNT, DEFAULT_SAMPLE_RATE);
+    let events = mixer.event_sender().subscribe();
+    let playing = crate::events::SoundId::new();
+    let scheduled = crate::events::SoundId::new();
+    mixer.add_with_id(Box::new(ConstantValueSound::new(100)), playing);
+    mixer.add_scheduled(
+        Box::new(ConstantValueSound::new(10)),
+        scheduled,
+        StartTime::AfterFrames(100),
+    );
+
+    mixer.stop_sound(playing);
+    mixer.stop_sound(scheduled);
+    let received = events
+        .try_iter()
+        .map(|event| event.id())
+        .collect::<Vec<_>>();
+    assert_eq!(received, [playing, scheduled]);
+}
+
+#[test]
+fn control_sounds_by_id() {
+    let mut mixer = SoundMixer::new(DEFAULT_CHANNEL_COUNT, DEFAULT_SAMPLE_RATE);
+    let first = crate::events::SoundId::new();
+    let second = crate::events::SoundId::new();
+    mixer.add_with_id(Box::new(ConstantValueSound::new(100)), first);
+    mixer.add_with_id(Box::new(ConstantValueSound::new(10)), second);
+    let mut buffer = [0; 2];
+
+    mixer.set_sound_volume(first, 2.0);
+    mixer.set_sound_paused(second, true);
+    mixer.on_start_of_batch();
+    assert_eq!(mixer.fill_buffer(&mut buffer).unwrap(), (1, None));
+    assert_eq!(buffer, [200, 200]);
+    assert!(mixer.contains_sound(second));
+
+    mixer.stop_sound(first);
+    assert!(!mixer.contains_sound(first));
+    mixer.on_start_of_batch();
+    assert_eq!(
+        mixer.fill_buffer(&mut buffer).unwrap(),
+        (0, Some(NextSample::Paused))
+    );
+
+    mixer.set_sound_paused(second, false);
+    mixer.on_start_of_batch();
+    assert_eq!(mixer.fill_buffer(&mut buffer).unwrap(), (1, None));
+    assert_eq!(buffer, [10, 10]);
+}
+
+#[test]
+fn resampler_quality_applies_to_sounds_without_override() {
+    let mut mixer = SoundMixer::new(DEFAULT_CHANNEL_COUNT, 48000);
+    let first = SoundId::new();
+    let second = SoundId::new();
+    mixer.add_with_id(Box::new(ConstantValueSound::new(5)), first);
+    mixer.add_with_id(Box::new(ConstantValueSound::new(5)), second);
+    mixer.set_sound_resampler_quality(second, ResamplerQuality::Linear);
+    mixer.set_resampler_quality(ResamplerQuality::Sinc);
+    assert_eq!(mixer.resampler_quality(), ResamplerQuality::Sinc);
+    assert_eq!(mixer.sounds[0].sound.quality(), ResamplerQuality::Sinc);
+    assert_eq!(mixer.sounds[1].sound.quality(), ResamplerQuality::Linear);
+
+    // The quality is kept when the output format changes.
+    mixer.set_output_channel_count_and_sample_rate(1, 96000);
+    assert_eq!(mixer.sounds[0].sound.quality(), ResamplerQuality::Sinc);
+    assert_eq!(mixer.sounds[1].sound.quality(), ResamplerQuality::Linear);
+}
+
+#[test]
+fn paused_sound_follows_output_format_change() {
+    let mut mixer = SoundMixer::new(2, 44100);
+    mixer.set_master_stage(MasterStage::None);
+    let id = SoundId::new();
+    mixer.add_with_id(Box::new(crate::tests::Sawtooth::new(1, 44100)), id);
+    mixer.on_start_of_batch();
+    let mut samples = Vec::new();
+    for _ in 0..4 {
+        samples.push(mixer.next_sample().unwrap());
+    }
+    assert_eq!(samples, [0, 0, 1, 1].map(NextSample::Sample).to_vec());
+
+    mixer.set_sound_paused(id, true);
+    mixer.on_start_of_batch();
+    mixer.set_output_channel_count_and_sample_rate(1, 22050);
+    mixer.set_sound_paused(id, false);
+    mixer.on_start_of_batch();
+    assert_eq!(mixer.next_sample().unwrap(), NextSample::MetadataChanged);
+    let mut samples = Vec::new();
+    for _ in 0..3 {
+        samples.push(mixer.next_sample().unwrap());
+    }
+    // One channel at half the rate.
+    assert_eq!(samples, [2, 4, 6].map(NextSample::Sample).to_vec());
+}
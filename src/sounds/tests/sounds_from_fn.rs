@@ -46,3 +46,40 @@ fn changing_metadata_and_finishing() {
     assert_eq!(from_fn.next_sample().unwrap(), NextSample::Finished);
     assert_eq!(from_fn.next_sample().unwrap(), NextSample::Finished);
 }
+
+#[test]
+fn errors_are_reported_to_event_sender() {
+    use crate::events::{EventSender, SoundEvent};
+
+    let mut num = 0;
+    let generator = move || {
+        num += 1;
+        let sound: Box<dyn Sound> = match num {
+            1 => Box::new(crate::tests::ErrorSound),
+            2 => Box::new(MemorySound::from_samples(
+                Arc::new(vec![1, 2]),
+                crate::tests::DEFAULT_CHANNEL_COUNT,
+                crate::tests::DEFAULT_SAMPLE_RATE,
+            )),
+            _ => return None,
+        };
+        Some(sound)
+    };
+    let events = EventSender::new();
+    let receiver = events.subscribe();
+    let mut from_fn = SoundsFromFn::new(Box::new(generator));
+    from_fn.set_event_sender(events);
+    let failing = from_fn.current_id();
+    assert_eq!(from_fn.next_sample().unwrap(), NextSample::Sample(1));
+    assert!(matches!(
+        receiver.try_recv().unwrap(),
+        SoundEvent::Error { id, .. } if id == failing
+    ));
+    let second = from_fn.current_id();
+    assert_eq!(from_fn.next_sample().unwrap(), NextSample::Sample(2));
+    assert_eq!(from_fn.next_sample().unwrap(), NextSample::Finished);
+    assert!(matches!(
+        receiver.try_recv().unwrap(),
+        SoundEvent::Finished { id } if id == second
+    ));
+}
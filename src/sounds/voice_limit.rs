@@ -0,0 +1,48 @@
+/// Limit how many sounds a [SoundMixer][super::SoundMixer] plays at once. See
+/// [SoundMixer::set_voice_limit][super::SoundMixer::set_voice_limit].
+///
+/// Every sound that is not paused is a voice except for
+/// [buses][crate::manager::Bus]. When there are more voices than
+/// `max_voices`, the voice with the lowest priority is faded out quickly and
+/// stopped. Ties are broken by `stealing`.
+///
+/// The limit does not reach into buses: sounds played on a bus are only
+/// counted by the bus's own limit set with
+/// [Bus::set_voice_limit][crate::manager::Bus::set_voice_limit] and are never
+/// stolen by the limit of the mixer the bus plays on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VoiceLimit {
+    /// The most voices that are rendered at once.
+    pub max_voices: usize,
+    /// Which voice is stolen among the voices with the lowest priority.
+    pub stealing: VoiceStealing,
+    /// Keep stolen voices as virtual voices instead of stopping them. A
+    /// virtual voice keeps its position but is not rendered. It resumes, in
+    /// order of priority, when there are fewer than `max_voices` voices
+    /// again. Time does not pass for a virtual voice.
+    pub virtual_voices: bool,
+}
+
+impl VoiceLimit {
+    /// Play at most `max_voices` sounds, stealing the oldest voice with the
+    /// lowest priority.
+    pub fn new(max_voices: usize) -> Self {
+        VoiceLimit {
+            max_voices,
+            stealing: VoiceStealing::default(),
+            virtual_voices: false,
+        }
+    }
+}
+
+/// Which voice of those with the lowest priority is stolen by a
+/// [VoiceLimit].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VoiceStealing {
+    /// The voice that started first.
+    #[default]
+    Oldest,
+    /// The voice with the lowest peak level in the last batch. Voices that
+    /// have not played yet are stolen last.
+    Quietest,
+}
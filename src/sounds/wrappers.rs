@@ -6,31 +6,51 @@
 //! instead of directly.
 
 mod adjustable_speed;
+mod adjustable_tempo;
 mod adjustable_volume;
 #[cfg(feature = "async")]
 pub mod async_completion_notifier;
+mod automation;
 mod channel_count_converter;
 mod completion_notifier;
 mod controllable;
+mod fade;
 mod finish_after;
+mod from_float;
+mod into_float;
+mod pannable;
 mod pausable;
+mod pitch_shift;
+mod prefetched;
 mod sample_rate_converter;
+mod sinc_resampler;
 mod stoppable;
 mod wrapper;
 
 pub use adjustable_speed::AdjustableSpeed;
 pub use adjustable_speed::SetSpeed;
+pub use adjustable_tempo::AdjustableTempo;
+pub use adjustable_tempo::SetTempo;
 pub use adjustable_volume::AdjustableVolume;
 pub use adjustable_volume::SetVolume;
+pub use adjustable_volume::VolumeCurve;
 #[cfg(feature = "async")]
 pub use async_completion_notifier::AsyncCompletionNotifier;
-pub use channel_count_converter::ChannelCountConverter;
+pub use automation::{Easing, Ramp, Tween, Tweener};
+pub use channel_count_converter::{ChannelCountConverter, ChannelMixMatrix};
 pub use completion_notifier::CompletionNotifier;
-pub use controllable::{Controllable, Controller};
+pub use controllable::{CommandQueueFull, Controllable, Controller};
+pub use fade::{Fade, SetFade};
 pub use finish_after::FinishAfter;
+pub use from_float::FromFloat;
+pub use into_float::IntoFloat;
+pub use pannable::{PanLaw, Pannable, SetPan};
 pub use pausable::Pausable;
 pub use pausable::SetPaused;
-pub use sample_rate_converter::SampleRateConverter;
+pub use pitch_shift::PitchShift;
+pub use pitch_shift::SetPitch;
+pub use prefetched::Prefetched;
+pub use sample_rate_converter::{ResamplerQuality, SampleRateConverter};
 pub use stoppable::SetStopped;
 pub use stoppable::Stoppable;
 pub use wrapper::Wrapper;
@@ -47,3 +67,23 @@ pub trait ClearSounds {
     /// Clear all sounds currently playing or scheduled to play.
     fn clear(&mut self);
 }
+
+/// A Sound whose playback position can be changed.
+///
+/// Unlike [Sound::skip][crate::Sound::skip], seeking does not need to decode
+/// every sample in between and can move backwards.
+pub trait Seek {
+    /// Move the playback position to `position` measured from the start of
+    /// the sound.
+    ///
+    /// Returns the position actually seeked to, which may be earlier than
+    /// `position` if the sound can only seek to certain points and is never
+    /// past the end of the sound. Seeking past the end positions the sound at
+    /// its end so the next call to next_sample returns `Finished`.
+    ///
+    /// Must only be called when the next sample is for the first channel. After
+    /// seeking the next sample is for the first channel. If the channel count
+    /// or sample rate is different at the new position `MetadataChanged` is
+    /// returned from the next call to next_sample.
+    fn seek(&mut self, position: std::time::Duration) -> Result<std::time::Duration, crate::Error>;
+}
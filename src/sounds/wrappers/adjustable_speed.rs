@@ -1,6 +1,10 @@
-use crate::Sound;
+use std::time::Duration;
 
-use super::{SetPaused, SetStopped, SetVolume};
+use crate::{sound::SampleType, NextSample, Sound};
+
+use super::automation::Tweener;
+use super::wrapper::forward_to_inner;
+use super::{Ramp, Seek, Tween};
 
 /// A sound that can have the playback speed adjusted.
 ///
@@ -11,13 +15,41 @@ pub trait SetSpeed {
     /// 1.0 is the normal playback speed. 2.0 would be twice as fast, 0.5 would
     /// be half has fast.
     fn set_speed(&mut self, multiplier: f32);
+
+    /// Move the playback speed to `multiplier` over `duration` following
+    /// `ramp`.
+    fn ramp_speed(&mut self, multiplier: f32, duration: Duration, ramp: Ramp) {
+        self.start_speed_tween(Tween::ramp(multiplier, duration, ramp))
+    }
+
+    /// Animate the playback speed with `tween`, replacing any running tween.
+    /// The default implementation calls [set_speed][SetSpeed::set_speed] with
+    /// the target of the tween.
+    fn start_speed_tween(&mut self, tween: Tween) {
+        if let Some(target) = tween.target() {
+            self.set_speed(target)
+        }
+    }
 }
 
+/// How many frames an automated speed is held before it is updated. Every
+/// update changes the sample rate so, like the k-rate parameters of Web
+/// Audio, the speed is not updated every frame.
+const AUTOMATION_UPDATE_FRAMES: usize = 128;
+
 /// A wrapper that adjusts the speed of the inner sound.
+///
+/// Speed tweens started with [start_speed_tween][SetSpeed::start_speed_tween]
+/// or [ramp_speed][SetSpeed::ramp_speed] update the speed every 128 frames.
 pub struct AdjustableSpeed<S: Sound> {
     inner: S,
     speed_adjustment: f32,
     speed_changed: bool,
+    /// The tween started with [start_speed_tween][SetSpeed::start_speed_tween].
+    automation: Option<Tweener>,
+    frames_until_update: usize,
+    /// The index in the frame of the next sample.
+    channel_idx: u16,
 }
 
 impl<S> AdjustableSpeed<S>
@@ -36,6 +68,9 @@ where
             inner,
             speed_adjustment,
             speed_changed: false,
+            automation: None,
+            frames_until_update: 0,
+            channel_idx: 0,
         }
     }
 
@@ -70,16 +105,42 @@ where
     }
 
     fn next_sample(&mut self) -> Result<crate::NextSample, crate::Error> {
-        if self.speed_changed {
-            self.speed_changed = false;
-            return Ok(crate::NextSample::MetadataChanged);
-        }
-        self.inner.next_sample()
+        self.next_sample_of()
+    }
+
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(usize, Option<crate::NextSample>), crate::Error> {
+        self.fill_buffer_of(buffer)
+    }
+
+    fn next_sample_f32(&mut self) -> Result<crate::NextSample<f32>, crate::Error> {
+        self.next_sample_of()
+    }
+
+    fn fill_buffer_f32(
+        &mut self,
+        buffer: &mut [f32],
+    ) -> Result<(usize, Option<crate::NextSample>), crate::Error> {
+        self.fill_buffer_of(buffer)
     }
 
     fn on_start_of_batch(&mut self) {
         self.inner.on_start_of_batch()
     }
+
+    /// The position of the inner sound in adjusted time.
+    fn position(&self) -> Option<Duration> {
+        self.inner.position().map(|p| self.to_adjusted_time(p))
+    }
+
+    /// The total duration of the inner sound in adjusted time.
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner
+            .total_duration()
+            .map(|d| self.to_adjusted_time(d))
+    }
 }
 
 impl<S> SetSpeed for AdjustableSpeed<S>
@@ -89,6 +150,17 @@ where
     fn set_speed(&mut self, new: f32) {
         self.speed_changed = true;
         self.speed_adjustment = new;
+        self.automation = None;
+    }
+
+    fn start_speed_tween(&mut self, tween: Tween) {
+        let tweener = Tweener::new(self.speed_adjustment, tween);
+        if tweener.is_finished() {
+            self.set_speed(tweener.target());
+        } else {
+            self.automation = Some(tweener);
+            self.frames_until_update = AUTOMATION_UPDATE_FRAMES;
+        }
     }
 }
 
@@ -100,32 +172,92 @@ where
     pub fn speed(&self) -> f32 {
         self.speed_adjustment
     }
-}
 
-impl<S> SetPaused for AdjustableSpeed<S>
-where
-    S: Sound + SetPaused,
-{
-    fn set_paused(&mut self, paused: bool) {
-        self.inner.set_paused(paused)
+    fn next_sample_of<T: SampleType>(&mut self) -> Result<NextSample<T>, crate::Error> {
+        if self.speed_changed {
+            self.speed_changed = false;
+            return Ok(NextSample::MetadataChanged);
+        }
+        let next = T::next_sample_of(&mut self.inner)?;
+        match next {
+            NextSample::Sample(_) => {
+                self.channel_idx += 1;
+                if self.channel_idx >= self.inner.channel_count() {
+                    self.channel_idx = 0;
+                    self.advance_automation(1);
+                }
+            }
+            NextSample::MetadataChanged => self.channel_idx = 0,
+            NextSample::Paused | NextSample::Finished => (),
+        }
+        Ok(next)
     }
-}
 
-impl<S> SetStopped for AdjustableSpeed<S>
-where
-    S: Sound + SetStopped,
-{
-    fn set_stopped(&mut self) {
-        self.inner.set_stopped()
+    fn fill_buffer_of<T: SampleType>(
+        &mut self,
+        buffer: &mut [T],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        if self.speed_changed {
+            self.speed_changed = false;
+            return Ok((0, Some(NextSample::MetadataChanged)));
+        }
+        if self.automation.as_ref().is_none_or(Tweener::is_finished) {
+            return T::fill_buffer_of(&mut self.inner, buffer);
+        }
+        // Stop at the next update of the speed.
+        let channel_count = self.inner.channel_count() as usize;
+        let len = buffer.len().min(self.frames_until_update * channel_count);
+        let (num_frames, next) = T::fill_buffer_of(&mut self.inner, &mut buffer[..len])?;
+        self.advance_automation(num_frames);
+        if next.is_none() && self.speed_changed {
+            // The next samples are at the new speed.
+            self.speed_changed = false;
+            return Ok((num_frames, Some(NextSample::MetadataChanged)));
+        }
+        Ok((num_frames, next))
+    }
+
+    /// Move the automated speed on by `num_frames`.
+    fn advance_automation(&mut self, num_frames: usize) {
+        let sample_rate = self.sample_rate();
+        let Some(automation) = self.automation.as_mut().filter(|a| !a.is_finished()) else {
+            return;
+        };
+        self.frames_until_update = self.frames_until_update.saturating_sub(num_frames);
+        // A finished tween is kept so that its memory is not freed while
+        // rendering.
+        if automation.advance(num_frames as u64, sample_rate) {
+            self.speed_adjustment = automation.target();
+            self.speed_changed = true;
+        } else if self.frames_until_update == 0 {
+            self.speed_adjustment = automation.value_at(0, sample_rate);
+            self.frames_until_update = AUTOMATION_UPDATE_FRAMES;
+            self.speed_changed = true;
+        }
+    }
+
+    /// Convert a time of the inner sound to the time it takes to play at the
+    /// current speed.
+    fn to_adjusted_time(&self, inner_time: Duration) -> Duration {
+        Duration::try_from_secs_f64(inner_time.as_secs_f64() / self.speed_adjustment as f64)
+            .unwrap_or(Duration::MAX)
     }
 }
 
-impl<S> SetVolume for AdjustableSpeed<S>
+forward_to_inner!(AdjustableSpeed: SetPaused, SetStopped, SetTempo, SetPitch, SetVolume, SetPan, SetFade);
+
+/// Positions are measured in the adjusted time. For example seeking to 10
+/// seconds with a speed of 2.0 seeks the inner sound to 20 seconds.
+impl<S> Seek for AdjustableSpeed<S>
 where
-    S: Sound + SetVolume,
+    S: Sound + Seek,
 {
-    fn set_volume(&mut self, multiplier: f32) {
-        self.inner.set_volume(multiplier)
+    fn seek(&mut self, position: Duration) -> Result<Duration, crate::Error> {
+        let inner_position =
+            Duration::try_from_secs_f64(position.as_secs_f64() * self.speed_adjustment as f64)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+        let inner_position = self.inner.seek(inner_position)?;
+        Ok(self.to_adjusted_time(inner_position))
     }
 }
 
@@ -0,0 +1,545 @@
+use std::time::Duration;
+
+use crate::{sound::SampleType, utils, NextSample, Sound};
+
+use super::wrapper::forward_to_inner;
+use super::Seek;
+
+/// A sound whose tempo can be adjusted without changing its pitch.
+///
+/// This is [time stretching](https://en.wikipedia.org/wiki/Audio_time_stretching_and_pitch_scaling)
+/// unlike [SetSpeed][super::SetSpeed] which changes the pitch along with the speed.
+pub trait SetTempo {
+    /// Change the tempo.
+    ///
+    /// 1.0 is the normal tempo. 2.0 would play twice as fast, 0.5 would be
+    /// half as fast. The pitch is unchanged.
+    fn set_tempo(&mut self, multiplier: f32);
+}
+
+/// The tempo is clamped to be at least this value.
+const MIN_TEMPO: f32 = 0.01;
+
+/// Length of each overlapping segment in seconds.
+const SEGMENT_SECS: f64 = 0.03;
+
+/// How far a segment may be moved from its nominal position in seconds to
+/// line up with the previous segment.
+const TOLERANCE_SECS: f64 = 0.008;
+
+/// Candidate positions are first compared this many frames apart and then the
+/// frames around the best one.
+const COARSE_STEP: usize = 4;
+
+/// A wrapper that adjusts the tempo of the inner sound while keeping its pitch
+/// using WSOLA (Waveform Similarity Overlap-Add).
+///
+/// Segments of the inner sound are cross-faded with each other. Each segment
+/// is taken from around the position the tempo calls for, shifted to where it
+/// best matches the end of the previous segment so the cross-fades do not
+/// smear or cancel out periodic sounds such as voices.
+///
+/// Until the tempo is first changed from 1.0, samples pass through unchanged.
+/// While stretching, up to around 40ms of the inner sound is read ahead of
+/// what is being output.
+///
+/// The buffers for stretching are allocated when the wrapper is created so
+/// that rendering does not allocate, unless the channel count or sample rate
+/// of the inner sound changes.
+pub struct AdjustableTempo<S: Sound> {
+    inner: S,
+    tempo: f32,
+    stretcher: Wsola,
+    /// False when passing samples through unchanged.
+    stretching: bool,
+}
+
+impl<S> AdjustableTempo<S>
+where
+    S: Sound,
+{
+    /// Wrap `inner` such that its tempo can be adjusted.
+    pub fn new(inner: S) -> Self {
+        Self::new_with_tempo(inner, 1.0)
+    }
+
+    /// Wrap `inner` such that its tempo can be adjusted and set an initial
+    /// tempo.
+    pub fn new_with_tempo(inner: S, tempo: f32) -> Self {
+        let stretcher = Wsola::new(inner.channel_count(), inner.sample_rate());
+        AdjustableTempo {
+            inner,
+            tempo: tempo.max(MIN_TEMPO),
+            stretcher,
+            stretching: false,
+        }
+    }
+
+    /// Return the current tempo multiplier. 1.0 is the default tempo.
+    pub fn tempo(&self) -> f32 {
+        self.tempo
+    }
+
+    /// Get a reference to the wrapped inner Sound.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the wrapped inner Sound.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Unwrap and return the previously wrapped Sound. Samples read ahead
+    /// while stretching are discarded.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Returns whether samples should not be passed through, starting over
+    /// with the current metadata of inner if stretching starts.
+    fn stretching(&mut self) -> bool {
+        if !self.stretching && self.tempo != 1.0 {
+            self.stretcher
+                .reset(self.inner.channel_count(), self.inner.sample_rate());
+            self.stretching = true;
+        }
+        self.stretching
+    }
+
+    /// Compute the next block of output. Returns the sample to end the
+    /// current output with if there is no more output for now.
+    fn next_block(&mut self) -> Result<Option<NextSample>, crate::Error> {
+        let next = self.stretcher.next_block(&mut self.inner, self.tempo)?;
+        if matches!(
+            next,
+            Some(NextSample::MetadataChanged | NextSample::Finished)
+        ) {
+            // Start over with the new metadata or in case more samples become
+            // available.
+            self.stretching = false;
+        }
+        Ok(next)
+    }
+
+    fn next_sample_of<T: SampleType>(&mut self) -> Result<NextSample<T>, crate::Error> {
+        if !self.stretching() {
+            return T::next_sample_of(&mut self.inner);
+        }
+        if let Some(sample) = self.stretcher.next_output() {
+            return Ok(NextSample::Sample(T::from_f32(sample)));
+        }
+        match self.next_block()? {
+            None => self.next_sample_of(),
+            Some(next) => Ok(next.map(|_| unreachable!())),
+        }
+    }
+
+    fn fill_buffer_of<T: SampleType>(
+        &mut self,
+        buffer: &mut [T],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        if !self.stretching() {
+            return T::fill_buffer_of(&mut self.inner, buffer);
+        }
+        let channel_count = self.stretcher.channel_count;
+        let mut filled = 0;
+        loop {
+            filled += self.stretcher.copy_output(&mut buffer[filled..]);
+            if filled == buffer.len() {
+                return Ok((filled / channel_count, None));
+            }
+            if let Some(next) = self.next_block()? {
+                return Ok((filled / channel_count, Some(next)));
+            }
+        }
+    }
+
+    /// Convert a time of the inner sound to the time it takes to play at the
+    /// current tempo.
+    fn to_adjusted_time(&self, inner_time: Duration) -> Duration {
+        Duration::try_from_secs_f64(inner_time.as_secs_f64() / self.tempo as f64)
+            .unwrap_or(Duration::MAX)
+    }
+}
+
+impl<S> Sound for AdjustableTempo<S>
+where
+    S: Sound,
+{
+    fn channel_count(&self) -> u16 {
+        if self.stretching {
+            self.stretcher.channel_count as u16
+        } else {
+            self.inner.channel_count()
+        }
+    }
+
+    fn sample_rate(&self) -> u32 {
+        if self.stretching {
+            self.stretcher.sample_rate
+        } else {
+            self.inner.sample_rate()
+        }
+    }
+
+    fn next_sample(&mut self) -> Result<NextSample, crate::Error> {
+        self.next_sample_of()
+    }
+
+    fn next_sample_f32(&mut self) -> Result<NextSample<f32>, crate::Error> {
+        self.next_sample_of()
+    }
+
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.fill_buffer_of(buffer)
+    }
+
+    fn fill_buffer_f32(
+        &mut self,
+        buffer: &mut [f32],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.fill_buffer_of(buffer)
+    }
+
+    fn on_start_of_batch(&mut self) {
+        self.inner.on_start_of_batch()
+    }
+
+    /// The position in the inner sound of the frame being output, in adjusted
+    /// time.
+    fn position(&self) -> Option<Duration> {
+        let inner_position = self.inner.position()?;
+        let inner_position = if self.stretching {
+            let frames = self.stretcher.read_ahead_frames();
+            let read_ahead = utils::num_samples_to_duration(
+                frames.unsigned_abs(),
+                1,
+                self.stretcher.sample_rate,
+            );
+            if frames >= 0 {
+                inner_position.saturating_sub(read_ahead)
+            } else {
+                inner_position + read_ahead
+            }
+        } else {
+            inner_position
+        };
+        Some(self.to_adjusted_time(inner_position))
+    }
+
+    /// The total duration of the inner sound in adjusted time.
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner
+            .total_duration()
+            .map(|d| self.to_adjusted_time(d))
+    }
+}
+
+impl<S> SetTempo for AdjustableTempo<S>
+where
+    S: Sound,
+{
+    fn set_tempo(&mut self, multiplier: f32) {
+        self.tempo = multiplier.max(MIN_TEMPO);
+    }
+}
+
+forward_to_inner!(AdjustableTempo: SetPaused, SetStopped, SetSpeed, SetPitch, SetVolume, SetPan, SetFade);
+
+/// Positions are measured in the adjusted time. For example seeking to 10
+/// seconds with a tempo of 2.0 seeks the inner sound to 20 seconds.
+impl<S> Seek for AdjustableTempo<S>
+where
+    S: Sound + Seek,
+{
+    fn seek(&mut self, position: Duration) -> Result<Duration, crate::Error> {
+        let inner_position = self.inner.seek(position.mul_f32(self.tempo))?;
+        self.stretching = false;
+        Ok(self.to_adjusted_time(inner_position))
+    }
+}
+
+/// The state of the time stretching. Frame indexes count the frames pulled
+/// from inner since the Wsola was last reset.
+///
+/// The input, tail and output are sized for the most they hold when created
+/// so they never grow. The input is shifted down as frames are dropped from
+/// its front.
+struct Wsola {
+    channel_count: usize,
+    sample_rate: u32,
+    /// The number of frames output per segment. Half the segment length.
+    hop: usize,
+    /// The maximum distance in frames of a segment from its nominal position.
+    tolerance: usize,
+    /// A Hann window the length of a segment. `window[i] + window[i + hop]`
+    /// is 1.
+    window: Vec<f32>,
+    /// Interleaved input frames starting at `input_start`.
+    input: Vec<f32>,
+    input_start: u64,
+    /// The number of frames pulled from inner.
+    frames_pulled: u64,
+    /// What inner returned once it had no more frames. Silence is read past
+    /// the end until the output catches up.
+    input_end: Option<NextSample>,
+    /// Where the next segment would be taken from if it did not need to line
+    /// up with the previous segment.
+    nominal: f64,
+    /// The second half of the previous segment which is faded out while the
+    /// next segment is faded in. Empty before the first segment.
+    tail: Vec<f32>,
+    /// The current block of output and how much of it has been read.
+    output: Vec<f32>,
+    output_read: usize,
+    /// The input frame the current block of output starts at.
+    output_start: u64,
+}
+
+impl Wsola {
+    fn new(channel_count: u16, sample_rate: u32) -> Self {
+        let channel_count = channel_count as usize;
+        let hop = ((sample_rate as f64 * SEGMENT_SECS / 2.0).round() as usize).max(1);
+        // A multiple of the coarse search step
+        let tolerance = (sample_rate as f64 * TOLERANCE_SECS) as usize / COARSE_STEP * COARSE_STEP;
+        let segment_len = 2 * hop;
+        let window = (0..segment_len)
+            .map(|i| {
+                let phase = 2.0 * std::f64::consts::PI * i as f64 / segment_len as f64;
+                (0.5 - 0.5 * phase.cos()) as f32
+            })
+            .collect();
+        // The input covers the segment and the tolerance on both sides of it.
+        let input_len = (2 * tolerance + segment_len) * channel_count;
+        Wsola {
+            channel_count,
+            sample_rate,
+            hop,
+            tolerance,
+            window,
+            input: Vec::with_capacity(input_len),
+            input_start: 0,
+            frames_pulled: 0,
+            input_end: None,
+            nominal: 0.0,
+            tail: Vec::with_capacity(hop * channel_count),
+            output: Vec::with_capacity(hop * channel_count),
+            output_read: 0,
+            output_start: 0,
+        }
+    }
+
+    /// Start over from the next frame of inner. Only allocates if the channel
+    /// count or sample rate changed.
+    fn reset(&mut self, channel_count: u16, sample_rate: u32) {
+        if channel_count as usize != self.channel_count || sample_rate != self.sample_rate {
+            *self = Wsola::new(channel_count, sample_rate);
+            return;
+        }
+        self.input.clear();
+        self.input_start = 0;
+        self.frames_pulled = 0;
+        self.input_end = None;
+        self.nominal = 0.0;
+        self.tail.clear();
+        self.output.clear();
+        self.output_read = 0;
+        self.output_start = 0;
+    }
+
+    fn next_output(&mut self) -> Option<f32> {
+        let sample = self.output.get(self.output_read).copied();
+        if sample.is_some() {
+            self.output_read += 1;
+        }
+        sample
+    }
+
+    /// Copy as much output as possible to `buffer` and return how many
+    /// samples were copied.
+    fn copy_output<T: SampleType>(&mut self, buffer: &mut [T]) -> usize {
+        let remaining = &self.output[self.output_read..];
+        let count = remaining.len().min(buffer.len());
+        for (out, sample) in buffer.iter_mut().zip(&remaining[..count]) {
+            *out = T::from_f32(*sample);
+        }
+        self.output_read += count;
+        count
+    }
+
+    /// How far the frames pulled from inner are ahead of the frame being
+    /// output. Negative while outputting the silence past the end of inner.
+    fn read_ahead_frames(&self) -> i64 {
+        let frame_in_block = (self.output_read / self.channel_count) as u64;
+        self.frames_pulled as i64 - (self.output_start + frame_in_block) as i64
+    }
+
+    fn input_frames(&self) -> u64 {
+        (self.input.len() / self.channel_count) as u64
+    }
+
+    /// Drop the input before `frame`.
+    fn drop_before(&mut self, frame: u64) {
+        let count = frame
+            .saturating_sub(self.input_start)
+            .min(self.input_frames());
+        self.input.drain(..count as usize * self.channel_count);
+        self.input_start += count;
+    }
+
+    /// Pull from inner until the input covers `start..end`. Returns what inner
+    /// returned if it paused.
+    fn fill_input<S: Sound + ?Sized>(
+        &mut self,
+        inner: &mut S,
+        start: u64,
+        end: u64,
+    ) -> Result<Option<NextSample>, crate::Error> {
+        self.drop_before(start);
+        while self.input_start + self.input_frames() < end {
+            if self.input_end.is_some() {
+                // Past the end of inner so read silence.
+                if self.input.is_empty() {
+                    self.input_start = self.input_start.max(start);
+                }
+                let len = (end - self.input_start) as usize * self.channel_count;
+                self.input.resize(len, 0.0);
+                break;
+            }
+            // At a high tempo start can be far past what was pulled so skip
+            // to it without growing the input.
+            let missing = ((end - self.input_start - self.input_frames()) as usize)
+                .min(self.input.capacity() / self.channel_count - self.input_frames() as usize);
+            let filled_len = self.input.len();
+            self.input
+                .resize(filled_len + missing * self.channel_count, 0.0);
+            let (num_frames, next) = inner.fill_buffer_f32(&mut self.input[filled_len..])?;
+            self.input
+                .truncate(filled_len + num_frames * self.channel_count);
+            self.frames_pulled += num_frames as u64;
+            match next {
+                None | Some(NextSample::Sample(_)) => {}
+                Some(NextSample::Paused) => return Ok(Some(NextSample::Paused)),
+                Some(end @ (NextSample::MetadataChanged | NextSample::Finished)) => {
+                    self.input_end = Some(end)
+                }
+            }
+            self.drop_before(start);
+        }
+        Ok(None)
+    }
+
+    /// Compute the next block of output. Returns what to return instead if
+    /// there is no more output for now.
+    fn next_block<S: Sound + ?Sized>(
+        &mut self,
+        inner: &mut S,
+        tempo: f32,
+    ) -> Result<Option<NextSample>, crate::Error> {
+        let nominal = self.nominal.round() as u64;
+        if let Some(end) = self.input_end {
+            if nominal >= self.frames_pulled {
+                return Ok(Some(end));
+            }
+        }
+        let start = nominal.saturating_sub(self.tolerance as u64);
+        let end = nominal + (self.tolerance + 2 * self.hop) as u64;
+        if let Some(paused) = self.fill_input(inner, start, end)? {
+            return Ok(Some(paused));
+        }
+
+        let position = if self.tail.is_empty() {
+            nominal
+        } else {
+            self.best_position(start, nominal)
+        };
+        let cc = self.channel_count;
+        let offset = (position - self.input_start) as usize * cc;
+        let segment = &self.input[offset..offset + 2 * self.hop * cc];
+        let (first_half, second_half) = segment.split_at(self.hop * cc);
+
+        self.output.clear();
+        self.output_read = 0;
+        self.output_start = position;
+        if self.tail.is_empty() {
+            self.output.extend_from_slice(first_half);
+        } else {
+            let fade_in = self.window[..self.hop].iter();
+            let fade_out = self.window[self.hop..].iter();
+            for (((fade_in, fade_out), new), old) in fade_in
+                .zip(fade_out)
+                .zip(first_half.chunks_exact(cc))
+                .zip(self.tail.chunks_exact(cc))
+            {
+                self.output.extend(
+                    new.iter()
+                        .zip(old)
+                        .map(|(new, old)| new * fade_in + old * fade_out),
+                );
+            }
+        }
+        self.tail.clear();
+        self.tail.extend_from_slice(second_half);
+        self.nominal += self.hop as f64 * tempo as f64;
+        Ok(None)
+    }
+
+    /// Find the position within `tolerance` of `nominal` whose segment best
+    /// continues the tail of the previous segment.
+    fn best_position(&self, start: u64, nominal: u64) -> u64 {
+        let tolerance = self.tolerance as i64;
+        let coarse = (-tolerance..=tolerance)
+            .step_by(COARSE_STEP)
+            .filter_map(|delta| nominal.checked_add_signed(delta))
+            .filter(|position| *position >= start);
+        let best = self.best_of(coarse, 2, nominal);
+        let fine = best.saturating_sub(COARSE_STEP as u64 - 1)..best + COARSE_STEP as u64;
+        let fine = fine.filter(|position| {
+            *position >= start && position.abs_diff(nominal) <= self.tolerance as u64
+        });
+        self.best_of(fine, 1, best)
+    }
+
+    /// The position whose normalized cross-correlation with the tail is the
+    /// highest, comparing every `frame_step`th frame.
+    fn best_of(
+        &self,
+        positions: impl Iterator<Item = u64>,
+        frame_step: usize,
+        default: u64,
+    ) -> u64 {
+        let cc = self.channel_count;
+        let mut best = default;
+        let mut best_score = f32::NEG_INFINITY;
+        for position in positions {
+            let offset = (position - self.input_start) as usize * cc;
+            let candidate = &self.input[offset..offset + self.hop * cc];
+            let mut correlation = 0.0;
+            let mut energy = 0.0;
+            for (new, old) in candidate
+                .chunks_exact(cc)
+                .zip(self.tail.chunks_exact(cc))
+                .step_by(frame_step)
+            {
+                for (new, old) in new.iter().zip(old) {
+                    correlation += new * old;
+                    energy += new * new;
+                }
+            }
+            let score = correlation / (energy + 1e-9).sqrt();
+            if score > best_score {
+                best_score = score;
+                best = position;
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/adjustable_tempo.rs"]
+mod tests;
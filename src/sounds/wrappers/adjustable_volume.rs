@@ -1,6 +1,10 @@
-use crate::Sound;
+use std::time::Duration;
 
-use super::{SetPaused, SetSpeed, SetStopped};
+use crate::{utils, Sound};
+
+use super::automation::Tweener;
+use super::wrapper::forward_to_inner;
+use super::{Ramp, Seek, Tween};
 
 /// A sound multiplied by a linear gain adjustment.
 pub trait SetVolume {
@@ -11,13 +15,91 @@ pub trait SetVolume {
     /// double them (saturating if larger than the max value).
     ///
     /// These changes linear and 0.5 will not sound half as loud since
+    /// loudness is perceived logarithmically. See [SetVolume::set_volume_db]
+    /// and [VolumeCurve].
     fn set_volume(&mut self, multiplier: f32);
+
+    /// Change the gain in decibels. 0.0 leaves the Sound unchanged, -6.0 is
+    /// roughly half the amplitude and `f32::NEG_INFINITY` is silence.
+    fn set_volume_db(&mut self, db: f32) {
+        self.set_volume(utils::db_to_gain(db))
+    }
+
+    /// Move the gain multiplier to `multiplier` over `duration` following
+    /// `ramp`, e.g. an exponential fade.
+    fn ramp_volume(&mut self, multiplier: f32, duration: Duration, ramp: Ramp) {
+        self.start_volume_tween(Tween::ramp(multiplier, duration, ramp))
+    }
+
+    /// Animate the gain multiplier with `tween`, replacing any running tween.
+    /// The default implementation calls [set_volume][SetVolume::set_volume]
+    /// with the target of the tween.
+    fn start_volume_tween(&mut self, tween: Tween) {
+        if let Some(target) = tween.target() {
+            self.set_volume(target)
+        }
+    }
 }
 
+/// Maps the position of a volume slider from 0.0 to 1.0 to a gain multiplier
+/// so that equal steps of the slider sound like equal steps in loudness.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VolumeCurve {
+    /// The gain is the position. Most of the change in loudness happens at the
+    /// bottom of the slider.
+    Linear,
+    /// The gain is the position cubed. A cheap and close approximation of
+    /// perceived loudness over a range of about 60dB.
+    Cubic,
+    /// The position is linear in decibels from `range_db` below full volume
+    /// at a position just above 0.0 up to 0dB at 1.0. A position of 0.0 is
+    /// silence.
+    Decibels {
+        /// The range of the slider in decibels. For example 60.0.
+        range_db: f32,
+    },
+}
+
+impl VolumeCurve {
+    /// The gain multiplier for the slider `position` which is clamped to the
+    /// range 0.0 to 1.0.
+    pub fn gain(&self, position: f32) -> f32 {
+        let position = position.clamp(0.0, 1.0);
+        match self {
+            VolumeCurve::Linear => position,
+            VolumeCurve::Cubic => position * position * position,
+            VolumeCurve::Decibels { .. } if position == 0.0 => 0.0,
+            VolumeCurve::Decibels { range_db } => {
+                utils::db_to_gain(range_db.abs() * (position - 1.0))
+            }
+        }
+    }
+}
+
+/// How long volume changes take by default. Long enough to avoid audible
+/// clicks, short enough to sound instant.
+const DEFAULT_RAMP: Duration = Duration::from_millis(10);
+
 /// A wrapper that adjusts the gain of the inner sound.
+///
+/// Changes made with [set_volume][SetVolume::set_volume] are ramped linearly
+/// from the old to the new gain over a short time (10ms by default, see
+/// [AdjustableVolume::set_ramp_duration]) to avoid zipper noise and clicks.
 pub struct AdjustableVolume<S: Sound> {
     inner: S,
+    /// The gain at the end of the ramp.
     volume_adjustment: f32,
+    /// The gain of the next frame.
+    gain: f32,
+    /// The change in gain per frame while ramping.
+    step: f32,
+    ramp_frames_remaining: u64,
+    ramp_duration: Duration,
+    /// The tween started with
+    /// [start_volume_tween][SetVolume::start_volume_tween].
+    automation: Option<Tweener>,
+    /// The index in the frame of the next sample.
+    channel_idx: u16,
 }
 
 impl<S> AdjustableVolume<S>
@@ -30,23 +112,32 @@ where
     ///
     /// See `set_volume`.
     pub fn new(inner: S) -> Self {
-        AdjustableVolume {
-            inner,
-            volume_adjustment: 1.0,
-        }
+        Self::new_with_volume(inner, 1.0)
     }
 
     /// Wrap `inner` such that its volume can be adjusted and set an initial
-    /// adjustment.
+    /// adjustment. The initial adjustment applies immediately.
     ///
     /// See `set_volume`.
     pub fn new_with_volume(inner: S, volume_adjustment: f32) -> Self {
         AdjustableVolume {
             inner,
             volume_adjustment,
+            gain: volume_adjustment,
+            step: 0.0,
+            ramp_frames_remaining: 0,
+            ramp_duration: DEFAULT_RAMP,
+            automation: None,
+            channel_idx: 0,
         }
     }
 
+    /// Set how long future volume changes take. `Duration::ZERO` applies them
+    /// on the next sample.
+    pub fn set_ramp_duration(&mut self, duration: Duration) {
+        self.ramp_duration = duration;
+    }
+
     /// Get a reference to the wrapped inner Sound.
     pub fn inner(&self) -> &S {
         &self.inner
@@ -61,6 +152,72 @@ where
     pub fn into_inner(self) -> S {
         self.inner
     }
+
+    /// Move on to the gain of the next frame.
+    fn advance_frame(&mut self) {
+        let sample_rate = self.inner.sample_rate();
+        // A finished tween is kept so that its memory is not freed while
+        // rendering.
+        if let Some(automation) = self.automation.as_mut().filter(|a| !a.is_finished()) {
+            if automation.advance(1, sample_rate) {
+                self.gain = automation.target();
+            } else {
+                self.gain = automation.value_at(0, sample_rate);
+            }
+            return;
+        }
+        if self.ramp_frames_remaining > 0 {
+            self.ramp_frames_remaining -= 1;
+            self.gain = if self.ramp_frames_remaining == 0 {
+                self.volume_adjustment
+            } else {
+                self.gain + self.step
+            };
+        }
+    }
+
+    /// The gain for the next sample.
+    fn next_gain(&mut self) -> f32 {
+        let gain = self.gain;
+        self.channel_idx += 1;
+        if self.channel_idx >= self.inner.channel_count() {
+            self.channel_idx = 0;
+            self.advance_frame();
+        }
+        gain
+    }
+
+    /// Apply the gain to `num_frames` frames of `buffer`.
+    fn apply<T: Copy>(&mut self, buffer: &mut [T], num_frames: usize, adjust: fn(T, f32) -> T) {
+        let channel_count = self.inner.channel_count() as usize;
+        let buffer = &mut buffer[..num_frames * channel_count];
+        if self.ramp_frames_remaining == 0
+            && self.automation.as_ref().is_none_or(Tweener::is_finished)
+        {
+            let gain = self.gain;
+            for sample in buffer {
+                *sample = adjust(*sample, gain);
+            }
+            return;
+        }
+        for frame in buffer.chunks_exact_mut(channel_count) {
+            let gain = self.gain;
+            for sample in frame {
+                *sample = adjust(*sample, gain);
+            }
+            self.advance_frame();
+        }
+    }
+}
+
+// Since Rust 1.45, the `as` keyword performs a *saturating cast* when casting
+// from float to int.
+fn adjust_i16(sample: i16, gain: f32) -> i16 {
+    (sample as f32 * gain) as i16
+}
+
+fn adjust_f32(sample: f32, gain: f32) -> f32 {
+    sample * gain
 }
 
 impl<S> Sound for AdjustableVolume<S>
@@ -79,20 +236,59 @@ where
         let next = self.inner.next_sample()?;
         Ok(match next {
             crate::NextSample::Sample(s) => {
-                // Since Rust 1.45, the `as` keyword performs a *saturating cast*
-                // when casting from float to int.
-                let adjusted = (s as f32 * self.volume_adjustment) as i16;
-                crate::NextSample::Sample(adjusted)
+                crate::NextSample::Sample(adjust_i16(s, self.next_gain()))
+            }
+            crate::NextSample::MetadataChanged => {
+                self.channel_idx = 0;
+                next
+            }
+            crate::NextSample::Paused | crate::NextSample::Finished => next,
+        })
+    }
+
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(usize, Option<crate::NextSample>), crate::Error> {
+        let (num_frames, next) = self.inner.fill_buffer(buffer)?;
+        self.apply(buffer, num_frames, adjust_i16);
+        Ok((num_frames, next))
+    }
+
+    fn next_sample_f32(&mut self) -> Result<crate::NextSample<f32>, crate::Error> {
+        let next = self.inner.next_sample_f32()?;
+        Ok(match next {
+            crate::NextSample::Sample(s) => {
+                crate::NextSample::Sample(adjust_f32(s, self.next_gain()))
             }
-            crate::NextSample::MetadataChanged
-            | crate::NextSample::Paused
-            | crate::NextSample::Finished => next,
+            crate::NextSample::MetadataChanged => {
+                self.channel_idx = 0;
+                next
+            }
+            crate::NextSample::Paused | crate::NextSample::Finished => next,
         })
     }
 
+    fn fill_buffer_f32(
+        &mut self,
+        buffer: &mut [f32],
+    ) -> Result<(usize, Option<crate::NextSample>), crate::Error> {
+        let (num_frames, next) = self.inner.fill_buffer_f32(buffer)?;
+        self.apply(buffer, num_frames, adjust_f32);
+        Ok((num_frames, next))
+    }
+
     fn on_start_of_batch(&mut self) {
         self.inner.on_start_of_batch()
     }
+
+    fn position(&self) -> Option<Duration> {
+        self.inner.position()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
 }
 
 impl<S> AdjustableVolume<S>
@@ -100,6 +296,8 @@ where
     S: Sound,
 {
     /// Return the current gain multiplier. 1.0 is the default multiplier.
+    ///
+    /// While a change is being ramped this is the new multiplier.
     pub fn volume(&self) -> f32 {
         self.volume_adjustment
     }
@@ -111,33 +309,33 @@ where
 {
     fn set_volume(&mut self, new: f32) {
         self.volume_adjustment = new;
+        self.automation = None;
+        self.ramp_frames_remaining =
+            utils::duration_to_num_samples(self.ramp_duration, 1, self.inner.sample_rate());
+        if self.ramp_frames_remaining == 0 {
+            self.gain = new;
+        } else {
+            self.step = (new - self.gain) / self.ramp_frames_remaining as f32;
+        }
     }
-}
 
-impl<S> SetPaused for AdjustableVolume<S>
-where
-    S: Sound + SetPaused,
-{
-    fn set_paused(&mut self, paused: bool) {
-        self.inner.set_paused(paused)
+    fn start_volume_tween(&mut self, tween: Tween) {
+        let tweener = Tweener::new(self.gain, tween);
+        self.volume_adjustment = tweener.target();
+        self.ramp_frames_remaining = 0;
+        self.gain = tweener.value_at(0, self.inner.sample_rate());
+        self.automation = (!tweener.is_finished()).then_some(tweener);
     }
 }
 
-impl<S> SetStopped for AdjustableVolume<S>
-where
-    S: Sound + SetStopped,
-{
-    fn set_stopped(&mut self) {
-        self.inner.set_stopped()
-    }
-}
+forward_to_inner!(AdjustableVolume: SetPaused, SetStopped, SetSpeed, SetTempo, SetPitch, SetPan, SetFade);
 
-impl<S> SetSpeed for AdjustableVolume<S>
+impl<S> Seek for AdjustableVolume<S>
 where
-    S: Sound + SetSpeed,
+    S: Sound + Seek,
 {
-    fn set_speed(&mut self, multiplier: f32) {
-        self.inner.set_speed(multiplier)
+    fn seek(&mut self, position: Duration) -> Result<Duration, crate::Error> {
+        self.inner.seek(position)
     }
 }
 
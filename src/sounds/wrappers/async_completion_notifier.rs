@@ -1,9 +1,11 @@
 //! [AsyncCompletionNotifier] and re-export of the tokio [`oneshot`] channel it
 //! uses for convenience.
 
+use super::wrapper::forward_to_inner;
 use super::Wrapper;
 use crate::NextSample;
 use crate::Sound;
+use std::time::Duration;
 pub use tokio::sync::oneshot;
 
 /// Notify via a [tokio::sync::oneshot::Receiver] when the contained Sound has
@@ -57,9 +59,53 @@ where
         Ok(next)
     }
 
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        let (num_frames, next) = self.inner.fill_buffer(buffer)?;
+        if let Some(NextSample::Finished) = next {
+            if let Some(sender) = self.sender.take() {
+                let _res = sender.send(());
+            }
+        }
+        Ok((num_frames, next))
+    }
+
+    fn next_sample_f32(&mut self) -> Result<NextSample<f32>, crate::Error> {
+        let next = self.inner.next_sample_f32()?;
+        if let NextSample::Finished = next {
+            if let Some(sender) = self.sender.take() {
+                let _res = sender.send(());
+            }
+        }
+        Ok(next)
+    }
+
+    fn fill_buffer_f32(
+        &mut self,
+        buffer: &mut [f32],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        let (num_frames, next) = self.inner.fill_buffer_f32(buffer)?;
+        if let Some(NextSample::Finished) = next {
+            if let Some(sender) = self.sender.take() {
+                let _res = sender.send(());
+            }
+        }
+        Ok((num_frames, next))
+    }
+
     fn on_start_of_batch(&mut self) {
         self.inner.on_start_of_batch();
     }
+
+    fn position(&self) -> Option<Duration> {
+        self.inner.position()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
 }
 
 impl<S> Wrapper for AsyncCompletionNotifier<S>
@@ -80,3 +126,5 @@ where
         self.inner
     }
 }
+
+forward_to_inner!(AsyncCompletionNotifier: Seek);
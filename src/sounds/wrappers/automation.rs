@@ -0,0 +1,269 @@
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+use std::time::Duration;
+
+/// The shape of an automated parameter change such as
+/// [SetVolume::ramp_volume][super::SetVolume::ramp_volume]. These match the
+/// ramps of Web Audio's `AudioParam`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Ramp {
+    /// Change by the same amount every frame.
+    #[default]
+    Linear,
+    /// Change by the same ratio every frame. This sounds even for volume and
+    /// speed. Falls back to [Ramp::Linear] when the start or target is 0.0 or
+    /// they have different signs since there is no exponential curve between
+    /// them.
+    Exponential,
+}
+
+/// How a [Tween] moves between values. See <https://easings.net> for graphs.
+///
+/// The elastic curves overshoot the start and target values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    /// Change at a constant rate.
+    #[default]
+    Linear,
+    /// Start slowly and speed up.
+    InQuad,
+    /// Start quickly and slow down.
+    OutQuad,
+    /// Start and end slowly.
+    InOutQuad,
+    /// Like [Easing::InQuad] but more pronounced.
+    InCubic,
+    /// Like [Easing::OutQuad] but more pronounced.
+    OutCubic,
+    /// Like [Easing::InOutQuad] but more pronounced.
+    InOutCubic,
+    /// Wobble around the start before moving to the target.
+    InElastic,
+    /// Overshoot the target and wobble around it before settling.
+    OutElastic,
+    /// Wobble around both the start and the target.
+    InOutElastic,
+}
+
+impl Easing {
+    /// The eased progress for the progress `t` from 0.0 to 1.0. Returns 0.0
+    /// at the start and 1.0 at the end.
+    pub fn ease(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::InQuad => t * t,
+            Easing::OutQuad => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::InOutQuad => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (2.0 - 2.0 * t).powi(2) / 2.0
+                }
+            }
+            Easing::InCubic => t * t * t,
+            Easing::OutCubic => 1.0 - (1.0 - t).powi(3),
+            Easing::InOutCubic => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (2.0 - 2.0 * t).powi(3) / 2.0
+                }
+            }
+            _ if t == 0.0 || t == 1.0 => t,
+            Easing::InElastic => {
+                -(2.0f32.powf(10.0 * t - 10.0)) * ((10.0 * t - 10.75) * ELASTIC_PERIOD).sin()
+            }
+            Easing::OutElastic => {
+                2.0f32.powf(-10.0 * t) * ((10.0 * t - 0.75) * ELASTIC_PERIOD).sin() + 1.0
+            }
+            Easing::InOutElastic => {
+                let wave = ((20.0 * t - 11.125) * IN_OUT_ELASTIC_PERIOD).sin();
+                if t < 0.5 {
+                    -(2.0f32.powf(20.0 * t - 10.0) * wave) / 2.0
+                } else {
+                    2.0f32.powf(-20.0 * t + 10.0) * wave / 2.0 + 1.0
+                }
+            }
+        }
+    }
+}
+
+const ELASTIC_PERIOD: f32 = 2.0 * PI / 3.0;
+const IN_OUT_ELASTIC_PERIOD: f32 = 2.0 * PI / 4.5;
+
+/// A chain of changes of a numeric control, e.g. the volume with
+/// [SetVolume::start_volume_tween][super::SetVolume::start_volume_tween].
+///
+/// Each step starts from where the previous one ended, the first from the
+/// current value of the control.
+///
+/// ```
+/// # use awedio::sounds::wrappers::{Easing, Tween};
+/// # use std::time::Duration;
+/// // Swell up and fade back out.
+/// let tween = Tween::new(1.0, Duration::from_millis(500), Easing::OutCubic)
+///     .then_hold(Duration::from_secs(2))
+///     .then(0.0, Duration::from_secs(1), Easing::InQuad);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tween {
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Step {
+    /// None to hold the value.
+    target: Option<f32>,
+    duration: f64,
+    curve: Curve,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Curve {
+    Ramp(Ramp),
+    Easing(Easing),
+}
+
+impl Tween {
+    /// Move to `target` over `duration` following `easing`.
+    pub fn new(target: f32, duration: Duration, easing: Easing) -> Self {
+        Tween { steps: Vec::new() }.then(target, duration, easing)
+    }
+
+    /// Move to `target` over `duration` following `ramp`.
+    pub fn ramp(target: f32, duration: Duration, ramp: Ramp) -> Self {
+        Tween {
+            steps: vec![Step {
+                target: Some(target),
+                duration: duration.as_secs_f64(),
+                curve: Curve::Ramp(ramp),
+            }],
+        }
+    }
+
+    /// Then move to `target` over `duration` following `easing`.
+    pub fn then(mut self, target: f32, duration: Duration, easing: Easing) -> Self {
+        self.steps.push(Step {
+            target: Some(target),
+            duration: duration.as_secs_f64(),
+            curve: Curve::Easing(easing),
+        });
+        self
+    }
+
+    /// Then keep the value for `duration`.
+    pub fn then_hold(mut self, duration: Duration) -> Self {
+        self.steps.push(Step {
+            target: None,
+            duration: duration.as_secs_f64(),
+            curve: Curve::Easing(Easing::Linear),
+        });
+        self
+    }
+
+    /// The value at the end of the tween if it changes the value.
+    pub fn target(&self) -> Option<f32> {
+        self.steps.iter().rev().find_map(|step| step.target)
+    }
+}
+
+/// Keeps rounding errors from adding a frame to the end of a step.
+const TOLERANCE: f64 = 1e-9;
+
+/// Runs a [Tween] as frames are rendered.
+///
+/// Wrappers with numeric controls use this to support tweens. It can be used
+/// the same way for the controls of custom sounds such as the cutoff of a
+/// filter: call [value_at][Tweener::value_at] for each frame and
+/// [advance][Tweener::advance] by the frames rendered.
+///
+/// Time is tracked in seconds so the tween keeps its duration when the sample
+/// rate changes while it runs, e.g. when the speed itself is tweened.
+#[derive(Debug, Clone)]
+pub struct Tweener {
+    steps: VecDeque<Step>,
+    /// The value at the start of the current step.
+    start: f32,
+    /// The time into the current step in seconds.
+    elapsed: f64,
+}
+
+impl Tweener {
+    /// Run `tween` on a control whose value is currently `from`.
+    pub fn new(from: f32, tween: Tween) -> Self {
+        let mut tweener = Tweener {
+            steps: tween.steps.into(),
+            start: from,
+            elapsed: 0.0,
+        };
+        // Skip steps without a duration.
+        tweener.advance(0, 1);
+        tweener
+    }
+
+    /// Whether all steps have completed.
+    pub fn is_finished(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// The value once all steps have completed.
+    pub fn target(&self) -> f32 {
+        self.steps
+            .iter()
+            .rev()
+            .find_map(|step| step.target)
+            .unwrap_or(self.start)
+    }
+
+    /// The value `frames_ahead` frames after the current frame at
+    /// `sample_rate`.
+    pub fn value_at(&self, frames_ahead: u64, sample_rate: u32) -> f32 {
+        let mut time = self.elapsed + frames_ahead as f64 / sample_rate as f64;
+        let mut start = self.start;
+        for step in &self.steps {
+            let target = step.target.unwrap_or(start);
+            if time < step.duration - TOLERANCE {
+                return step.value(start, target, (time / step.duration) as f32);
+            }
+            time -= step.duration;
+            start = target;
+        }
+        start
+    }
+
+    /// Move on by `num_frames` at `sample_rate`. Returns true once all steps
+    /// have completed.
+    pub fn advance(&mut self, num_frames: u64, sample_rate: u32) -> bool {
+        self.elapsed += num_frames as f64 / sample_rate as f64;
+        while let Some(step) = self.steps.front() {
+            if self.elapsed < step.duration - TOLERANCE {
+                break;
+            }
+            self.elapsed = (self.elapsed - step.duration).max(0.0);
+            self.start = step.target.unwrap_or(self.start);
+            self.steps.pop_front();
+        }
+        if self.steps.is_empty() {
+            self.elapsed = 0.0;
+        }
+        self.is_finished()
+    }
+}
+
+impl Step {
+    /// The value `t` of the way through the step.
+    fn value(&self, start: f32, target: f32, t: f32) -> f32 {
+        match self.curve {
+            Curve::Ramp(Ramp::Exponential) if start * target > 0.0 => {
+                start * (target / start).powf(t)
+            }
+            Curve::Ramp(_) => start + (target - start) * t,
+            Curve::Easing(easing) => start + (target - start) * easing.ease(t),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/automation.rs"]
+mod tests;
@@ -1,19 +1,59 @@
-use crate::{NextSample, Sound};
+use std::time::Duration;
 
+use crate::{sound::SampleType, NextSample, Sound};
+
+use super::wrapper::forward_to_inner;
 use super::Wrapper;
 
+/// The most frames of inner read at a time by fill_buffer when inner has more
+/// channels. Bounds the scratch buffer so it is allocated up front.
+const SCRATCH_FRAMES: usize = 256;
+
+/// The channel counts supported by [ChannelMixMatrix::standard].
+const STANDARD_COUNTS: [u16; 5] = [1, 2, 4, 6, 8];
+
 /// Convert a Sound to have a specified number of output channels.
 /// For example convert a mono sound to stereo or vice versa.
+///
+/// Conversions between 1, 2, 4, 6 and 8 channels use the standard
+/// [ChannelMixMatrix::standard] rules. Other conversions require a custom
+/// matrix passed to [ChannelCountConverter::with_matrix]. If a conversion is
+/// not supported, an [Error::UnsupportedChannelConversion][crate::Error] is
+/// returned from next_sample.
+///
+/// The matrices for every channel count inner may change to are computed when
+/// the converter is created so that rendering does not allocate.
 pub struct ChannelCountConverter<S: Sound> {
     inner: S,
     to_count: u16,
     converter_type: ConverterType,
+    /// The matrices for each supported channel count of inner that is not
+    /// converted otherwise. The custom matrix, if any, is first so that it is
+    /// used instead of the standard rules.
+    matrices: Vec<ChannelMixMatrix>,
+    /// A single frame of inner for next_sample.
+    input: Vec<f32>,
+    /// The remaining samples of the current output frame in reverse order.
+    pending: Vec<f32>,
+    /// Holds samples of inner for fill_buffer when inner has more channels.
+    scratch: Vec<f32>,
 }
 
 enum ConverterType {
     PassThrough,
-    MonoToStereo { last_sample: Option<i16> },
+    /// The sample still to be repeated. Stored as f32 so that it can be
+    /// returned from both next_sample and next_sample_f32 without loss.
+    MonoToStereo {
+        last_sample: Option<f32>,
+    },
     StereoToMono,
+    Matrix {
+        /// The index of the matrix in `matrices`.
+        index: usize,
+    },
+    Unsupported {
+        from_count: u16,
+    },
 }
 
 impl<S> ChannelCountConverter<S>
@@ -21,68 +61,104 @@ where
     S: Sound,
 {
     /// Wrap `inner` such that it will output `to_count` channels.
+    ///
+    /// If the conversion is not supported, the error is returned from
+    /// next_sample. Use [ChannelCountConverter::try_new] to get the error
+    /// immediately.
     pub fn new(inner: S, to_count: u16) -> ChannelCountConverter<S> {
-        let converter_type = Self::get_type(inner.channel_count(), to_count);
+        Self::with_matrices(inner, to_count, None)
+    }
+
+    /// Wrap `inner` such that it will output `to_count` channels or return an
+    /// error if that conversion is not supported.
+    pub fn try_new(inner: S, to_count: u16) -> Result<ChannelCountConverter<S>, crate::Error> {
+        let converter = Self::new(inner, to_count);
+        if let ConverterType::Unsupported { from_count } = converter.converter_type {
+            return Err(crate::Error::UnsupportedChannelConversion {
+                from: from_count,
+                to: to_count,
+            });
+        }
+        Ok(converter)
+    }
+
+    /// Wrap `inner` such that it is mixed to `matrix.to_count()` channels using
+    /// `matrix`.
+    ///
+    /// The matrix is used while inner has `matrix.from_count()` channels.
+    /// The standard rules are used if inner changes to a different number of
+    /// channels.
+    pub fn with_matrix(inner: S, matrix: ChannelMixMatrix) -> ChannelCountConverter<S> {
+        let to_count = matrix.to_count();
+        Self::with_matrices(inner, to_count, Some(matrix))
+    }
 
+    fn with_matrices(
+        inner: S,
+        to_count: u16,
+        custom_matrix: Option<ChannelMixMatrix>,
+    ) -> ChannelCountConverter<S> {
+        let custom_from_count = custom_matrix.as_ref().map(ChannelMixMatrix::from_count);
+        let mut matrices: Vec<ChannelMixMatrix> = custom_matrix.into_iter().collect();
+        for from_count in STANDARD_COUNTS {
+            let converted_otherwise = from_count == to_count
+                || (from_count, to_count) == (1, 2)
+                || (from_count, to_count) == (2, 1);
+            if custom_from_count == Some(from_count) || converted_otherwise {
+                continue;
+            }
+            if let Ok(matrix) = ChannelMixMatrix::standard(from_count, to_count) {
+                matrices.push(matrix);
+            }
+        }
+
+        let max_from_count = matrices.iter().map(|m| m.from_count()).max().unwrap_or(0);
+        let converter_type = Self::get_type(inner.channel_count(), to_count, &matrices);
         ChannelCountConverter {
             inner,
             to_count,
             converter_type,
+            input: Vec::with_capacity(max_from_count as usize),
+            pending: Vec::with_capacity(to_count as usize),
+            // Enough for StereoToMono and each of the matrices.
+            scratch: Vec::with_capacity(SCRATCH_FRAMES * max_from_count.max(2) as usize),
+            matrices,
         }
     }
 
-    fn get_type(from_count: u16, to_count: u16) -> ConverterType {
-        if from_count == to_count {
-            ConverterType::PassThrough
-        } else if from_count == 1 && to_count == 2 {
-            ConverterType::MonoToStereo { last_sample: None }
-        } else if from_count == 2 && to_count == 1 {
-            ConverterType::StereoToMono
-        } else {
-            // Can implement more conversions like
-            // https://developer.mozilla.org/en-US/docs/Web/API/Web_Audio_API/Basic_concepts_behind_Web_Audio_API#up-mixing_and_down-mixing
-            todo!(
-                "ChannelCountConverter for {} to {} channels not implemented.",
-                from_count,
-                to_count
-            );
+    fn get_type(from_count: u16, to_count: u16, matrices: &[ChannelMixMatrix]) -> ConverterType {
+        if let Some(index) = matrices.iter().position(|m| m.from_count() == from_count) {
+            return ConverterType::Matrix { index };
+        }
+        match (from_count, to_count) {
+            _ if from_count == to_count => ConverterType::PassThrough,
+            (1, 2) => ConverterType::MonoToStereo { last_sample: None },
+            (2, 1) => ConverterType::StereoToMono,
+            _ => ConverterType::Unsupported { from_count },
         }
     }
 
     // We could save the metadata of the inner Source and only return MetadataChange
     // if the metadata change is something we can't handle (i.e. a Rate Change).
-    fn handle_possible_channel_count_change(&mut self, next: NextSample) {
+    fn handle_possible_channel_count_change<T>(&mut self, next: NextSample<T>) {
         if let NextSample::MetadataChanged = next {
             let from_count = self.inner.channel_count();
-            self.converter_type = Self::get_type(from_count, self.to_count);
+            self.converter_type = Self::get_type(from_count, self.to_count, &self.matrices);
+            self.pending.clear();
         }
     }
 
-    /// Unwrap the inner Sound.
-    ///
-    /// It is guaranteed that the inner Sound is at the start of a Frame.
-    /// (i.e. the inner sound has not been partially incremented inside a frame)
-    pub fn into_inner(self) -> S {
-        self.inner
-    }
-}
-
-impl<S> Sound for ChannelCountConverter<S>
-where
-    S: Sound,
-{
-    fn channel_count(&self) -> u16 {
-        self.to_count
-    }
-
-    fn sample_rate(&self) -> u32 {
-        self.inner.sample_rate()
+    fn unsupported_error(&self, from_count: u16) -> crate::Error {
+        crate::Error::UnsupportedChannelConversion {
+            from: from_count,
+            to: self.to_count,
+        }
     }
 
-    fn next_sample(&mut self) -> Result<NextSample, crate::Error> {
+    fn next_sample_of<T: SampleType>(&mut self) -> Result<NextSample<T>, crate::Error> {
         match &mut self.converter_type {
             ConverterType::PassThrough => {
-                let next = self.inner.next_sample()?;
+                let next = T::next_sample_of(&mut self.inner)?;
                 self.handle_possible_channel_count_change(next);
                 Ok(next)
             }
@@ -90,14 +166,14 @@ where
                 ref mut last_sample,
             } => {
                 if let Some(sample) = last_sample {
-                    let sample = *sample;
+                    let sample = T::from_f32(*sample);
                     *last_sample = None;
                     Ok(NextSample::Sample(sample))
                 } else {
-                    let next = self.inner.next_sample()?;
+                    let next = T::next_sample_of(&mut self.inner)?;
                     match next {
                         NextSample::Sample(sample) => {
-                            *last_sample = Some(sample);
+                            *last_sample = Some(sample.to_f32());
                         }
                         NextSample::MetadataChanged => {} // handled below
                         NextSample::Paused | NextSample::Finished => {} // Just pass through
@@ -107,7 +183,7 @@ where
                 }
             }
             ConverterType::StereoToMono => {
-                let next1 = self.inner.next_sample()?;
+                let next1 = T::next_sample_of(&mut self.inner)?;
                 self.handle_possible_channel_count_change(next1);
                 let sample1 = match next1 {
                     NextSample::Sample(s) => s,
@@ -115,7 +191,7 @@ where
                         return Ok(next1);
                     }
                 };
-                let next2 = self.inner.next_sample()?;
+                let next2 = T::next_sample_of(&mut self.inner)?;
                 self.handle_possible_channel_count_change(next2);
                 let sample2 = match next2 {
                     NextSample::Sample(s) => s,
@@ -125,15 +201,176 @@ where
                 };
 
                 // Get the average of the two
-                let avg = ((sample1 as i32 + sample2 as i32) / 2) as i16;
-                Ok(NextSample::Sample(avg))
+                Ok(NextSample::Sample(T::average(sample1, sample2)))
             }
+            ConverterType::Matrix { index } => {
+                if let Some(sample) = self.pending.pop() {
+                    return Ok(NextSample::Sample(T::from_f32(sample)));
+                }
+                let matrix = &self.matrices[*index];
+                // Pull a whole frame at once so it can be mixed.
+                self.input.resize(matrix.from_count() as usize, 0.0);
+                let (num_frames, next) = self.inner.fill_buffer_f32(&mut self.input)?;
+                if num_frames == 0 {
+                    let next = not_a_sample(next.expect("no frames without a reason"));
+                    self.handle_possible_channel_count_change(next);
+                    return Ok(next);
+                }
+                self.pending.resize(matrix.to_count() as usize, 0.0);
+                matrix.apply(&self.input, &mut self.pending);
+                self.pending.reverse();
+                let sample = self.pending.pop().expect("at least one output channel");
+                Ok(NextSample::Sample(T::from_f32(sample)))
+            }
+            ConverterType::Unsupported { from_count } => {
+                let from_count = *from_count;
+                Err(self.unsupported_error(from_count))
+            }
+        }
+    }
+
+    fn fill_buffer_of<T: SampleType>(
+        &mut self,
+        buffer: &mut [T],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        let (num_frames, next) = match &mut self.converter_type {
+            ConverterType::PassThrough => T::fill_buffer_of(&mut self.inner, buffer)?,
+            ConverterType::MonoToStereo { last_sample } => {
+                debug_assert!(last_sample.is_none());
+                // Fill the first half then spread out each sample from the back
+                // so nothing is overwritten before it is copied.
+                let half = buffer.len() / 2;
+                let (num_frames, next) = T::fill_buffer_of(&mut self.inner, &mut buffer[..half])?;
+                for idx in (0..num_frames).rev() {
+                    buffer[idx * 2] = buffer[idx];
+                    buffer[idx * 2 + 1] = buffer[idx];
+                }
+                (num_frames, next)
+            }
+            ConverterType::StereoToMono => {
+                fill_in_chunks(
+                    &mut self.inner,
+                    &mut self.scratch,
+                    buffer,
+                    2,
+                    1,
+                    |input, output| {
+                        // Convert first so the result is the same as next_sample.
+                        output[0] = T::average(T::from_f32(input[0]), T::from_f32(input[1]));
+                    },
+                )?
+            }
+            ConverterType::Matrix { index } => {
+                debug_assert!(self.pending.is_empty());
+                let matrix = &self.matrices[*index];
+                let from_count = matrix.from_count() as usize;
+                let to_count = matrix.to_count() as usize;
+                fill_in_chunks(
+                    &mut self.inner,
+                    &mut self.scratch,
+                    buffer,
+                    from_count,
+                    to_count,
+                    |input, output| matrix.apply(input, output),
+                )?
+            }
+            ConverterType::Unsupported { from_count } => {
+                let from_count = *from_count;
+                return Err(self.unsupported_error(from_count));
+            }
+        };
+        if let Some(next) = next {
+            self.handle_possible_channel_count_change(next);
+        }
+        Ok((num_frames, next))
+    }
+
+    /// Unwrap the inner Sound.
+    ///
+    /// It is guaranteed that the inner Sound is at the start of a Frame.
+    /// (i.e. the inner sound has not been partially incremented inside a frame)
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+/// Fill `buffer` with frames of `to_count` channels converted by `convert`
+/// from frames of `from_count` channels read from `inner` through `scratch`
+/// at most [SCRATCH_FRAMES] at a time.
+fn fill_in_chunks<S: Sound, T>(
+    inner: &mut S,
+    scratch: &mut Vec<f32>,
+    buffer: &mut [T],
+    from_count: usize,
+    to_count: usize,
+    mut convert: impl FnMut(&[f32], &mut [T]),
+) -> Result<(usize, Option<NextSample>), crate::Error> {
+    let total_frames = buffer.len() / to_count;
+    let mut num_frames = 0;
+    while num_frames < total_frames {
+        let chunk_frames = (total_frames - num_frames).min(SCRATCH_FRAMES);
+        scratch.resize(chunk_frames * from_count, 0.0);
+        let (filled, next) = inner.fill_buffer_f32(scratch)?;
+        for (input, output) in scratch
+            .chunks_exact(from_count)
+            .zip(buffer[num_frames * to_count..].chunks_exact_mut(to_count))
+            .take(filled)
+        {
+            convert(input, output);
         }
+        num_frames += filled;
+        if next.is_some() {
+            return Ok((num_frames, next));
+        }
+    }
+    Ok((num_frames, None))
+}
+
+impl<S> Sound for ChannelCountConverter<S>
+where
+    S: Sound,
+{
+    fn channel_count(&self) -> u16 {
+        self.to_count
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn next_sample(&mut self) -> Result<NextSample, crate::Error> {
+        self.next_sample_of()
+    }
+
+    fn next_sample_f32(&mut self) -> Result<NextSample<f32>, crate::Error> {
+        self.next_sample_of()
+    }
+
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.fill_buffer_of(buffer)
+    }
+
+    fn fill_buffer_f32(
+        &mut self,
+        buffer: &mut [f32],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.fill_buffer_of(buffer)
     }
 
     fn on_start_of_batch(&mut self) {
         self.inner.on_start_of_batch()
     }
+
+    fn position(&self) -> Option<Duration> {
+        self.inner.position()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
 }
 
 impl<S: Sound> Wrapper for ChannelCountConverter<S> {
@@ -151,3 +388,261 @@ impl<S: Sound> Wrapper for ChannelCountConverter<S> {
         self.inner
     }
 }
+
+forward_to_inner!(ChannelCountConverter: Seek);
+
+/// Convert a NextSample that is known not to be a sample to a different
+/// sample type.
+fn not_a_sample<T>(next: NextSample) -> NextSample<T> {
+    match next {
+        NextSample::Sample(_) => unreachable!(),
+        NextSample::MetadataChanged => NextSample::MetadataChanged,
+        NextSample::Paused => NextSample::Paused,
+        NextSample::Finished => NextSample::Finished,
+    }
+}
+
+const SQRT_HALF: f32 = std::f32::consts::FRAC_1_SQRT_2;
+
+/// The gain of each input channel in each output channel when converting
+/// between channel counts.
+///
+/// Output channel `o` is the sum of each input channel `i` multiplied by the
+/// coefficient at `o * from_count + i`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelMixMatrix {
+    from_count: u16,
+    to_count: u16,
+    coefficients: Vec<f32>,
+}
+
+impl ChannelMixMatrix {
+    /// Create a matrix from `coefficients` in row-major order where each row
+    /// is an output channel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `coefficients.len()` is not `from_count * to_count` or either
+    /// count is 0.
+    pub fn new(from_count: u16, to_count: u16, coefficients: Vec<f32>) -> Self {
+        assert!(from_count > 0 && to_count > 0);
+        assert_eq!(
+            coefficients.len(),
+            from_count as usize * to_count as usize,
+            "channel mix matrix must have from_count * to_count coefficients"
+        );
+        ChannelMixMatrix {
+            from_count,
+            to_count,
+            coefficients,
+        }
+    }
+
+    /// The standard up-mixing and down-mixing rules between 1, 2, 4, 6 and 8
+    /// channels.
+    ///
+    /// Conversions between 1 (mono), 2 (L, R), 4 (L, R, SL, SR) and 6 (L, R,
+    /// C, LFE, SL, SR) channels follow the [Web Audio speaker
+    /// layouts](https://webaudio.github.io/web-audio-api/#ChannelLayouts).
+    /// 8 channels are 7.1 (L, R, C, LFE, BL, BR, SL, SR). 7.1 is down-mixed to
+    /// 5.1 by mixing the back and side channels of each side with a gain of
+    /// sqrt(1/2) and 5.1 is up-mixed to 7.1 by playing the surround channels
+    /// on the back channels.
+    ///
+    /// Returns an [Error::UnsupportedChannelConversion][crate::Error] for any
+    /// other channel counts.
+    pub fn standard(from_count: u16, to_count: u16) -> Result<Self, crate::Error> {
+        let unsupported = || crate::Error::UnsupportedChannelConversion {
+            from: from_count,
+            to: to_count,
+        };
+        if from_count == to_count {
+            return if from_count == 0 {
+                Err(unsupported())
+            } else {
+                Ok(Self::identity(from_count))
+            };
+        }
+        match (from_count, to_count) {
+            (8, 6) => Ok(Self::seven_one_to_five_one()),
+            (6, 8) => Ok(Self::five_one_to_seven_one()),
+            (8, to_count) => {
+                let to = Self::web_audio(6, to_count).ok_or_else(unsupported)?;
+                Ok(to.after(&Self::seven_one_to_five_one()))
+            }
+            (from_count, 8) => {
+                let from = Self::web_audio(from_count, 6).ok_or_else(unsupported)?;
+                Ok(Self::five_one_to_seven_one().after(&from))
+            }
+            _ => Self::web_audio(from_count, to_count).ok_or_else(unsupported),
+        }
+    }
+
+    /// The number of input channels.
+    pub fn from_count(&self) -> u16 {
+        self.from_count
+    }
+
+    /// The number of output channels.
+    pub fn to_count(&self) -> u16 {
+        self.to_count
+    }
+
+    /// The coefficients in row-major order where each row is an output
+    /// channel.
+    pub fn coefficients(&self) -> &[f32] {
+        &self.coefficients
+    }
+
+    fn identity(count: u16) -> Self {
+        let mut matrix = Self::zeros(count, count);
+        for channel in 0..count as usize {
+            matrix.set(channel, channel, 1.0);
+        }
+        matrix
+    }
+
+    fn zeros(from_count: u16, to_count: u16) -> Self {
+        Self::new(
+            from_count,
+            to_count,
+            vec![0.0; from_count as usize * to_count as usize],
+        )
+    }
+
+    fn set(&mut self, output: usize, input: usize, value: f32) {
+        self.coefficients[output * self.from_count as usize + input] = value;
+    }
+
+    fn get(&self, output: usize, input: usize) -> f32 {
+        self.coefficients[output * self.from_count as usize + input]
+    }
+
+    /// A matrix that applies `first` and then `self`.
+    fn after(&self, first: &ChannelMixMatrix) -> ChannelMixMatrix {
+        debug_assert_eq!(first.to_count, self.from_count);
+        let mut combined = Self::zeros(first.from_count, self.to_count);
+        for output in 0..self.to_count as usize {
+            for input in 0..first.from_count as usize {
+                let value = (0..self.from_count as usize)
+                    .map(|mid| self.get(output, mid) * first.get(mid, input))
+                    .sum();
+                combined.set(output, input, value);
+            }
+        }
+        combined
+    }
+
+    /// The rules defined by Web Audio between 1, 2, 4 and 6 channels.
+    fn web_audio(from_count: u16, to_count: u16) -> Option<Self> {
+        // Channel indexes
+        const L: usize = 0;
+        const R: usize = 1;
+        const C: usize = 2;
+        const QUAD_SL: usize = 2;
+        const QUAD_SR: usize = 3;
+        const SL: usize = 4;
+        const SR: usize = 5;
+
+        if from_count == to_count && [1, 2, 4, 6].contains(&from_count) {
+            return Some(Self::identity(from_count));
+        }
+        let mut m = Self::zeros(from_count, to_count);
+        match (from_count, to_count) {
+            // Up-mixing
+            (1, 2) | (1, 4) => {
+                m.set(L, 0, 1.0);
+                m.set(R, 0, 1.0);
+            }
+            (1, 6) => m.set(C, 0, 1.0),
+            (2, 4) | (2, 6) => {
+                m.set(L, L, 1.0);
+                m.set(R, R, 1.0);
+            }
+            (4, 6) => {
+                m.set(L, L, 1.0);
+                m.set(R, R, 1.0);
+                m.set(SL, QUAD_SL, 1.0);
+                m.set(SR, QUAD_SR, 1.0);
+            }
+            // Down-mixing
+            (2, 1) => {
+                m.set(0, L, 0.5);
+                m.set(0, R, 0.5);
+            }
+            (4, 1) => {
+                for input in 0..4 {
+                    m.set(0, input, 0.25);
+                }
+            }
+            (6, 1) => {
+                m.set(0, L, SQRT_HALF);
+                m.set(0, R, SQRT_HALF);
+                m.set(0, C, 1.0);
+                m.set(0, SL, 0.5);
+                m.set(0, SR, 0.5);
+            }
+            (4, 2) => {
+                m.set(L, L, 0.5);
+                m.set(L, QUAD_SL, 0.5);
+                m.set(R, R, 0.5);
+                m.set(R, QUAD_SR, 0.5);
+            }
+            (6, 2) => {
+                m.set(L, L, 1.0);
+                m.set(L, C, SQRT_HALF);
+                m.set(L, SL, SQRT_HALF);
+                m.set(R, R, 1.0);
+                m.set(R, C, SQRT_HALF);
+                m.set(R, SR, SQRT_HALF);
+            }
+            (6, 4) => {
+                m.set(L, L, 1.0);
+                m.set(L, C, SQRT_HALF);
+                m.set(R, R, 1.0);
+                m.set(R, C, SQRT_HALF);
+                m.set(QUAD_SL, SL, 1.0);
+                m.set(QUAD_SR, SR, 1.0);
+            }
+            _ => return None,
+        }
+        Some(m)
+    }
+
+    fn seven_one_to_five_one() -> Self {
+        let mut m = Self::zeros(8, 6);
+        for channel in 0..4 {
+            m.set(channel, channel, 1.0);
+        }
+        // Back and side of each side into surround
+        m.set(4, 4, SQRT_HALF);
+        m.set(4, 6, SQRT_HALF);
+        m.set(5, 5, SQRT_HALF);
+        m.set(5, 7, SQRT_HALF);
+        m
+    }
+
+    fn five_one_to_seven_one() -> Self {
+        let mut m = Self::zeros(6, 8);
+        for channel in 0..6 {
+            m.set(channel, channel, 1.0);
+        }
+        m
+    }
+
+    /// Mix a single frame.
+    fn apply<T: SampleType>(&self, input: &[f32], output: &mut [T]) {
+        let from_count = self.from_count as usize;
+        for (out, row) in output
+            .iter_mut()
+            .zip(self.coefficients.chunks_exact(from_count))
+        {
+            let sum: f32 = row.iter().zip(input).map(|(c, s)| c * s).sum();
+            *out = T::from_f32(sum);
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/channel_count_converter.rs"]
+mod tests;
@@ -1,12 +1,28 @@
+use crate::deferred::{Deferred, Garbage};
+use crate::sound::SampleType;
 use crate::sounds::wrappers::SetPaused;
 use crate::sounds::wrappers::SetVolume;
+use crate::NextSample;
 use crate::Sound;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::mpsc;
+use std::sync::Arc;
+use std::time::Duration;
 
 use super::stoppable::SetStopped;
+use super::wrapper::forward_to_inner;
 use super::AddSound;
 use super::ClearSounds;
+use super::Easing;
+use super::Ramp;
+use super::Seek;
+use super::SetFade;
+use super::SetPan;
+use super::SetPitch;
 use super::SetSpeed;
+use super::SetTempo;
+use super::Tween;
 use super::Wrapper;
 
 /// Wrap a Sound so that it can be controlled via a [Controller] even after it
@@ -17,25 +33,90 @@ use super::Wrapper;
 /// the controller has dropped and all sounds have played will Finished be
 /// returned. If you want the Controllable to Finish immediately when the inner
 /// Sound has completed, use [finish_with_inner][Controllable].
+///
+/// Commands are applied at the start of the next batch unless they were sent
+/// with [Controller::send_command_at] in which case they are applied at the
+/// exact frame, even within a batch.
+///
+/// Commands are sent over a bounded queue which the Controllable reads
+/// without locking or allocating. Commands sent while it is full, for example
+/// before the backend has started, wait in an unbounded overflow queue instead
+/// which may free memory on the renderer thread when it is read. Once a
+/// [Manager][crate::manager::Manager] has been created, commands are freed on
+/// a background thread after they have been applied.
 pub struct Controllable<S: Sound> {
     inner: S,
-    command_receiver: mpsc::Receiver<Command<S>>,
+    command_receiver: mpsc::Receiver<Message<S>>,
+    overflow_receiver: mpsc::Receiver<Message<S>>,
+    /// The number of commands in the overflow queue, shared with the
+    /// Controllers.
+    overflowed: Arc<AtomicUsize>,
     finished: bool,
+    /// Commands waiting for their frame in the order they are applied. Space
+    /// for TIMED_COMMAND_CAPACITY commands is reserved up front.
+    timed_commands: VecDeque<(u64, Command<S>)>,
+    /// The number of frames output so far.
+    frame: u64,
+    /// `frame` as of the start of the last batch, shared with the Controllers.
+    shared_frame: Arc<AtomicU64>,
+    /// The frame `frame` is set to at the start of each batch.
+    followed_frame: Option<Arc<AtomicU64>>,
+    /// The index in the frame of the next sample.
+    channel_idx: u16,
+    deferred: Deferred,
 }
 
+/// The number of commands that can be waiting to be received by a
+/// Controllable created with [Controllable::new].
+const DEFAULT_COMMAND_CAPACITY: usize = 256;
+
+/// The number of timed commands a Controllable can hold before it has to
+/// allocate.
+const TIMED_COMMAND_CAPACITY: usize = 64;
+
 impl<S> Controllable<S>
 where
     S: Sound,
 {
-    /// Wrap `inner` so it can be controlled.
+    /// Wrap `inner` so it can be controlled with a queue of 256 commands.
     pub fn new(inner: S) -> (Self, Controller<S>) {
-        let (command_sender, command_receiver) = mpsc::channel::<Command<S>>();
+        Self::with_command_capacity(inner, DEFAULT_COMMAND_CAPACITY)
+    }
+
+    /// Wrap `inner` so it can be controlled. Up to `capacity` commands can be
+    /// sent between batches before further commands go to the overflow queue
+    /// or are rejected by [Controller::try_send_command].
+    ///
+    /// Panics
+    ///
+    /// Panics if `capacity` is 0.
+    pub fn with_command_capacity(inner: S, capacity: usize) -> (Self, Controller<S>) {
+        // A capacity of 0 would make a rendezvous channel which commands can
+        // never be sent on without waiting.
+        assert!(capacity > 0, "the command capacity must be at least 1");
+        let (command_sender, command_receiver) = mpsc::sync_channel::<Message<S>>(capacity);
+        let (overflow_sender, overflow_receiver) = mpsc::channel::<Message<S>>();
+        let overflowed = Arc::new(AtomicUsize::new(0));
+        let shared_frame = Arc::new(AtomicU64::new(0));
         let controllable = Controllable {
             inner,
             command_receiver,
+            overflow_receiver,
+            overflowed: overflowed.clone(),
             finished: false,
+            timed_commands: VecDeque::with_capacity(TIMED_COMMAND_CAPACITY),
+            frame: 0,
+            shared_frame: shared_frame.clone(),
+            followed_frame: None,
+            channel_idx: 0,
+            deferred: Deferred::current(),
+        };
+        let controller = Controller {
+            command_sender,
+            overflow_sender,
+            overflowed,
+            shared_frame,
         };
-        let controller = Controller { command_sender };
 
         (controllable, controller)
     }
@@ -45,6 +126,124 @@ where
     pub fn finish_with_inner(&mut self) {
         self.finished = true;
     }
+
+    /// The number of frames output so far. This is the clock of
+    /// [Controller::send_command_at].
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Move the clock of timed commands to `frame`. Must be called before
+    /// [on_start_of_batch][Sound::on_start_of_batch]. The Renderer uses this
+    /// so that the clock counts the frames of silence output while the inner
+    /// sound is paused or has nothing to play.
+    pub(crate) fn set_frame(&mut self, frame: u64) {
+        self.frame = frame;
+    }
+
+    /// Free applied commands on `deferred` instead of the renderer thread.
+    pub(crate) fn set_deferred(&mut self, deferred: Deferred) {
+        self.deferred = deferred;
+    }
+
+    /// Set the clock of timed commands to `frame` at the start of each batch.
+    /// Buses follow the frame of their parent so that the `_at` methods of
+    /// their sound handles use the same clock as those of the Manager.
+    pub(crate) fn follow_frame(&mut self, frame: Arc<AtomicU64>) {
+        self.followed_frame = Some(frame);
+    }
+
+    fn receive(&mut self, message: Message<S>) {
+        match message {
+            Message::Command(Some(frame), command) if frame > self.frame => {
+                self.add_timed_command(frame, command)
+            }
+            Message::Command(_, command) => self.apply(command),
+            Message::Call(function) => function(&mut self.inner),
+        }
+    }
+
+    fn add_timed_command(&mut self, frame: u64, command: Command<S>) {
+        // Commands for the same frame are applied in the order they were sent.
+        let idx = self.timed_commands.partition_point(|(f, _)| *f <= frame);
+        self.timed_commands.insert(idx, (frame, command));
+    }
+
+    /// Apply the timed commands whose frame has been reached.
+    fn apply_due_commands(&mut self) {
+        while matches!(self.timed_commands.front(), Some((frame, _)) if *frame <= self.frame) {
+            if let Some((_, command)) = self.timed_commands.pop_front() {
+                self.apply(command);
+            }
+        }
+    }
+
+    fn apply(&mut self, mut command: Command<S>) {
+        command.run(&mut self.inner);
+        self.deferred.drop(command);
+    }
+
+    /// Convert Finished to Paused unless finishing with the inner sound.
+    fn map_finished<T>(&self, next: NextSample<T>) -> NextSample<T> {
+        match next {
+            // Since this is controllable we might add another sound later.
+            // Ideally we would do this only if the inner sound can have sounds
+            // added to it but I don't think we can branch on S: AddSound here.
+            // We could add a Sound::is_addable but lets avoid that until we see
+            // a reason why it is necessary.
+            NextSample::Finished if !self.finished => NextSample::Paused,
+            _ => next,
+        }
+    }
+
+    fn next_sample_of<T: SampleType>(&mut self) -> Result<NextSample<T>, crate::Error> {
+        if self.channel_idx == 0 {
+            self.apply_due_commands();
+        }
+        let next = T::next_sample_of(&mut self.inner)?;
+        match next {
+            NextSample::Sample(_) => {
+                self.channel_idx += 1;
+                if self.channel_idx >= self.inner.channel_count() {
+                    self.channel_idx = 0;
+                    self.frame += 1;
+                }
+            }
+            NextSample::MetadataChanged => self.channel_idx = 0,
+            NextSample::Paused | NextSample::Finished => (),
+        }
+        Ok(self.map_finished(next))
+    }
+
+    /// Fill `buffer` in parts that end at the frames of timed commands so
+    /// that each command is applied exactly at its frame.
+    fn fill_buffer_of<T: SampleType>(
+        &mut self,
+        buffer: &mut [T],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        let mut num_frames = 0;
+        let mut offset: usize = 0;
+        loop {
+            self.apply_due_commands();
+            let channel_count = self.inner.channel_count() as usize;
+            let end = match self.timed_commands.front() {
+                Some((frame, _)) => {
+                    let frames_until = (frame - self.frame).min(usize::MAX as u64) as usize;
+                    buffer
+                        .len()
+                        .min(offset.saturating_add(frames_until.saturating_mul(channel_count)))
+                }
+                None => buffer.len(),
+            };
+            let (new_frames, next) = T::fill_buffer_of(&mut self.inner, &mut buffer[offset..end])?;
+            num_frames += new_frames;
+            offset += new_frames * channel_count;
+            self.frame += new_frames as u64;
+            if next.is_some() || end == buffer.len() {
+                return Ok((num_frames, next.map(|next| self.map_finished(next))));
+            }
+        }
+    }
 }
 
 impl<S> Sound for Controllable<S>
@@ -60,30 +259,34 @@ where
     }
 
     fn next_sample(&mut self) -> Result<crate::NextSample, crate::Error> {
-        let next = self.inner.next_sample()?;
-        match next {
-            crate::NextSample::Sample(_)
-            | crate::NextSample::MetadataChanged
-            | crate::NextSample::Paused => Ok(next),
-            // Since this is controllable we might add another sound later.
-            // Ideally we would do this only if the inner sound can have sounds
-            // added to it but I don't think we can branch on S: AddSound here.
-            // We could add a Sound::is_addable but lets avoid that until we see
-            // a reason why it is necessary.
-            crate::NextSample::Finished => {
-                if self.finished {
-                    Ok(crate::NextSample::Finished)
-                } else {
-                    Ok(crate::NextSample::Paused)
-                }
-            }
-        }
+        self.next_sample_of()
+    }
+
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(usize, Option<crate::NextSample>), crate::Error> {
+        self.fill_buffer_of(buffer)
+    }
+
+    fn next_sample_f32(&mut self) -> Result<crate::NextSample<f32>, crate::Error> {
+        self.next_sample_of()
+    }
+
+    fn fill_buffer_f32(
+        &mut self,
+        buffer: &mut [f32],
+    ) -> Result<(usize, Option<crate::NextSample>), crate::Error> {
+        self.fill_buffer_of(buffer)
     }
 
     fn on_start_of_batch(&mut self) {
+        if let Some(frame) = &self.followed_frame {
+            self.frame = frame.load(Ordering::Relaxed);
+        }
         loop {
             match self.command_receiver.try_recv() {
-                Ok(command) => command(&mut self.inner),
+                Ok(message) => self.receive(message),
                 Err(mpsc::TryRecvError::Empty) => break,
                 Err(mpsc::TryRecvError::Disconnected) => {
                     self.finished = true;
@@ -91,8 +294,24 @@ where
                 }
             }
         }
+        // Commands only overflow after the queue filled up so they are newer
+        // than those in the queue.
+        while let Ok(message) = self.overflow_receiver.try_recv() {
+            self.overflowed.fetch_sub(1, Ordering::Relaxed);
+            self.receive(message);
+        }
+        self.apply_due_commands();
+        self.shared_frame.store(self.frame, Ordering::Relaxed);
         self.inner.on_start_of_batch();
     }
+
+    fn position(&self) -> Option<Duration> {
+        self.inner.position()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
 }
 
 impl<S> Wrapper for Controllable<S>
@@ -114,12 +333,57 @@ where
     }
 }
 
-// TODO consider using Small Box for perf
-type Command<S> = Box<dyn FnOnce(&mut S) + Send>;
+forward_to_inner!(Controllable: Seek);
+
+/// A command that can be freed after it has run so that the renderer thread
+/// does not have to free it.
+trait CommandFn<S>: Garbage {
+    /// Run the command. Does nothing after the first call.
+    fn run(&mut self, sound: &mut S);
+}
+
+impl<S, F> CommandFn<S> for Option<F>
+where
+    F: FnOnce(&mut S) + Send,
+{
+    fn run(&mut self, sound: &mut S) {
+        if let Some(command) = self.take() {
+            command(sound);
+        }
+    }
+}
+
+type Command<S> = Box<dyn CommandFn<S>>;
+
+enum Message<S> {
+    /// A command and the frame to apply it at, if any.
+    Command(Option<u64>, Command<S>),
+    /// A command that does not need to be boxed.
+    Call(fn(&mut S)),
+}
+
+/// The error returned by [Controller::try_send_command] when the queue of the
+/// Controllable is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandQueueFull;
+
+impl std::fmt::Display for CommandQueueFull {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the command queue of the Controllable is full")
+    }
+}
+
+impl std::error::Error for CommandQueueFull {}
 
 /// The remote Controller for a Sound wrapped in a Controllable.
 pub struct Controller<S: Sound> {
-    command_sender: mpsc::Sender<Command<S>>,
+    command_sender: mpsc::SyncSender<Message<S>>,
+    /// Commands sent while the queue is full.
+    overflow_sender: mpsc::Sender<Message<S>>,
+    /// The number of commands in the overflow queue. While there are any, new
+    /// commands are sent there too so that commands are applied in order.
+    overflowed: Arc<AtomicUsize>,
+    shared_frame: Arc<AtomicU64>,
 }
 
 impl<S> Clone for Controller<S>
@@ -129,6 +393,9 @@ where
     fn clone(&self) -> Self {
         Self {
             command_sender: self.command_sender.clone(),
+            overflow_sender: self.overflow_sender.clone(),
+            overflowed: self.overflowed.clone(),
+            shared_frame: self.shared_frame.clone(),
         }
     }
 }
@@ -143,11 +410,97 @@ where
     /// sound of the controllable. Many but not all actions are available as
     /// trait functions on `Controller` but sending a command allows for full
     /// control.
-    pub fn send_command(&mut self, command: Command<S>) {
-        // Ignore the error since it only happens if the receiver
-        // has been dropped which is not expected after it has been
-        // sent to the manager.
-        let _ = self.command_sender.send(command);
+    ///
+    /// If the queue of the Controllable is full, for example because it is
+    /// not playing yet, the command waits in an overflow queue, see
+    /// [Controllable]. Use [Controller::try_send_command] to handle a full
+    /// queue instead.
+    pub fn send_command<F>(&mut self, command: F)
+    where
+        F: FnOnce(&mut S) + Send + 'static,
+    {
+        self.send(Message::Command(None, Box::new(Some(command))));
+    }
+
+    /// The same as [Controller::send_command] but returns an error instead of
+    /// using the overflow queue when the queue is full. The Controllable
+    /// empties the queue at the start of each batch.
+    pub fn try_send_command<F>(&mut self, command: F) -> Result<(), CommandQueueFull>
+    where
+        F: FnOnce(&mut S) + Send + 'static,
+    {
+        self.try_send(Message::Command(None, Box::new(Some(command))))
+            .map_err(|_| CommandQueueFull)
+    }
+
+    /// Send a command to be applied exactly when the Controllable reaches
+    /// `frame`, even within a batch. The frame counts the frames output by the
+    /// Controllable, see [Controller::frame]. Commands for a frame that has
+    /// passed are applied at the start of the next batch.
+    ///
+    /// Like [Controller::send_command], the command is received at the start
+    /// of the next batch so `frame` should be at least a batch ahead.
+    pub fn send_command_at<F>(&mut self, frame: u64, command: F)
+    where
+        F: FnOnce(&mut S) + Send + 'static,
+    {
+        self.send(Message::Command(Some(frame), Box::new(Some(command))));
+    }
+
+    /// The same as [Controller::send_command_at] but returns an error instead
+    /// of using the overflow queue when the queue is full.
+    pub fn try_send_command_at<F>(&mut self, frame: u64, command: F) -> Result<(), CommandQueueFull>
+    where
+        F: FnOnce(&mut S) + Send + 'static,
+    {
+        self.try_send(Message::Command(Some(frame), Box::new(Some(command))))
+            .map_err(|_| CommandQueueFull)
+    }
+
+    /// Send `function` to be called at the start of the next batch unless the
+    /// queue is full. Returns whether it was sent. Used on the renderer thread
+    /// since it neither allocates nor waits for the queue.
+    pub(crate) fn try_call(&mut self, function: fn(&mut S)) -> bool {
+        !matches!(
+            self.command_sender.try_send(Message::Call(function)),
+            Err(mpsc::TrySendError::Full(_))
+        )
+    }
+
+    fn send(&self, message: Message<S>) {
+        if let Err(message) = self.try_send(message) {
+            self.overflowed.fetch_add(1, Ordering::Relaxed);
+            // Only fails once the Controllable has been dropped.
+            let _ = self.overflow_sender.send(message);
+        }
+    }
+
+    /// Send `message` on the queue. Returns it if the queue is full or earlier
+    /// commands are still waiting in the overflow queue.
+    fn try_send(&self, message: Message<S>) -> Result<(), Message<S>> {
+        if self.overflowed.load(Ordering::Relaxed) > 0 {
+            return Err(message);
+        }
+        // Never block since waking a blocked sender would make the
+        // Controllable take a lock.
+        match self.command_sender.try_send(message) {
+            // The receiver is only dropped when the Controllable is, after
+            // which commands have no effect.
+            Ok(()) | Err(mpsc::TrySendError::Disconnected(_)) => Ok(()),
+            Err(mpsc::TrySendError::Full(message)) => Err(message),
+        }
+    }
+
+    /// The number of frames output by the Controllable before the current
+    /// batch.
+    pub fn frame(&self) -> u64 {
+        self.shared_frame.load(Ordering::Relaxed)
+    }
+
+    /// The frame shared by [Controller::frame], for mixers that follow the
+    /// clock of their Controllable.
+    pub(crate) fn shared_frame(&self) -> Arc<AtomicU64> {
+        self.shared_frame.clone()
     }
 }
 
@@ -157,7 +510,7 @@ where
 {
     /// Add `sound` to the sound container.
     pub fn add(&mut self, sound: Box<dyn Sound>) {
-        self.send_command(Box::new(|s: &mut S| s.add(sound)));
+        self.send_command(|s: &mut S| s.add(sound));
     }
 }
 
@@ -167,7 +520,7 @@ where
 {
     /// Clear all sounds currently playing or scheduled to play.
     pub fn clear(&mut self) {
-        self.send_command(Box::new(|s: &mut S| s.clear()));
+        self.send_command(|s: &mut S| s.clear());
     }
 }
 
@@ -177,7 +530,7 @@ where
 {
     /// Pause or unpause the controllable sound.
     pub fn set_paused(&mut self, paused: bool) {
-        self.send_command(Box::new(move |s: &mut S| s.set_paused(paused)));
+        self.send_command(move |s: &mut S| s.set_paused(paused));
     }
 }
 
@@ -187,7 +540,7 @@ where
 {
     /// Stop the controllable sound (i.e. it will return Finished).
     pub fn set_stopped(&mut self) {
-        self.send_command(Box::new(move |s: &mut S| s.set_stopped()));
+        self.send_command(move |s: &mut S| s.set_stopped());
     }
 }
 
@@ -197,7 +550,71 @@ where
 {
     /// Set the playback speed of the controllable sound.
     pub fn set_speed(&mut self, speed: f32) {
-        self.send_command(Box::new(move |s: &mut S| s.set_speed(speed)));
+        self.send_command(move |s: &mut S| s.set_speed(speed));
+    }
+
+    /// Ramp the playback speed of the controllable sound to `speed` over
+    /// `duration`.
+    pub fn ramp_speed(&mut self, speed: f32, duration: Duration, ramp: Ramp) {
+        self.send_command(move |s: &mut S| s.ramp_speed(speed, duration, ramp));
+    }
+
+    /// Animate the playback speed of the controllable sound to `speed` over
+    /// `duration` following `easing`.
+    pub fn tween_speed(&mut self, speed: f32, duration: Duration, easing: Easing) {
+        self.start_speed_tween(Tween::new(speed, duration, easing));
+    }
+
+    /// Animate the playback speed of the controllable sound with `tween`.
+    pub fn start_speed_tween(&mut self, tween: Tween) {
+        self.send_command(move |s: &mut S| s.start_speed_tween(tween));
+    }
+}
+
+impl<S> Controller<S>
+where
+    S: Sound + SetTempo,
+{
+    /// Set the tempo of the controllable sound without changing its pitch.
+    pub fn set_tempo(&mut self, tempo: f32) {
+        self.send_command(move |s: &mut S| s.set_tempo(tempo));
+    }
+}
+
+impl<S> Controller<S>
+where
+    S: Sound + SetPan,
+{
+    /// Set the pan of the controllable sound from -1.0 (left) to 1.0 (right).
+    pub fn set_pan(&mut self, pan: f32) {
+        self.send_command(move |s: &mut S| s.set_pan(pan));
+    }
+
+    /// Ramp the pan of the controllable sound to `pan` over `duration`.
+    pub fn ramp_pan(&mut self, pan: f32, duration: Duration, ramp: Ramp) {
+        self.send_command(move |s: &mut S| s.ramp_pan(pan, duration, ramp));
+    }
+
+    /// Animate the pan of the controllable sound to `pan` over `duration`
+    /// following `easing`.
+    pub fn tween_pan(&mut self, pan: f32, duration: Duration, easing: Easing) {
+        self.start_pan_tween(Tween::new(pan, duration, easing));
+    }
+
+    /// Animate the pan of the controllable sound with `tween`.
+    pub fn start_pan_tween(&mut self, tween: Tween) {
+        self.send_command(move |s: &mut S| s.start_pan_tween(tween));
+    }
+}
+
+impl<S> Controller<S>
+where
+    S: Sound + SetPitch,
+{
+    /// Transpose the controllable sound by `semitones` without changing its
+    /// duration.
+    pub fn set_pitch(&mut self, semitones: f32) {
+        self.send_command(move |s: &mut S| s.set_pitch(semitones));
     }
 }
 
@@ -207,6 +624,64 @@ where
 {
     /// Set the volume of the controllable sound.
     pub fn set_volume(&mut self, volume: f32) {
-        self.send_command(Box::new(move |s: &mut S| s.set_volume(volume)));
+        self.send_command(move |s: &mut S| s.set_volume(volume));
+    }
+
+    /// Set the volume of the controllable sound in decibels.
+    pub fn set_volume_db(&mut self, db: f32) {
+        self.send_command(move |s: &mut S| s.set_volume_db(db));
+    }
+
+    /// Ramp the volume of the controllable sound to `volume` over `duration`.
+    pub fn ramp_volume(&mut self, volume: f32, duration: Duration, ramp: Ramp) {
+        self.send_command(move |s: &mut S| s.ramp_volume(volume, duration, ramp));
+    }
+
+    /// Animate the volume of the controllable sound to `volume` over
+    /// `duration` following `easing`.
+    pub fn tween_volume(&mut self, volume: f32, duration: Duration, easing: Easing) {
+        self.start_volume_tween(Tween::new(volume, duration, easing));
+    }
+
+    /// Animate the volume of the controllable sound with `tween`, e.g. a chain
+    /// of changes.
+    pub fn start_volume_tween(&mut self, tween: Tween) {
+        self.send_command(move |s: &mut S| s.start_volume_tween(tween));
+    }
+}
+
+impl<S> Controller<S>
+where
+    S: Sound + SetFade,
+{
+    /// Ramp the volume of the controllable sound to `volume` over `duration`.
+    pub fn fade_to(&mut self, volume: f32, duration: Duration) {
+        self.send_command(move |s: &mut S| s.fade_to(volume, duration));
+    }
+
+    /// Fade the controllable sound out over `duration` and then stop it.
+    pub fn fade_out_and_stop(&mut self, duration: Duration) {
+        self.send_command(move |s: &mut S| s.fade_out_and_stop(duration));
+    }
+}
+
+impl<S> Controller<S>
+where
+    S: Sound + Seek,
+{
+    /// Seek the controllable sound to `position`.
+    ///
+    /// Since the seek happens on the renderer, an error while seeking is logged
+    /// instead of returned.
+    pub fn seek(&mut self, position: Duration) {
+        self.send_command(move |s: &mut S| {
+            if let Err(e) = s.seek(position) {
+                log::error!("error seeking controllable sound: {}", e);
+            }
+        });
     }
 }
+
+#[cfg(test)]
+#[path = "./tests/controllable.rs"]
+mod tests;
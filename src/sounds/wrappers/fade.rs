@@ -0,0 +1,340 @@
+use std::time::Duration;
+
+use crate::{sound::SampleType, utils, NextSample, Sound};
+
+use super::wrapper::forward_to_inner;
+use super::Seek;
+
+/// A sound whose volume can be faded over time.
+pub trait SetFade {
+    /// Ramp the gain linearly from its current value to `volume` over
+    /// `duration`. A `volume` of 1.0 leaves the sound unchanged.
+    fn fade_to(&mut self, volume: f32, duration: Duration);
+
+    /// Fade the gain to 0 over `duration` and then finish.
+    fn fade_out_and_stop(&mut self, duration: Duration);
+}
+
+/// A wrapper that fades the gain of the inner sound in and out.
+///
+/// Fades are measured in samples of the inner sound so they are sample
+/// accurate and, like [FinishAfter][super::FinishAfter], time spent paused does
+/// not count. The remainder of a fade is recalculated when the inner sound
+/// returns `MetadataChanged`. All channels of a frame get the same gain.
+///
+/// A fade out before the end of the inner sound (see
+/// [Fade::set_fade_out_at_end]) requires the inner sound to know its
+/// [position][Sound::position] and [total_duration][Sound::total_duration].
+pub struct Fade<S: Sound> {
+    inner: S,
+    /// The gain of the next frame.
+    gain: f32,
+    /// The gain when the current fade completes.
+    target: f32,
+    /// The number of frames until the current fade completes.
+    fade_frames_remaining: u64,
+    /// Whether to finish once the current fade completes.
+    stop_after_fade: bool,
+    stopped: bool,
+    fade_out_at_end: Option<Duration>,
+    /// The number of frames until the fade out at end starts if it is known.
+    frames_until_end_fade: Option<u64>,
+    /// The index in the frame of the next sample.
+    channel_idx: u16,
+    current_channel_count: u16,
+    current_sample_rate: u32,
+}
+
+impl<S> Fade<S>
+where
+    S: Sound,
+{
+    /// Wrap `inner` such that it can be faded. Starts at full volume.
+    pub fn new(inner: S) -> Self {
+        let current_channel_count = inner.channel_count();
+        let current_sample_rate = inner.sample_rate();
+        Fade {
+            inner,
+            gain: 1.0,
+            target: 1.0,
+            fade_frames_remaining: 0,
+            stop_after_fade: false,
+            stopped: false,
+            fade_out_at_end: None,
+            frames_until_end_fade: None,
+            channel_idx: 0,
+            current_channel_count,
+            current_sample_rate,
+        }
+    }
+
+    /// Wrap `inner` and fade it in from silence over `duration`.
+    pub fn new_fade_in(inner: S, duration: Duration) -> Self {
+        let mut fade = Fade::new(inner);
+        fade.gain = 0.0;
+        fade.fade_to(1.0, duration);
+        fade
+    }
+
+    /// Fade to silence over the last `duration` of the inner sound. `None`
+    /// disables the fade out.
+    ///
+    /// Has no effect if the inner sound does not know its position and total
+    /// duration.
+    pub fn set_fade_out_at_end(&mut self, duration: Option<Duration>) {
+        self.fade_out_at_end = duration;
+        self.schedule_end_fade();
+    }
+
+    /// The gain of the next frame.
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Get a reference to the wrapped inner Sound.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the wrapped inner Sound.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Unwrap and return the previously wrapped Sound.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn num_frames(&self, duration: Duration) -> u64 {
+        utils::duration_to_num_samples(duration, 1, self.current_sample_rate)
+    }
+
+    fn frames_to_duration(&self, num_frames: u64) -> Duration {
+        utils::num_samples_to_duration(num_frames, 1, self.current_sample_rate)
+    }
+
+    /// Work out when the fade out at end starts from the position of inner.
+    fn schedule_end_fade(&mut self) {
+        self.frames_until_end_fade = None;
+        let Some(fade_duration) = self.fade_out_at_end else {
+            return;
+        };
+        let (Some(position), Some(total)) = (self.inner.position(), self.inner.total_duration())
+        else {
+            return;
+        };
+        let remaining = total.saturating_sub(position);
+        let frames_until_fade = self.num_frames(remaining.saturating_sub(fade_duration));
+        if frames_until_fade == 0 {
+            self.start_fade(0.0, remaining.min(fade_duration));
+        } else {
+            self.frames_until_end_fade = Some(frames_until_fade);
+        }
+    }
+
+    fn start_fade(&mut self, volume: f32, duration: Duration) {
+        self.target = volume;
+        self.fade_frames_remaining = self.num_frames(duration);
+        if self.fade_frames_remaining == 0 {
+            self.gain = volume;
+        }
+    }
+
+    /// Move on to the gain of the next frame.
+    fn advance_frame(&mut self) {
+        if self.fade_frames_remaining > 0 {
+            self.gain += (self.target - self.gain) / self.fade_frames_remaining as f32;
+            self.fade_frames_remaining -= 1;
+            if self.fade_frames_remaining == 0 {
+                self.gain = self.target;
+            }
+        }
+        if let Some(frames) = &mut self.frames_until_end_fade {
+            *frames = frames.saturating_sub(1);
+            if *frames == 0 {
+                self.frames_until_end_fade = None;
+                let duration = self.fade_out_at_end.unwrap_or_default();
+                self.start_fade(0.0, duration);
+            }
+        }
+        if self.fade_frames_remaining == 0 && self.stop_after_fade {
+            self.stopped = true;
+        }
+    }
+
+    /// The number of frames until the gain jumps or the sound stops. Frames
+    /// can be processed in bulk up to that point.
+    fn frames_until_event(&self) -> u64 {
+        let mut frames = u64::MAX;
+        if self.stop_after_fade {
+            frames = self.fade_frames_remaining.max(1);
+        }
+        if let Some(until_end_fade) = self.frames_until_end_fade {
+            frames = frames.min(until_end_fade.max(1));
+        }
+        frames
+    }
+
+    fn track_metadata_changed(&mut self) {
+        let fade_remaining = self.frames_to_duration(self.fade_frames_remaining);
+        self.current_channel_count = self.inner.channel_count();
+        self.current_sample_rate = self.inner.sample_rate();
+        self.channel_idx = 0;
+        self.fade_frames_remaining = self.num_frames(fade_remaining);
+        if self.fade_frames_remaining == 0 {
+            self.gain = self.target;
+        }
+        if self.frames_until_end_fade.is_some() {
+            self.schedule_end_fade();
+        }
+    }
+
+    fn next_sample_of<T: SampleType>(&mut self) -> Result<NextSample<T>, crate::Error> {
+        if self.stopped {
+            return Ok(NextSample::Finished);
+        }
+        let next = T::next_sample_of(&mut self.inner)?;
+        match next {
+            NextSample::Sample(s) => {
+                let gain = self.gain;
+                self.channel_idx += 1;
+                if self.channel_idx >= self.current_channel_count {
+                    self.channel_idx = 0;
+                    self.advance_frame();
+                }
+                Ok(NextSample::Sample(apply_gain(s, gain)))
+            }
+            NextSample::MetadataChanged => {
+                self.track_metadata_changed();
+                Ok(next)
+            }
+            NextSample::Paused | NextSample::Finished => Ok(next),
+        }
+    }
+
+    fn fill_buffer_of<T: SampleType>(
+        &mut self,
+        buffer: &mut [T],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        debug_assert_eq!(self.channel_idx, 0);
+        let channel_count = self.current_channel_count as usize;
+        let mut num_frames = 0;
+        while num_frames * channel_count < buffer.len() {
+            if self.stopped {
+                return Ok((num_frames, Some(NextSample::Finished)));
+            }
+            let remaining = &mut buffer[num_frames * channel_count..];
+            let chunk_frames = (remaining.len() / channel_count)
+                .min(self.frames_until_event().try_into().unwrap_or(usize::MAX));
+            let chunk = &mut remaining[..chunk_frames * channel_count];
+            let (filled, next) = T::fill_buffer_of(&mut self.inner, chunk)?;
+            for frame in chunk[..filled * channel_count].chunks_exact_mut(channel_count) {
+                if self.gain != 1.0 || self.fade_frames_remaining > 0 {
+                    for sample in frame.iter_mut() {
+                        *sample = apply_gain(*sample, self.gain);
+                    }
+                }
+                self.advance_frame();
+            }
+            num_frames += filled;
+            if let Some(next) = next {
+                if next == NextSample::MetadataChanged {
+                    self.track_metadata_changed();
+                }
+                return Ok((num_frames, Some(next)));
+            }
+        }
+        Ok((num_frames, None))
+    }
+}
+
+fn apply_gain<T: SampleType>(sample: T, gain: f32) -> T {
+    T::from_f32(sample.to_f32() * gain)
+}
+
+impl<S> Sound for Fade<S>
+where
+    S: Sound,
+{
+    fn channel_count(&self) -> u16 {
+        self.inner.channel_count()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn next_sample(&mut self) -> Result<NextSample, crate::Error> {
+        self.next_sample_of()
+    }
+
+    fn next_sample_f32(&mut self) -> Result<NextSample<f32>, crate::Error> {
+        self.next_sample_of()
+    }
+
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.fill_buffer_of(buffer)
+    }
+
+    fn fill_buffer_f32(
+        &mut self,
+        buffer: &mut [f32],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.fill_buffer_of(buffer)
+    }
+
+    fn on_start_of_batch(&mut self) {
+        self.inner.on_start_of_batch()
+    }
+
+    fn position(&self) -> Option<Duration> {
+        self.inner.position()
+    }
+
+    /// The total duration of the inner sound. Stopping after a fade is not
+    /// taken into account.
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+}
+
+impl<S> SetFade for Fade<S>
+where
+    S: Sound,
+{
+    fn fade_to(&mut self, volume: f32, duration: Duration) {
+        self.stop_after_fade = false;
+        self.start_fade(volume, duration);
+    }
+
+    fn fade_out_and_stop(&mut self, duration: Duration) {
+        self.start_fade(0.0, duration);
+        self.stop_after_fade = true;
+        if self.fade_frames_remaining == 0 {
+            self.stopped = true;
+        }
+    }
+}
+
+forward_to_inner!(Fade: SetPaused, SetStopped, SetSpeed, SetTempo, SetPitch, SetVolume, SetPan);
+
+/// Seeking keeps any fade in progress and reschedules the fade out at end.
+impl<S> Seek for Fade<S>
+where
+    S: Sound + Seek,
+{
+    fn seek(&mut self, position: Duration) -> Result<Duration, crate::Error> {
+        let actual = self.inner.seek(position)?;
+        self.channel_idx = 0;
+        self.schedule_end_fade();
+        Ok(actual)
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/fade.rs"]
+mod tests;
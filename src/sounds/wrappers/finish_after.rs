@@ -1,8 +1,8 @@
 use std::time::Duration;
 
-use crate::Sound;
+use crate::{utils, Sound};
 
-use super::Wrapper;
+use super::{Seek, Wrapper};
 
 /// Play the  first part of an inner Sound measured in seconds then stop even
 /// if the inner sound still has samples remaining. Finishes early if the inner
@@ -67,6 +67,48 @@ where
             return Ok(crate::NextSample::Finished);
         }
         let next = self.inner.next_sample()?;
+        self.track_next(&next);
+        Ok(next)
+    }
+
+    fn next_sample_f32(&mut self) -> Result<crate::NextSample<f32>, crate::Error> {
+        if self.samples_remaining == 0 {
+            return Ok(crate::NextSample::Finished);
+        }
+        let next = self.inner.next_sample_f32()?;
+        self.track_next(&next);
+        Ok(next)
+    }
+
+    fn on_start_of_batch(&mut self) {
+        self.inner.on_start_of_batch()
+    }
+
+    /// The amount of the duration that has been played.
+    fn position(&self) -> Option<Duration> {
+        let remaining = utils::num_samples_to_duration(
+            self.samples_remaining,
+            self.current_channel_count,
+            self.current_sample_rate,
+        );
+        Some(self.total_duration.saturating_sub(remaining))
+    }
+
+    /// The duration passed in or the total duration of the inner sound if it
+    /// is known to be shorter.
+    fn total_duration(&self) -> Option<Duration> {
+        match self.inner.total_duration() {
+            Some(inner_total) => Some(inner_total.min(self.total_duration)),
+            None => Some(self.total_duration),
+        }
+    }
+}
+
+impl<S> FinishAfter<S>
+where
+    S: Sound,
+{
+    fn track_next<T>(&mut self, next: &crate::NextSample<T>) {
         match next {
             crate::NextSample::Sample(_) => {
                 self.samples_remaining -= 1;
@@ -94,11 +136,6 @@ where
             crate::NextSample::Paused => (),
             crate::NextSample::Finished => (),
         }
-        Ok(next)
-    }
-
-    fn on_start_of_batch(&mut self) {
-        self.inner.on_start_of_batch()
     }
 }
 
@@ -108,6 +145,28 @@ pub fn num_samples(duration: Duration, num_channels: u16, num_samples: u32) -> u
     micros * num_channels as u64 * num_samples as u64 / MICROS_PER_SEC
 }
 
+/// Seeking to or past the finish duration finishes the sound. Seeking before it
+/// plays the remainder of the duration from the new position.
+impl<S> Seek for FinishAfter<S>
+where
+    S: Sound + Seek,
+{
+    fn seek(&mut self, position: Duration) -> Result<Duration, crate::Error> {
+        if position >= self.total_duration {
+            self.samples_remaining = 0;
+            return Ok(self.total_duration);
+        }
+        let actual = self.inner.seek(position)?;
+        let duration_remaining = self.total_duration.saturating_sub(actual);
+        self.samples_remaining = num_samples(
+            duration_remaining,
+            self.current_channel_count,
+            self.current_sample_rate,
+        );
+        Ok(actual)
+    }
+}
+
 impl<S: Sound> Wrapper for FinishAfter<S> {
     type Inner = S;
 
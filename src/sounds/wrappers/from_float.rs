@@ -0,0 +1,99 @@
+use std::time::Duration;
+
+use crate::{utils, FloatSound, NextSample, Sound};
+
+/// Adapts a [FloatSound] to be a [Sound].
+///
+/// Float samples are passed through unchanged by `next_sample_f32` and
+/// `fill_buffer_f32` and only converted to i16 by `next_sample` and
+/// `fill_buffer`. Normally created with [FloatSound::into_sound].
+pub struct FromFloat<S: FloatSound> {
+    inner: S,
+    /// Holds float samples for fill_buffer before they are converted.
+    scratch: Vec<f32>,
+}
+
+impl<S> FromFloat<S>
+where
+    S: FloatSound,
+{
+    /// Wrap `inner` so it can be used as a Sound.
+    pub fn new(inner: S) -> Self {
+        FromFloat {
+            inner,
+            scratch: Vec::new(),
+        }
+    }
+
+    /// Get a reference to the wrapped inner FloatSound.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the wrapped inner FloatSound.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Unwrap and return the previously wrapped FloatSound.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Sound for FromFloat<S>
+where
+    S: FloatSound,
+{
+    fn channel_count(&self) -> u16 {
+        self.inner.channel_count()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn next_sample(&mut self) -> Result<NextSample, crate::Error> {
+        Ok(self.inner.next_sample()?.map(utils::f32_to_i16))
+    }
+
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.scratch.resize(buffer.len(), 0.0);
+        let (num_frames, next) = self.inner.fill_buffer(&mut self.scratch)?;
+        let num_samples = num_frames * self.inner.channel_count() as usize;
+        for (out, sample) in buffer.iter_mut().zip(&self.scratch[..num_samples]) {
+            *out = utils::f32_to_i16(*sample);
+        }
+        Ok((num_frames, next))
+    }
+
+    fn next_sample_f32(&mut self) -> Result<NextSample<f32>, crate::Error> {
+        self.inner.next_sample()
+    }
+
+    fn fill_buffer_f32(
+        &mut self,
+        buffer: &mut [f32],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.inner.fill_buffer(buffer)
+    }
+
+    fn on_start_of_batch(&mut self) {
+        self.inner.on_start_of_batch()
+    }
+
+    fn position(&self) -> Option<Duration> {
+        self.inner.position()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/from_float.rs"]
+mod tests;
@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use crate::{FloatSound, NextSample, Sound};
+
+use super::wrapper::forward_to_inner;
+use super::Wrapper;
+
+/// Adapts a [Sound] to be a [FloatSound].
+///
+/// Samples are retrieved with `next_sample_f32` and `fill_buffer_f32` so sounds
+/// that produce floats are not quantized. Normally created with
+/// [Sound::into_float].
+pub struct IntoFloat<S: Sound> {
+    inner: S,
+}
+
+impl<S> IntoFloat<S>
+where
+    S: Sound,
+{
+    /// Wrap `inner` so it can be used as a FloatSound.
+    pub fn new(inner: S) -> Self {
+        IntoFloat { inner }
+    }
+
+    /// Get a reference to the wrapped inner Sound.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the wrapped inner Sound.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Unwrap and return the previously wrapped Sound.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> FloatSound for IntoFloat<S>
+where
+    S: Sound,
+{
+    fn channel_count(&self) -> u16 {
+        self.inner.channel_count()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn next_sample(&mut self) -> Result<NextSample<f32>, crate::Error> {
+        self.inner.next_sample_f32()
+    }
+
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [f32],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.inner.fill_buffer_f32(buffer)
+    }
+
+    fn on_start_of_batch(&mut self) {
+        self.inner.on_start_of_batch()
+    }
+
+    fn position(&self) -> Option<Duration> {
+        self.inner.position()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+}
+
+impl<S> Wrapper for IntoFloat<S>
+where
+    S: Sound,
+{
+    type Inner = S;
+
+    fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    fn inner_mut(&mut self) -> &mut Self::Inner {
+        &mut self.inner
+    }
+
+    fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+forward_to_inner!(IntoFloat: Seek);
@@ -0,0 +1,392 @@
+use std::time::Duration;
+
+use crate::{sound::SampleType, utils, NextSample, Sound};
+
+use super::automation::Tweener;
+use super::wrapper::forward_to_inner;
+use super::{Ramp, Seek, Tween};
+
+/// A sound that can be placed between the left and right speakers.
+pub trait SetPan {
+    /// Change the pan. -1.0 is fully left, 0.0 is centered and 1.0 is fully
+    /// right. Values outside of that range are clamped.
+    fn set_pan(&mut self, pan: f32);
+
+    /// Move the pan to `pan` over `duration` following `ramp`.
+    fn ramp_pan(&mut self, pan: f32, duration: Duration, ramp: Ramp) {
+        self.start_pan_tween(Tween::ramp(pan, duration, ramp))
+    }
+
+    /// Animate the pan with `tween`, replacing any running tween. Values
+    /// outside of -1.0 to 1.0 are clamped. The default implementation calls
+    /// [set_pan][SetPan::set_pan] with the target of the tween.
+    fn start_pan_tween(&mut self, tween: Tween) {
+        if let Some(target) = tween.target() {
+            self.set_pan(target)
+        }
+    }
+}
+
+/// How the gains of the left and right channels follow the pan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PanLaw {
+    /// The total power stays the same across the pan range so the sound is
+    /// equally loud wherever it is placed. A centered mono sound is 3dB
+    /// quieter in each channel.
+    #[default]
+    ConstantPower,
+    /// The gains change linearly. A centered mono sound is 6dB quieter in each
+    /// channel which sounds quieter than when panned to one side.
+    Linear,
+}
+
+impl PanLaw {
+    /// The gain of a channel where `amount` is 0.0 when panned fully away
+    /// from the channel and 1.0 when panned fully towards it.
+    fn gain(&self, amount: f32) -> f32 {
+        match self {
+            PanLaw::ConstantPower => (amount * std::f32::consts::FRAC_PI_2).sin(),
+            PanLaw::Linear => amount,
+        }
+    }
+
+    /// The left and right gains for placing a mono sound at `pan`.
+    fn mono_gains(&self, pan: f32) -> [f32; 2] {
+        let right = (pan + 1.0) / 2.0;
+        [self.gain(1.0 - right), self.gain(right)]
+    }
+
+    /// The left and right gains for the balance of a stereo sound at `pan`.
+    /// A centered balance leaves both channels unchanged.
+    fn balance_gains(&self, pan: f32) -> [f32; 2] {
+        if pan < 0.0 {
+            [1.0, self.gain(1.0 + pan)]
+        } else {
+            [self.gain(1.0 - pan), 1.0]
+        }
+    }
+}
+
+/// How long pan changes are ramped over to avoid clicks.
+const PAN_RAMP: Duration = Duration::from_millis(10);
+
+/// A wrapper that places the inner sound between the left and right speakers.
+///
+/// A mono inner sound is upmixed to stereo and placed at the pan position. For
+/// a stereo inner sound the pan is a balance control that attenuates the
+/// opposite channel. With more channels the balance applies to the first two
+/// (front left and right) channels and the others are unchanged.
+///
+/// Pan changes are ramped over 10ms.
+pub struct Pannable<S: Sound> {
+    inner: S,
+    pan: f32,
+    law: PanLaw,
+    /// The left and right gains of the next frame.
+    gains: [f32; 2],
+    /// The change in the gains per frame while ramping.
+    steps: [f32; 2],
+    ramp_frames_remaining: u64,
+    /// The tween started with [start_pan_tween][SetPan::start_pan_tween].
+    automation: Option<Tweener>,
+    /// The right channel of a mono frame waiting to be returned.
+    pending_right: Option<f32>,
+    /// The index in the frame of the next sample of a sound with two or more
+    /// channels.
+    channel_idx: u16,
+}
+
+impl<S> Pannable<S>
+where
+    S: Sound,
+{
+    /// Wrap `inner` such that it can be panned. Starts centered.
+    pub fn new(inner: S) -> Self {
+        Self::new_with_pan(inner, 0.0)
+    }
+
+    /// Wrap `inner` such that it can be panned and set the initial pan.
+    pub fn new_with_pan(inner: S, pan: f32) -> Self {
+        let mut pannable = Pannable {
+            inner,
+            pan: 0.0,
+            law: PanLaw::default(),
+            gains: [1.0; 2],
+            steps: [0.0; 2],
+            ramp_frames_remaining: 0,
+            automation: None,
+            pending_right: None,
+            channel_idx: 0,
+        };
+        pannable.pan = pan.clamp(-1.0, 1.0);
+        pannable.gains = pannable.target_gains();
+        pannable
+    }
+
+    /// Change the pan law. Applies immediately.
+    pub fn set_pan_law(&mut self, law: PanLaw) {
+        self.law = law;
+        self.gains = self.settled_gains();
+        self.ramp_frames_remaining = 0;
+    }
+
+    /// The current pan from -1.0 (left) to 1.0 (right).
+    pub fn pan(&self) -> f32 {
+        self.pan
+    }
+
+    /// Get a reference to the wrapped inner Sound.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the wrapped inner Sound.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Unwrap and return the previously wrapped Sound.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn is_mono(&self) -> bool {
+        self.inner.channel_count() == 1
+    }
+
+    fn target_gains(&self) -> [f32; 2] {
+        self.gains_for(self.pan)
+    }
+
+    /// The gains without the short ramp of [set_pan][SetPan::set_pan].
+    fn settled_gains(&self) -> [f32; 2] {
+        match &self.automation {
+            Some(automation) => self.gains_for(automation.value_at(0, self.inner.sample_rate())),
+            None => self.target_gains(),
+        }
+    }
+
+    fn gains_for(&self, pan: f32) -> [f32; 2] {
+        let pan = pan.clamp(-1.0, 1.0);
+        if self.is_mono() {
+            self.law.mono_gains(pan)
+        } else {
+            self.law.balance_gains(pan)
+        }
+    }
+
+    /// The gains `frames_ahead` frames after the next frame.
+    fn gains_at(&self, frames_ahead: u64) -> [f32; 2] {
+        if let Some(automation) = &self.automation {
+            return self.gains_for(automation.value_at(frames_ahead, self.inner.sample_rate()));
+        }
+        if self.ramp_frames_remaining == 0 {
+            return self.gains;
+        }
+        if frames_ahead >= self.ramp_frames_remaining {
+            return self.target_gains();
+        }
+        let frames_ahead = frames_ahead as f32;
+        [
+            self.gains[0] + self.steps[0] * frames_ahead,
+            self.gains[1] + self.steps[1] * frames_ahead,
+        ]
+    }
+
+    /// Move on by `num_frames` frames.
+    fn advance_frames(&mut self, num_frames: u64) {
+        let sample_rate = self.inner.sample_rate();
+        // A finished tween is kept so that its memory is not freed while
+        // rendering. It stays at the target pan.
+        if let Some(automation) = self.automation.as_mut().filter(|a| !a.is_finished()) {
+            automation.advance(num_frames, sample_rate);
+            self.gains = self.gains_at(0);
+            return;
+        }
+        if self.ramp_frames_remaining > 0 {
+            self.gains = self.gains_at(num_frames);
+            self.ramp_frames_remaining = self.ramp_frames_remaining.saturating_sub(num_frames);
+        }
+    }
+
+    /// The inner metadata changed so the gains for the new channel count apply
+    /// immediately.
+    fn metadata_changed(&mut self) {
+        self.pending_right = None;
+        self.channel_idx = 0;
+        self.ramp_frames_remaining = 0;
+        self.gains = self.settled_gains();
+    }
+
+    fn next_sample_of<T: SampleType>(&mut self) -> Result<NextSample<T>, crate::Error> {
+        if let Some(right) = self.pending_right.take() {
+            return Ok(NextSample::Sample(T::from_f32(right)));
+        }
+        let next = T::next_sample_of(&mut self.inner)?;
+        let sample = match next {
+            NextSample::Sample(s) => s.to_f32(),
+            NextSample::MetadataChanged => {
+                self.metadata_changed();
+                return Ok(next);
+            }
+            NextSample::Paused | NextSample::Finished => return Ok(next),
+        };
+        if self.is_mono() {
+            let [left, right] = self.gains;
+            self.pending_right = Some(sample * right);
+            self.advance_frames(1);
+            return Ok(NextSample::Sample(T::from_f32(sample * left)));
+        }
+        let gain = self.gains.get(self.channel_idx as usize).copied();
+        self.channel_idx += 1;
+        if self.channel_idx >= self.inner.channel_count() {
+            self.channel_idx = 0;
+            self.advance_frames(1);
+        }
+        match gain {
+            Some(gain) => Ok(NextSample::Sample(T::from_f32(sample * gain))),
+            None => Ok(NextSample::Sample(T::from_f32(sample))),
+        }
+    }
+
+    fn fill_buffer_of<T: SampleType>(
+        &mut self,
+        buffer: &mut [T],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        if self.is_mono() {
+            // Fill the first half with mono frames then spread them out from
+            // the back so no frame is overwritten before it is read.
+            let max_frames = buffer.len() / 2;
+            let (num_frames, next) = T::fill_buffer_of(&mut self.inner, &mut buffer[..max_frames])?;
+            for i in (0..num_frames).rev() {
+                let [left, right] = self.gains_at(i as u64);
+                let sample = buffer[i].to_f32();
+                buffer[2 * i] = T::from_f32(sample * left);
+                buffer[2 * i + 1] = T::from_f32(sample * right);
+            }
+            self.advance_frames(num_frames as u64);
+            if next == Some(NextSample::MetadataChanged) {
+                self.metadata_changed();
+            }
+            return Ok((num_frames, next));
+        }
+
+        let channel_count = self.inner.channel_count() as usize;
+        let (num_frames, next) = T::fill_buffer_of(&mut self.inner, buffer)?;
+        let unchanged = self.ramp_frames_remaining == 0
+            && self.automation.as_ref().is_none_or(Tweener::is_finished)
+            && self.gains == [1.0; 2];
+        if !unchanged {
+            let frames = buffer[..num_frames * channel_count].chunks_exact_mut(channel_count);
+            for (i, frame) in frames.enumerate() {
+                for (sample, gain) in frame.iter_mut().zip(self.gains_at(i as u64)) {
+                    *sample = T::from_f32(sample.to_f32() * gain);
+                }
+            }
+            self.advance_frames(num_frames as u64);
+        }
+        if next == Some(NextSample::MetadataChanged) {
+            self.metadata_changed();
+        }
+        Ok((num_frames, next))
+    }
+}
+
+impl<S> Sound for Pannable<S>
+where
+    S: Sound,
+{
+    /// Two for a mono inner sound, otherwise the channel count of the inner
+    /// sound.
+    fn channel_count(&self) -> u16 {
+        self.inner.channel_count().max(2)
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn next_sample(&mut self) -> Result<NextSample, crate::Error> {
+        self.next_sample_of()
+    }
+
+    fn next_sample_f32(&mut self) -> Result<NextSample<f32>, crate::Error> {
+        self.next_sample_of()
+    }
+
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.fill_buffer_of(buffer)
+    }
+
+    fn fill_buffer_f32(
+        &mut self,
+        buffer: &mut [f32],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.fill_buffer_of(buffer)
+    }
+
+    fn on_start_of_batch(&mut self) {
+        self.inner.on_start_of_batch()
+    }
+
+    fn position(&self) -> Option<Duration> {
+        self.inner.position()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+}
+
+impl<S> SetPan for Pannable<S>
+where
+    S: Sound,
+{
+    fn set_pan(&mut self, pan: f32) {
+        self.pan = pan.clamp(-1.0, 1.0);
+        self.automation = None;
+        let target = self.target_gains();
+        self.ramp_frames_remaining =
+            utils::duration_to_num_samples(PAN_RAMP, 1, self.inner.sample_rate());
+        if self.ramp_frames_remaining == 0 {
+            self.gains = target;
+        } else {
+            for ((step, gain), target) in self.steps.iter_mut().zip(self.gains).zip(target) {
+                *step = (target - gain) / self.ramp_frames_remaining as f32;
+            }
+        }
+    }
+
+    fn start_pan_tween(&mut self, tween: Tween) {
+        let start = match &self.automation {
+            Some(automation) => automation.value_at(0, self.inner.sample_rate()),
+            None => self.pan,
+        };
+        let tweener = Tweener::new(start, tween);
+        self.pan = tweener.target().clamp(-1.0, 1.0);
+        self.ramp_frames_remaining = 0;
+        self.automation = (!tweener.is_finished()).then_some(tweener);
+        self.gains = self.settled_gains();
+    }
+}
+
+forward_to_inner!(Pannable: SetPaused, SetStopped, SetSpeed, SetTempo, SetPitch, SetVolume, SetFade);
+
+impl<S> Seek for Pannable<S>
+where
+    S: Sound + Seek,
+{
+    fn seek(&mut self, position: Duration) -> Result<Duration, crate::Error> {
+        let actual = self.inner.seek(position)?;
+        self.pending_right = None;
+        self.channel_idx = 0;
+        Ok(actual)
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/pannable.rs"]
+mod tests;
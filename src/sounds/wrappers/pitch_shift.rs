@@ -0,0 +1,279 @@
+use std::time::Duration;
+
+use crate::{sound::SampleType, NextSample, Sound};
+
+use super::wrapper::forward_to_inner;
+use super::{AdjustableTempo, Seek, SetTempo};
+
+/// A sound whose pitch can be adjusted without changing its duration.
+pub trait SetPitch {
+    /// Transpose the sound by `semitones`. 0.0 is the original pitch, 12.0 is
+    /// an octave higher and -12.0 an octave lower. Fractions of a semitone
+    /// are allowed.
+    fn set_pitch(&mut self, semitones: f32);
+}
+
+/// The pitch is clamped to this many semitones up or down.
+const MAX_SEMITONES: f32 = 48.0;
+
+/// A wrapper that transposes the inner sound without changing its duration.
+///
+/// The inner sound is time stretched with [AdjustableTempo] by the inverse of
+/// the pitch ratio and then resampled back to the original duration with
+/// linear interpolation. The sample rate is that of the inner sound.
+///
+/// Like [AdjustableTempo], rendering does not allocate unless the channel
+/// count or sample rate of the inner sound changes.
+pub struct PitchShift<S: Sound> {
+    inner: AdjustableTempo<S>,
+    semitones: f32,
+    /// The output frequency divided by the input frequency.
+    ratio: f64,
+    /// False until the pitch is first changed. Samples pass through unchanged
+    /// until then.
+    shifting: bool,
+    /// The two frames of the stretched sound output frames are interpolated
+    /// between.
+    previous: Vec<f32>,
+    next: Vec<f32>,
+    /// The position of the next output frame between `previous` and `next`.
+    fraction: f64,
+    /// The number of frames to pull before the next output frame.
+    frames_needed: usize,
+    /// The current output frame and how much of it has been read.
+    output: Vec<f32>,
+    output_read: usize,
+}
+
+impl<S> PitchShift<S>
+where
+    S: Sound,
+{
+    /// Wrap `inner` such that its pitch can be adjusted.
+    pub fn new(inner: S) -> Self {
+        Self::new_with_pitch(inner, 0.0)
+    }
+
+    /// Wrap `inner` such that its pitch can be adjusted and set an initial
+    /// pitch in semitones.
+    pub fn new_with_pitch(inner: S, semitones: f32) -> Self {
+        let channel_count = inner.channel_count() as usize;
+        let mut shift = PitchShift {
+            inner: AdjustableTempo::new(inner),
+            semitones: 0.0,
+            ratio: 1.0,
+            shifting: false,
+            previous: Vec::with_capacity(channel_count),
+            next: Vec::with_capacity(channel_count),
+            fraction: 0.0,
+            frames_needed: 2,
+            output: Vec::with_capacity(channel_count),
+            output_read: 0,
+        };
+        shift.set_pitch(semitones);
+        shift
+    }
+
+    /// Return the current pitch in semitones. 0.0 is the original pitch.
+    pub fn pitch(&self) -> f32 {
+        self.semitones
+    }
+
+    /// Get a reference to the wrapped inner Sound.
+    pub fn inner(&self) -> &S {
+        self.inner.inner()
+    }
+
+    /// Get a mutable reference to the wrapped inner Sound.
+    pub fn inner_mut(&mut self) -> &mut S {
+        self.inner.inner_mut()
+    }
+
+    /// Unwrap and return the previously wrapped Sound. Samples read ahead
+    /// while shifting are discarded.
+    pub fn into_inner(self) -> S {
+        self.inner.into_inner()
+    }
+
+    /// Start over from the next frame of the stretched sound.
+    fn reset(&mut self) {
+        self.shifting = self.ratio != 1.0;
+        self.fraction = 0.0;
+        self.frames_needed = 2;
+        self.output.clear();
+        self.output_read = 0;
+    }
+
+    /// Compute the next output frame. Returns what to return instead if there
+    /// is no output for now.
+    fn next_frame(&mut self) -> Result<Option<NextSample>, crate::Error> {
+        let channel_count = self.inner.channel_count() as usize;
+        while self.frames_needed > 0 {
+            std::mem::swap(&mut self.previous, &mut self.next);
+            self.next.resize(channel_count, 0.0);
+            let (num_frames, next) = self.inner.fill_buffer_f32(&mut self.next)?;
+            if num_frames == 0 {
+                match next {
+                    Some(NextSample::Paused) => {
+                        // Try again with the same frames once unpaused.
+                        std::mem::swap(&mut self.previous, &mut self.next);
+                    }
+                    _ => self.reset(),
+                }
+                return Ok(Some(next.expect("a frame was requested")));
+            }
+            self.frames_needed -= 1;
+        }
+
+        let weight = self.fraction as f32;
+        self.output.clear();
+        self.output.extend(
+            self.previous
+                .iter()
+                .zip(&self.next)
+                .map(|(previous, next)| previous + (next - previous) * weight),
+        );
+        self.output_read = 0;
+
+        self.fraction += self.ratio;
+        while self.fraction >= 1.0 {
+            self.fraction -= 1.0;
+            self.frames_needed += 1;
+        }
+        Ok(None)
+    }
+
+    fn next_sample_of<T: SampleType>(&mut self) -> Result<NextSample<T>, crate::Error> {
+        if !self.shifting {
+            return T::next_sample_of(&mut self.inner);
+        }
+        if let Some(sample) = self.output.get(self.output_read) {
+            self.output_read += 1;
+            return Ok(NextSample::Sample(T::from_f32(*sample)));
+        }
+        match self.next_frame()? {
+            None => self.next_sample_of(),
+            Some(next) => Ok(next.map(|_| unreachable!())),
+        }
+    }
+
+    fn fill_buffer_of<T: SampleType>(
+        &mut self,
+        buffer: &mut [T],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        if !self.shifting {
+            return T::fill_buffer_of(&mut self.inner, buffer);
+        }
+        let channel_count = self.inner.channel_count() as usize;
+        let mut filled = 0;
+        loop {
+            let remaining = &self.output[self.output_read..];
+            let count = remaining.len().min(buffer.len() - filled);
+            for (out, sample) in buffer[filled..].iter_mut().zip(&remaining[..count]) {
+                *out = T::from_f32(*sample);
+            }
+            self.output_read += count;
+            filled += count;
+            if filled == buffer.len() {
+                return Ok((filled / channel_count, None));
+            }
+            if let Some(next) = self.next_frame()? {
+                return Ok((filled / channel_count, Some(next)));
+            }
+        }
+    }
+}
+
+impl<S> Sound for PitchShift<S>
+where
+    S: Sound,
+{
+    fn channel_count(&self) -> u16 {
+        self.inner.channel_count()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn next_sample(&mut self) -> Result<NextSample, crate::Error> {
+        self.next_sample_of()
+    }
+
+    fn next_sample_f32(&mut self) -> Result<NextSample<f32>, crate::Error> {
+        self.next_sample_of()
+    }
+
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.fill_buffer_of(buffer)
+    }
+
+    fn fill_buffer_f32(
+        &mut self,
+        buffer: &mut [f32],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.fill_buffer_of(buffer)
+    }
+
+    fn on_start_of_batch(&mut self) {
+        self.inner.on_start_of_batch()
+    }
+
+    /// The position of the inner sound. Shifting the pitch does not change
+    /// the timing.
+    fn position(&self) -> Option<Duration> {
+        let stretched = self.inner.position()?;
+        Duration::try_from_secs_f64(stretched.as_secs_f64() / self.ratio).ok()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.inner().total_duration()
+    }
+}
+
+impl<S> SetPitch for PitchShift<S>
+where
+    S: Sound,
+{
+    fn set_pitch(&mut self, semitones: f32) {
+        let semitones = semitones.clamp(-MAX_SEMITONES, MAX_SEMITONES);
+        self.semitones = semitones;
+        self.ratio = 2f64.powf(semitones as f64 / 12.0);
+        self.inner.set_tempo((1.0 / self.ratio) as f32);
+        if self.ratio != 1.0 {
+            self.shifting = true;
+        }
+    }
+}
+
+forward_to_inner!(PitchShift: SetPaused, SetStopped, SetSpeed, SetVolume, SetPan, SetFade);
+
+impl<S> SetTempo for PitchShift<S>
+where
+    S: Sound + SetTempo,
+{
+    fn set_tempo(&mut self, multiplier: f32) {
+        self.inner.inner_mut().set_tempo(multiplier)
+    }
+}
+
+impl<S> Seek for PitchShift<S>
+where
+    S: Sound + Seek,
+{
+    fn seek(&mut self, position: Duration) -> Result<Duration, crate::Error> {
+        let stretched = Duration::try_from_secs_f64(position.as_secs_f64() * self.ratio)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+        let stretched = self.inner.seek(stretched)?;
+        self.reset();
+        Duration::try_from_secs_f64(stretched.as_secs_f64() / self.ratio)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e).into())
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/pitch_shift.rs"]
+mod tests;
@@ -0,0 +1,356 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+use crate::sound::SampleType;
+use crate::{utils, NextSample, Sound};
+
+/// The fewest samples the ring buffer holds regardless of the requested
+/// duration so that a whole frame always fits.
+const MIN_CAPACITY: usize = 4096;
+
+/// How many frames the worker decodes at a time.
+const DECODE_CHUNK_FRAMES: usize = 1024;
+
+/// Decodes the inner Sound on a worker thread ahead of playback.
+///
+/// Decoders that read from a file or the network can block, which causes
+/// audible glitches when done on the renderer thread. Prefetched moves the
+/// inner Sound to its own thread which keeps a ring buffer of decoded samples
+/// filled. Reading from the ring buffer never blocks or allocates.
+///
+/// Metadata changes and errors of the inner Sound are returned once the
+/// samples before them have been played. If the worker has not decoded the
+/// next frame in time, `Paused` is returned until it has. The worker stops
+/// once the inner Sound finishes or errors or when the Prefetched is dropped.
+///
+/// Unlike most wrappers the inner Sound cannot be accessed or controlled since
+/// it lives on the worker thread. Wrap the Prefetched instead, e.g. with
+/// [Sound::pausable].
+pub struct Prefetched {
+    ring: Arc<Ring>,
+    events: mpsc::Receiver<(u64, Event)>,
+    /// The next event received from the worker and the sample index it
+    /// happens at.
+    next_event: Option<(u64, Event)>,
+    worker: thread::Thread,
+    /// The number of samples read from the ring buffer.
+    read: u64,
+    channel_count: u16,
+    sample_rate: u32,
+    channel_idx: u16,
+    finished: bool,
+    /// The position at the last metadata change.
+    position: Option<Duration>,
+    frames_since_metadata_change: u64,
+    total_duration: Option<Duration>,
+}
+
+enum Event {
+    MetadataChanged {
+        channel_count: u16,
+        sample_rate: u32,
+    },
+    Finished,
+    Error(crate::Error),
+}
+
+/// A single producer single consumer ring buffer of f32 samples stored as
+/// their bits.
+struct Ring {
+    samples: Box<[AtomicU32]>,
+    /// The total number of samples written. Only modified by the worker.
+    written: AtomicU64,
+    /// The total number of samples read. Only modified by the Prefetched.
+    read: AtomicU64,
+    /// Set when the Prefetched is dropped.
+    closed: AtomicBool,
+}
+
+impl Prefetched {
+    /// The buffer duration used by [open_file_prefetched][crate::sounds::open_file_prefetched]
+    /// when none is given.
+    pub const DEFAULT_BUFFER: Duration = Duration::from_millis(500);
+
+    /// Start decoding `inner` on a new thread, keeping up to `buffer` of
+    /// samples decoded ahead.
+    ///
+    /// The size of the ring buffer is set from the channel count and sample
+    /// rate of `inner` when this is called. Returns an error if the thread
+    /// could not be spawned.
+    pub fn new<S: Sound + 'static>(inner: S, buffer: Duration) -> Result<Self, crate::Error> {
+        let channel_count = inner.channel_count();
+        let sample_rate = inner.sample_rate();
+        let capacity = (utils::duration_to_num_samples(buffer, channel_count, sample_rate)
+            as usize)
+            .max(MIN_CAPACITY);
+        let ring = Arc::new(Ring {
+            samples: (0..capacity).map(|_| AtomicU32::new(0)).collect(),
+            written: AtomicU64::new(0),
+            read: AtomicU64::new(0),
+            closed: AtomicBool::new(false),
+        });
+        let position = inner.position();
+        let total_duration = inner.total_duration();
+        // Wake up when about a quarter of the buffer has been played.
+        let wait = (buffer / 4).max(Duration::from_millis(1));
+        let (sender, events) = mpsc::channel();
+        let worker_ring = ring.clone();
+        let worker = thread::Builder::new()
+            .name("awedio-prefetch".to_owned())
+            .spawn(move || run_worker(inner, worker_ring, sender, wait))?;
+        Ok(Prefetched {
+            ring,
+            events,
+            next_event: None,
+            worker: worker.thread().clone(),
+            read: 0,
+            channel_count,
+            sample_rate,
+            channel_idx: 0,
+            finished: false,
+            position,
+            frames_since_metadata_change: 0,
+            total_duration,
+        })
+    }
+
+    /// The number of samples in whole frames that can be read now or, if
+    /// there are none, why not.
+    fn readable(&mut self) -> Result<(usize, Option<NextSample>), crate::Error> {
+        if self.finished {
+            return Ok((0, Some(NextSample::Finished)));
+        }
+        // The worker sends an event before writing any samples after it so
+        // loading `written` first ensures no event before it is missed.
+        let written = self.ring.written.load(Ordering::Acquire);
+        if self.next_event.is_none() {
+            self.next_event = match self.events.try_recv() {
+                Ok(event) => Some(event),
+                Err(mpsc::TryRecvError::Empty) => None,
+                // The worker panicked.
+                Err(mpsc::TryRecvError::Disconnected) => Some((written, Event::Finished)),
+            };
+        }
+        let limit = match self.next_event {
+            Some((at, _)) if at <= self.read => {
+                let (_, event) = self.next_event.take().expect("checked above");
+                return self.apply_event(event);
+            }
+            Some((at, _)) => at,
+            None => written,
+        };
+        let channel_count = self.channel_count as usize;
+        let available = (limit - self.read) as usize / channel_count * channel_count;
+        if available == 0 {
+            return Ok((0, Some(NextSample::Paused)));
+        }
+        Ok((available, None))
+    }
+
+    fn apply_event(&mut self, event: Event) -> Result<(usize, Option<NextSample>), crate::Error> {
+        match event {
+            Event::MetadataChanged {
+                channel_count,
+                sample_rate,
+            } => {
+                self.position = self.position();
+                self.frames_since_metadata_change = 0;
+                self.channel_count = channel_count;
+                self.sample_rate = sample_rate;
+                Ok((0, Some(NextSample::MetadataChanged)))
+            }
+            Event::Finished => {
+                self.finished = true;
+                Ok((0, Some(NextSample::Finished)))
+            }
+            Event::Error(e) => {
+                self.finished = true;
+                Err(e)
+            }
+        }
+    }
+
+    fn read_samples<T: SampleType>(&mut self, buffer: &mut [T]) {
+        let capacity = self.ring.samples.len() as u64;
+        for (i, sample) in buffer.iter_mut().enumerate() {
+            let idx = ((self.read + i as u64) % capacity) as usize;
+            *sample = T::from_f32(f32::from_bits(
+                self.ring.samples[idx].load(Ordering::Relaxed),
+            ));
+        }
+        self.read += buffer.len() as u64;
+        self.ring.read.store(self.read, Ordering::Release);
+    }
+
+    fn next_sample_of<T: SampleType>(&mut self) -> Result<NextSample<T>, crate::Error> {
+        // The rest of a frame is always available once its first sample is.
+        if self.channel_idx == 0 {
+            if let (_, Some(next)) = self.readable()? {
+                return Ok(next.map(|_| unreachable!()));
+            }
+        }
+        let mut sample = [T::default()];
+        self.read_samples(&mut sample);
+        self.channel_idx += 1;
+        if self.channel_idx >= self.channel_count {
+            self.channel_idx = 0;
+            self.frames_since_metadata_change += 1;
+        }
+        Ok(NextSample::Sample(sample[0]))
+    }
+
+    fn fill_buffer_of<T: SampleType>(
+        &mut self,
+        buffer: &mut [T],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        let channel_count = self.channel_count as usize;
+        let mut num_samples = 0;
+        while num_samples < buffer.len() {
+            let (available, next) = self.readable()?;
+            if next.is_some() {
+                return Ok((num_samples / channel_count, next));
+            }
+            let to_read = available.min(buffer.len() - num_samples);
+            self.read_samples(&mut buffer[num_samples..num_samples + to_read]);
+            num_samples += to_read;
+            self.frames_since_metadata_change += (to_read / channel_count) as u64;
+        }
+        Ok((num_samples / channel_count, None))
+    }
+}
+
+impl Sound for Prefetched {
+    fn channel_count(&self) -> u16 {
+        self.channel_count
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn next_sample(&mut self) -> Result<NextSample, crate::Error> {
+        self.next_sample_of()
+    }
+
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.fill_buffer_of(buffer)
+    }
+
+    fn next_sample_f32(&mut self) -> Result<NextSample<f32>, crate::Error> {
+        self.next_sample_of()
+    }
+
+    fn fill_buffer_f32(
+        &mut self,
+        buffer: &mut [f32],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.fill_buffer_of(buffer)
+    }
+
+    fn on_start_of_batch(&mut self) {}
+
+    fn position(&self) -> Option<Duration> {
+        self.position.map(|position| {
+            position
+                + utils::num_samples_to_duration(
+                    self.frames_since_metadata_change,
+                    1,
+                    self.sample_rate,
+                )
+        })
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.total_duration
+    }
+}
+
+impl Drop for Prefetched {
+    fn drop(&mut self) {
+        self.ring.closed.store(true, Ordering::Release);
+        self.worker.unpark();
+    }
+}
+
+impl Ring {
+    /// Write as many of `samples` as fit. Returns how many were written.
+    fn push(&self, samples: &[f32]) -> usize {
+        let capacity = self.samples.len() as u64;
+        let written = self.written.load(Ordering::Relaxed);
+        let free = capacity - (written - self.read.load(Ordering::Acquire));
+        let num_samples = samples.len().min(free as usize);
+        for (i, sample) in samples[..num_samples].iter().enumerate() {
+            let idx = ((written + i as u64) % capacity) as usize;
+            self.samples[idx].store(sample.to_bits(), Ordering::Relaxed);
+        }
+        self.written
+            .store(written + num_samples as u64, Ordering::Release);
+        num_samples
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+}
+
+fn run_worker<S: Sound>(
+    mut inner: S,
+    ring: Arc<Ring>,
+    events: mpsc::Sender<(u64, Event)>,
+    wait: Duration,
+) {
+    let mut buffer = Vec::new();
+    while !ring.is_closed() {
+        inner.on_start_of_batch();
+        let channel_count = inner.channel_count() as usize;
+        buffer.resize(DECODE_CHUNK_FRAMES * channel_count, 0.0);
+        let (num_frames, next) = match inner.fill_buffer_f32(&mut buffer) {
+            Ok(result) => result,
+            Err(e) => {
+                let _ = events.send((ring.written.load(Ordering::Relaxed), Event::Error(e)));
+                return;
+            }
+        };
+        let mut remaining = &buffer[..num_frames * channel_count];
+        loop {
+            remaining = &remaining[ring.push(remaining)..];
+            if remaining.is_empty() {
+                break;
+            }
+            thread::park_timeout(wait);
+            if ring.is_closed() {
+                return;
+            }
+        }
+        let event = match next {
+            None | Some(NextSample::Sample(_)) => continue,
+            Some(NextSample::Paused) => {
+                thread::park_timeout(wait);
+                continue;
+            }
+            Some(NextSample::MetadataChanged) => Event::MetadataChanged {
+                channel_count: inner.channel_count(),
+                sample_rate: inner.sample_rate(),
+            },
+            Some(NextSample::Finished) => Event::Finished,
+        };
+        let finished = matches!(event, Event::Finished);
+        // If the receiver is gone the Prefetched was dropped.
+        if events
+            .send((ring.written.load(Ordering::Relaxed), event))
+            .is_err()
+            || finished
+        {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./tests/prefetched.rs"]
+mod tests;
@@ -1,11 +1,31 @@
-use crate::{NextSample, Sound};
+use std::time::Duration;
 
-use super::Wrapper;
+use crate::{
+    sound::{fill_buffer_from_next_sample, SampleType},
+    utils, NextSample, Sound,
+};
+
+use super::{sinc_resampler::SincResampler, Seek, Wrapper};
 
 // Forked from https://github.com/RustAudio/rodio/blob/d5b9ae3467dab4316ee77b260a5b7432f74866b0/src/conversions/sample_rate.rs
 
+/// The algorithm a [SampleRateConverter] uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResamplerQuality {
+    /// Linear interpolation between neighbouring frames. Cheap enough for
+    /// microcontrollers but without an anti-aliasing filter.
+    #[default]
+    Linear,
+    /// A band-limited windowed-sinc (polyphase FIR) resampler. Avoids aliasing
+    /// at the cost of more computation per sample and a small delay before the
+    /// first sample while the filter is filled.
+    Sinc,
+}
+
 /// Convert a Sound from one sample rate (number of samples per second) to
 /// another.
+///
+/// Uses linear interpolation by default. See [ResamplerQuality].
 pub struct SampleRateConverter<S: Sound> {
     /// The from Sound we are pulling samples from.
     inner: S,
@@ -17,11 +37,12 @@ pub struct SampleRateConverter<S: Sound> {
     /// This is not the samples per second of inner but a possibly scaled down
     /// value.
     from_rate_scaled: u32,
-    /// One sample per channel, extracted from `inner`.
-    current_frame: Vec<i16>,
+    /// One sample per channel, extracted from `inner`. Samples are kept as
+    /// f32 so both the i16 and f32 outputs can be produced without loss.
+    current_frame: Vec<f32>,
     /// The samples right after `current_frame` (one per channel), extracted
     /// from `inner`.
-    next_frame: Vec<i16>,
+    next_frame: Vec<f32>,
     /// Position of `current_sample` modulo `from_rate_scaled`.
     current_frame_pos_in_chunk: u32,
     /// The position of the next sample that this sound should return, modulo
@@ -31,7 +52,7 @@ pub struct SampleRateConverter<S: Sound> {
     /// The buffer containing the samples waiting to be output. Never needs to
     /// contain the first channels sample. The highest channel is stored
     /// first for efficient Vec::pop retrieval
-    output_frame: Vec<i16>,
+    output_frame: Vec<f32>,
     /// The channel count of inner and ourself
     channel_count: u16,
     /// The number of channels has changed. We need to notify the output.
@@ -41,6 +62,9 @@ pub struct SampleRateConverter<S: Sound> {
     channel_count_changed: bool,
     /// Whether the inner channel last returned Paused or Finished
     inner_paused: bool,
+    quality: ResamplerQuality,
+    /// Only used for [ResamplerQuality::Sinc] when the rates differ.
+    sinc: Option<SincResampler>,
 }
 
 impl<S> SampleRateConverter<S>
@@ -50,6 +74,16 @@ where
     /// Create a new SampleRateConverter with an output sample rate of
     /// `to_rate`.
     pub fn new(inner: S, to_rate: u32) -> SampleRateConverter<S> {
+        Self::with_quality(inner, to_rate, ResamplerQuality::default())
+    }
+
+    /// Create a new SampleRateConverter with an output sample rate of
+    /// `to_rate` using the `quality` algorithm.
+    pub fn with_quality(
+        inner: S,
+        to_rate: u32,
+        quality: ResamplerQuality,
+    ) -> SampleRateConverter<S> {
         let channel_count = inner.channel_count();
         let mut new = SampleRateConverter {
             inner,
@@ -64,6 +98,8 @@ where
             channel_count,
             channel_count_changed: false,
             inner_paused: false,
+            quality,
+            sinc: None,
         };
         new.init();
         new
@@ -93,96 +129,134 @@ where
             gcd(from_rate, self.to_rate)
         };
 
-        // These will get filled on the first or next call to next_sample
-        self.current_frame = Vec::new();
-        self.next_frame = Vec::new();
+        // These will get filled on the first or next call to next_sample.
+        // Their memory is kept so that starting over does not allocate.
+        self.current_frame.clear();
+        self.current_frame.reserve(channel_count as usize);
+        self.next_frame.clear();
+        self.next_frame.reserve(channel_count as usize);
 
         self.to_rate_scaled = self.to_rate / gcd;
         self.from_rate_scaled = from_rate / gcd;
         self.current_frame_pos_in_chunk = 0;
         self.next_output_frame_pos_in_chunk = 0;
-        self.output_frame = Vec::with_capacity(channel_count as usize - 1);
+        self.output_frame.clear();
+        self.output_frame.reserve(channel_count as usize);
+        if self.quality != ResamplerQuality::Sinc || self.from_rate_scaled == self.to_rate_scaled {
+            self.sinc = None;
+            return;
+        }
+        match &mut self.sinc {
+            Some(sinc)
+                if sinc.matches(channel_count, self.from_rate_scaled, self.to_rate_scaled) =>
+            {
+                sinc.reset()
+            }
+            sinc => {
+                *sinc = Some(SincResampler::new(
+                    channel_count,
+                    self.from_rate_scaled,
+                    self.to_rate_scaled,
+                ))
+            }
+        }
+    }
+
+    /// The algorithm used to convert the sample rate.
+    pub fn quality(&self) -> ResamplerQuality {
+        self.quality
+    }
+
+    /// Change the algorithm used to convert the sample rate. Any frames
+    /// buffered by the previous algorithm are discarded. Must only be called
+    /// when the next sample is for the first channel in the frame.
+    pub fn set_quality(&mut self, quality: ResamplerQuality) {
+        if self.quality != quality {
+            self.quality = quality;
+            self.init();
+        }
+    }
+
+    fn next_sample_sinc<T: SampleType>(&mut self) -> Result<NextSample<T>, crate::Error> {
+        if let Some(sample) = self.output_frame.pop() {
+            return Ok(NextSample::Sample(T::from_f32(sample)));
+        }
+        let sinc = self.sinc.as_mut().expect("sinc resampler is initialized");
+        self.output_frame.resize(self.channel_count as usize, 0.0);
+        match sinc.next_frame(&mut self.inner, &mut self.output_frame)? {
+            None => {
+                self.output_frame.reverse();
+                let sample = self.output_frame.pop().expect("at least one channel");
+                Ok(NextSample::Sample(T::from_f32(sample)))
+            }
+            Some(NextSample::Sample(_)) => unreachable!(),
+            Some(NextSample::MetadataChanged) => {
+                self.output_frame.clear();
+                self.init();
+                self.next_sample_of()
+            }
+            Some(NextSample::Paused) => {
+                self.output_frame.clear();
+                Ok(NextSample::Paused)
+            }
+            Some(NextSample::Finished) => {
+                self.output_frame.clear();
+                // Start over in case more samples become available.
+                self.init();
+                Ok(NextSample::Finished)
+            }
+        }
     }
 
     fn fill_frames(&mut self) -> Result<bool, crate::Error> {
-        let (first_samples, next_samples) = if self.from_rate_scaled == self.to_rate_scaled {
-            (Vec::new(), Vec::new())
-        } else {
-            let mut collect_frame = || match self.inner.next_frame() {
-                Ok(f) => Ok(f),
-                Err(special) => match special {
-                    Ok(NextSample::Sample(_)) => unreachable!(),
-                    Ok(NextSample::MetadataChanged) => Err(None),
-                    Err(e) => Err(Some(e)),
-                    Ok(NextSample::Paused) => {
-                        self.inner_paused = true;
-                        Ok(Vec::new())
-                    }
-                    Ok(NextSample::Finished) => {
-                        self.inner_paused = false;
-                        Ok(Vec::new())
-                    }
-                },
-            };
-            let first = match collect_frame() {
-                Ok(o) => o,
-                Err(Some(e)) => return Err(e),
-                Err(None) => return Ok(false),
-            };
-            let next = match collect_frame() {
-                Ok(o) => o,
-                Err(Some(e)) => return Err(e),
-                Err(None) => return Ok(false),
-            };
-            (first, next)
-        };
-        self.current_frame = first_samples;
-        self.next_frame = next_samples;
-        Ok(true)
+        if self.from_rate_scaled == self.to_rate_scaled {
+            self.current_frame.clear();
+            self.next_frame.clear();
+            return Ok(true);
+        }
+        let mut first = std::mem::take(&mut self.current_frame);
+        let mut next = std::mem::take(&mut self.next_frame);
+        let filled = self.pull_frame(&mut first)? && self.pull_frame(&mut next)?;
+        self.current_frame = first;
+        self.next_frame = next;
+        Ok(filled)
     }
 
     fn next_input_frame(&mut self) -> Result<bool, crate::Error> {
         self.current_frame_pos_in_chunk += 1;
 
         std::mem::swap(&mut self.current_frame, &mut self.next_frame);
-        self.next_frame.clear();
-        let specials = self.inner.append_next_frame_to(&mut self.next_frame);
-        match specials {
-            Ok(()) => (),
-            Err(Ok(NextSample::Sample(_))) => unreachable!(),
-            Err(Ok(NextSample::MetadataChanged)) => {
+        let mut next = std::mem::take(&mut self.next_frame);
+        let result = self.pull_frame(&mut next);
+        self.next_frame = next;
+        result
+    }
+
+    /// Read the next frame of inner into `frame`. `frame` is left empty if
+    /// inner is paused or finished. Returns false if the metadata of inner
+    /// changed.
+    fn pull_frame(&mut self, frame: &mut Vec<f32>) -> Result<bool, crate::Error> {
+        // Pull the whole frame at once so a boxed inner sound costs a single
+        // dynamic call per frame.
+        frame.resize(self.inner.channel_count() as usize, 0.0);
+        let (num_frames, next) = self.inner.fill_buffer_f32(frame)?;
+        if num_frames == 0 {
+            frame.clear();
+        }
+        match next {
+            None => (),
+            Some(NextSample::Sample(_)) => unreachable!(),
+            Some(NextSample::MetadataChanged) => {
                 return Ok(false);
             }
             // We handle not having any more samples left outside this function
-            Err(Ok(NextSample::Paused)) => self.inner_paused = true,
-            Err(Ok(NextSample::Finished)) => self.inner_paused = false,
-            Err(Err(e)) => return Err(e),
+            Some(NextSample::Paused) => self.inner_paused = true,
+            Some(NextSample::Finished) => self.inner_paused = false,
         }
         Ok(true)
     }
 
-    /// Unwrap the inner Sound.
-    ///
-    /// It is guaranteed that the inner Sound is at the start of a Frame.
-    /// (i.e. the inner sound has not been partially incremented inside a frame)
-    pub fn into_inner(self) -> S {
-        self.inner
-    }
-}
-
-impl<S> Sound for SampleRateConverter<S>
-where
-    S: Sound,
-{
-    fn channel_count(&self) -> u16 {
-        self.inner.channel_count()
-    }
-
-    fn sample_rate(&self) -> u32 {
-        self.to_rate
-    }
-
-    fn next_sample(&mut self) -> Result<NextSample, crate::Error> {
+    fn next_sample_of<T: SampleType>(&mut self) -> Result<NextSample<T>, crate::Error> {
         if self.channel_count_changed {
             self.channel_count_changed = false;
             return Ok(NextSample::MetadataChanged);
@@ -191,7 +265,7 @@ where
         // self.to_rate_scaled`
         if self.from_rate_scaled == self.to_rate_scaled {
             debug_assert_eq!(self.from_rate_scaled, 1);
-            let next = self.inner.next_sample()?;
+            let next = T::next_sample_of(&mut self.inner)?;
             match next {
                 NextSample::Sample(_) | NextSample::Paused | NextSample::Finished => {
                     return Ok(next);
@@ -205,16 +279,20 @@ where
             }
         }
 
+        if self.sinc.is_some() {
+            return self.next_sample_sinc();
+        }
+
         // Short circuit if there are some samples waiting in the already processed
         // frame
         if let Some(sample) = self.output_frame.pop() {
-            return Ok(NextSample::Sample(sample));
+            return Ok(NextSample::Sample(T::from_f32(sample)));
         }
 
         // Coming back from being paused or first run. Refill our frames.
         if self.current_frame.is_empty() && !self.fill_frames()? {
             self.init();
-            return self.next_sample();
+            return self.next_sample_of();
         }
 
         // The frame we are going to return from this function will be a linear
@@ -226,12 +304,12 @@ where
 
             if !self.next_input_frame()? {
                 self.init();
-                return self.next_sample();
+                return self.next_sample_of();
             }
             while self.current_frame_pos_in_chunk != self.from_rate_scaled {
                 if !self.next_input_frame()? {
                     self.init();
-                    return self.next_sample();
+                    return self.next_sample_of();
                 }
             }
             self.current_frame_pos_in_chunk = 0;
@@ -247,7 +325,7 @@ where
             while self.current_frame_pos_in_chunk != req_left_sample {
                 if !self.next_input_frame()? {
                     self.init();
-                    return self.next_sample();
+                    return self.next_sample_of();
                 }
                 debug_assert!(self.current_frame_pos_in_chunk < self.from_rate_scaled);
             }
@@ -265,7 +343,7 @@ where
             let has_next = self.next_input_frame()?;
             if !has_next {
                 self.init();
-                return self.next_sample();
+                return self.next_sample_of();
             }
         }
         for (index, (cur, next)) in self
@@ -276,12 +354,17 @@ where
             // push frames in reverse for efficient retrieval
             .rev()
         {
-            let sample = linear_interpolation(*cur, *next, numerator, self.to_rate_scaled);
+            let sample = T::linear_interpolation(
+                T::from_f32(*cur),
+                T::from_f32(*next),
+                numerator,
+                self.to_rate_scaled,
+            );
 
             if index == 0 {
                 result = Some(sample);
             } else {
-                self.output_frame.push(sample);
+                self.output_frame.push(sample.to_f32());
             }
         }
 
@@ -295,7 +378,7 @@ where
             // current_frame to the output
             if !self.current_frame.is_empty() {
                 self.current_frame.reverse();
-                let r = NextSample::Sample(self.current_frame.pop().unwrap());
+                let r = NextSample::Sample(T::from_f32(self.current_frame.pop().unwrap()));
                 std::mem::swap(&mut self.output_frame, &mut self.current_frame);
                 debug_assert!(self.current_frame.is_empty());
                 Ok(r)
@@ -312,15 +395,111 @@ where
         }
     }
 
+    fn fill_buffer_of<T: SampleType>(
+        &mut self,
+        buffer: &mut [T],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        if self.from_rate_scaled != self.to_rate_scaled || self.channel_count_changed {
+            return fill_buffer_from_next_sample(self, buffer);
+        }
+        let (num_frames, next) = T::fill_buffer_of(&mut self.inner, buffer)?;
+        if next == Some(NextSample::MetadataChanged) && self.inner.sample_rate() != self.to_rate {
+            self.init();
+        }
+        Ok((num_frames, next))
+    }
+
+    /// Get a reference to the wrapped inner Sound.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the wrapped inner Sound.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Unwrap the inner Sound.
+    ///
+    /// It is guaranteed that the inner Sound is at the start of a Frame.
+    /// (i.e. the inner sound has not been partially incremented inside a frame)
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Sound for SampleRateConverter<S>
+where
+    S: Sound,
+{
+    fn channel_count(&self) -> u16 {
+        self.inner.channel_count()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.to_rate
+    }
+
+    fn next_sample(&mut self) -> Result<NextSample, crate::Error> {
+        self.next_sample_of()
+    }
+
+    fn next_sample_f32(&mut self) -> Result<NextSample<f32>, crate::Error> {
+        self.next_sample_of()
+    }
+
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.fill_buffer_of(buffer)
+    }
+
+    fn fill_buffer_f32(
+        &mut self,
+        buffer: &mut [f32],
+    ) -> Result<(usize, Option<NextSample>), crate::Error> {
+        self.fill_buffer_of(buffer)
+    }
+
     fn on_start_of_batch(&mut self) {
         self.inner.on_start_of_batch()
     }
+
+    /// The position of the inner sound excluding the frames buffered for
+    /// interpolation.
+    fn position(&self) -> Option<Duration> {
+        let inner_position = self.inner.position()?;
+        let buffered_frames = match &self.sinc {
+            Some(sinc) => sinc.lookahead() as u64,
+            None if self.next_frame.is_empty() => return Some(inner_position),
+            None => 1,
+        };
+        let buffered = utils::num_samples_to_duration(buffered_frames, 1, self.inner.sample_rate());
+        Some(inner_position.saturating_sub(buffered))
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+}
+
+/// Any frames buffered for interpolation are discarded when seeking.
+impl<S> Seek for SampleRateConverter<S>
+where
+    S: Sound + Seek,
+{
+    fn seek(&mut self, position: Duration) -> Result<Duration, crate::Error> {
+        let actual = self.inner.seek(position)?;
+        self.init();
+        Ok(actual)
+    }
 }
 
 impl<S: Sound> Wrapper for SampleRateConverter<S> {
     type Inner = S;
 
-    fn inner(&self) -> &S {
+    fn inner(&self) -> &Self::Inner {
         &self.inner
     }
 
@@ -328,15 +507,11 @@ impl<S: Sound> Wrapper for SampleRateConverter<S> {
         &mut self.inner
     }
 
-    fn into_inner(self) -> S {
+    fn into_inner(self) -> Self::Inner {
         self.inner
     }
 }
 
-fn linear_interpolation(first: i16, second: i16, numerator: u32, denominator: u32) -> i16 {
-    (first as i64 + (second as i64 - first as i64) * numerator as i64 / denominator as i64) as i16
-}
-
 #[cfg(test)]
 #[path = "./tests/sample_rate_converter.rs"]
 mod tests;
@@ -0,0 +1,200 @@
+//! The windowed-sinc resampler used by
+//! [SampleRateConverter][super::SampleRateConverter] for
+//! [ResamplerQuality::Sinc][super::ResamplerQuality::Sinc].
+
+use crate::{NextSample, Sound};
+
+/// The number of zero crossings of the sinc on each side of the kernel when
+/// upsampling. Downsampling widens the kernel to keep the same number of zero
+/// crossings of the lower cutoff.
+const ZERO_CROSSINGS: usize = 16;
+
+/// Limits the kernel size for extreme downsampling ratios.
+const MAX_HALF_WIDTH: usize = 256;
+
+/// The number of precomputed kernel phases between two input frames. Phases in
+/// between are linearly interpolated.
+const NUM_PHASES: usize = 128;
+
+/// The cutoff relative to the lower of the input and output Nyquist
+/// frequencies. Slightly below 1 so the transition band is mostly attenuated
+/// before the Nyquist frequency.
+const CUTOFF: f64 = 0.95;
+
+/// A band-limited polyphase FIR resampler.
+///
+/// Output frames are interpolated from `2 * half_width` input frames around
+/// the output position, so the resampler reads `half_width` frames ahead of
+/// the frame it is outputting.
+pub(crate) struct SincResampler {
+    channel_count: usize,
+    half_width: usize,
+    from_rate_scaled: u32,
+    to_rate_scaled: u32,
+    /// `NUM_PHASES + 1` rows of `2 * half_width` coefficients.
+    table: Vec<f32>,
+    /// The last `2 * half_width` input frames, interleaved. The frame at index
+    /// `half_width - 1` is the input frame at or right before the output
+    /// position.
+    frames: Vec<f32>,
+    /// A single frame pulled from inner.
+    incoming: Vec<f32>,
+    /// The position of the next output frame past the frame at
+    /// `half_width - 1`, in units of 1 / `to_rate_scaled` input frames.
+    phase: u32,
+    /// The number of input frames that must be pulled before the next output
+    /// frame can be computed.
+    frames_needed: usize,
+    /// The number of silent frames pulled after inner finished.
+    zeros_fed: usize,
+}
+
+impl SincResampler {
+    pub(crate) fn new(channel_count: u16, from_rate_scaled: u32, to_rate_scaled: u32) -> Self {
+        let channel_count = channel_count as usize;
+        // Relative to the input Nyquist frequency
+        let cutoff = CUTOFF * (to_rate_scaled as f64 / from_rate_scaled as f64).min(1.0);
+        let half_width = ((ZERO_CROSSINGS as f64 / cutoff).ceil() as usize).min(MAX_HALF_WIDTH);
+        let num_taps = 2 * half_width;
+
+        let mut table = Vec::with_capacity((NUM_PHASES + 1) * num_taps);
+        for phase in 0..=NUM_PHASES {
+            let frac = phase as f64 / NUM_PHASES as f64;
+            let row_start = table.len();
+            for tap in 0..num_taps {
+                // Distance of the input frame from the output position
+                let distance = tap as f64 - (half_width as f64 - 1.0) - frac;
+                table.push(kernel(distance, cutoff, half_width as f64));
+            }
+            // Normalize each phase so a constant input stays constant.
+            let sum: f32 = table[row_start..].iter().sum();
+            for coefficient in &mut table[row_start..] {
+                *coefficient /= sum;
+            }
+        }
+
+        SincResampler {
+            channel_count,
+            half_width,
+            from_rate_scaled,
+            to_rate_scaled,
+            table,
+            frames: vec![0.0; num_taps * channel_count],
+            incoming: vec![0.0; channel_count],
+            phase: 0,
+            frames_needed: half_width + 1,
+            zeros_fed: 0,
+        }
+    }
+
+    /// Whether this resampler converts between the same rates and channel
+    /// count, i.e. can be [reset][SincResampler::reset] instead of created
+    /// again.
+    pub(crate) fn matches(
+        &self,
+        channel_count: u16,
+        from_rate_scaled: u32,
+        to_rate_scaled: u32,
+    ) -> bool {
+        self.channel_count == channel_count as usize
+            && self.from_rate_scaled == from_rate_scaled
+            && self.to_rate_scaled == to_rate_scaled
+    }
+
+    /// Forget all input frames as if newly created. Does not allocate.
+    pub(crate) fn reset(&mut self) {
+        self.frames.fill(0.0);
+        self.phase = 0;
+        self.frames_needed = self.half_width + 1;
+        self.zeros_fed = 0;
+    }
+
+    /// The number of input frames read ahead of the output.
+    pub(crate) fn lookahead(&self) -> usize {
+        self.half_width
+    }
+
+    /// Compute the next output frame into `output` pulling frames from
+    /// `inner` as needed.
+    ///
+    /// Returns None if `output` was filled. If inner is paused the state is
+    /// kept so playback continues seamlessly once inner resumes. Finished is
+    /// returned once the output position passes the last frame of inner.
+    pub(crate) fn next_frame<S: Sound + ?Sized>(
+        &mut self,
+        inner: &mut S,
+        output: &mut [f32],
+    ) -> Result<Option<NextSample>, crate::Error> {
+        debug_assert_eq!(output.len(), self.channel_count);
+        while self.frames_needed > 0 {
+            if self.zeros_fed == 0 {
+                let (num_frames, next) = inner.fill_buffer_f32(&mut self.incoming)?;
+                if num_frames == 0 {
+                    match next {
+                        Some(NextSample::Finished) => {
+                            self.incoming.fill(0.0);
+                            self.zeros_fed = 1;
+                        }
+                        Some(next) => return Ok(Some(next)),
+                        None => unreachable!("a frame was requested"),
+                    }
+                }
+            } else {
+                self.zeros_fed += 1;
+            }
+            if self.zeros_fed > self.half_width {
+                return Ok(Some(NextSample::Finished));
+            }
+            self.frames.copy_within(self.channel_count.., 0);
+            let newest = self.frames.len() - self.channel_count;
+            self.frames[newest..].copy_from_slice(&self.incoming);
+            self.frames_needed -= 1;
+        }
+
+        let num_taps = 2 * self.half_width;
+        let phase_position = self.phase as f64 / self.to_rate_scaled as f64 * NUM_PHASES as f64;
+        let phase_idx = (phase_position as usize).min(NUM_PHASES - 1);
+        let weight = (phase_position - phase_idx as f64) as f32;
+        let first = &self.table[phase_idx * num_taps..][..num_taps];
+        let second = &self.table[(phase_idx + 1) * num_taps..][..num_taps];
+
+        output.fill(0.0);
+        for ((frame, a), b) in self
+            .frames
+            .chunks_exact(self.channel_count)
+            .zip(first)
+            .zip(second)
+        {
+            let coefficient = a + (b - a) * weight;
+            for (out, sample) in output.iter_mut().zip(frame) {
+                *out += sample * coefficient;
+            }
+        }
+
+        self.phase += self.from_rate_scaled;
+        while self.phase >= self.to_rate_scaled {
+            self.phase -= self.to_rate_scaled;
+            self.frames_needed += 1;
+        }
+        Ok(None)
+    }
+}
+
+/// A low pass sinc with `cutoff` relative to the Nyquist frequency, windowed by
+/// a Blackman window spanning `half_width` frames on each side.
+fn kernel(distance: f64, cutoff: f64, half_width: f64) -> f32 {
+    use std::f64::consts::PI;
+
+    if distance.abs() >= half_width {
+        return 0.0;
+    }
+    let x = cutoff * distance;
+    let sinc = if x == 0.0 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    };
+    let n = (distance / half_width + 1.0) / 2.0;
+    let window = 0.42 - 0.5 * (2.0 * PI * n).cos() + 0.08 * (4.0 * PI * n).cos();
+    (cutoff * sinc * window) as f32
+}
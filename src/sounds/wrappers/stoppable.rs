@@ -1,6 +1,9 @@
+use std::time::Duration;
+
 use crate::Sound;
 
-use super::{SetSpeed, SetVolume};
+use super::wrapper::forward_to_inner;
+use super::Seek;
 
 /// A Sound which can be stopped.
 pub trait SetStopped {
@@ -63,9 +66,44 @@ where
         self.inner.next_sample()
     }
 
+    fn fill_buffer(
+        &mut self,
+        buffer: &mut [i16],
+    ) -> Result<(usize, Option<crate::NextSample>), crate::Error> {
+        if self.stopped {
+            return Ok((0, Some(crate::NextSample::Finished)));
+        }
+        self.inner.fill_buffer(buffer)
+    }
+
+    fn next_sample_f32(&mut self) -> Result<crate::NextSample<f32>, crate::Error> {
+        if self.stopped {
+            return Ok(crate::NextSample::Finished);
+        }
+        self.inner.next_sample_f32()
+    }
+
+    fn fill_buffer_f32(
+        &mut self,
+        buffer: &mut [f32],
+    ) -> Result<(usize, Option<crate::NextSample>), crate::Error> {
+        if self.stopped {
+            return Ok((0, Some(crate::NextSample::Finished)));
+        }
+        self.inner.fill_buffer_f32(buffer)
+    }
+
     fn on_start_of_batch(&mut self) {
         self.inner.on_start_of_batch()
     }
+
+    fn position(&self) -> Option<Duration> {
+        self.inner.position()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
 }
 
 impl<S> Stoppable<S>
@@ -87,21 +125,14 @@ where
     }
 }
 
-impl<S> SetVolume for Stoppable<S>
-where
-    S: Sound + SetVolume,
-{
-    fn set_volume(&mut self, multiplier: f32) {
-        self.inner.set_volume(multiplier)
-    }
-}
+forward_to_inner!(Stoppable: SetSpeed, SetTempo, SetPitch, SetVolume, SetPan, SetFade);
 
-impl<S> SetSpeed for Stoppable<S>
+impl<S> Seek for Stoppable<S>
 where
-    S: Sound + SetSpeed,
+    S: Sound + Seek,
 {
-    fn set_speed(&mut self, multiplier: f32) {
-        self.inner.set_speed(multiplier)
+    fn seek(&mut self, position: Duration) -> Result<Duration, crate::Error> {
+        self.inner.seek(position)
     }
 }
 
@@ -75,3 +75,57 @@ fn metadata_changed_notification() {
         crate::NextSample::Sample(1000)
     );
 }
+
+#[test]
+fn position_and_total_duration_in_adjusted_time() {
+    let inner =
+        crate::sounds::MemorySound::from_samples(std::sync::Arc::new(vec![0; 100]), 1, 1000);
+    let mut sound = inner.with_adjustable_speed_of(2.0);
+    assert_eq!(sound.total_duration(), Some(Duration::from_millis(50)));
+    for _ in 0..10 {
+        sound.next_sample().unwrap();
+    }
+    assert_eq!(sound.position(), Some(Duration::from_millis(5)));
+}
+
+#[test]
+fn ramp_speed_updates_every_block() {
+    let mut sound = ConstantValueSound::new(1000).with_adjustable_speed();
+    sound.ramp_speed(2.0, std::time::Duration::from_millis(10), Ramp::Linear);
+    let mut buffer = [0; 1024];
+    // The first block is at the old speed.
+    assert_eq!(
+        sound.fill_buffer(&mut buffer).unwrap(),
+        (128, Some(crate::NextSample::MetadataChanged))
+    );
+    assert!(sound.sample_rate() > DEFAULT_SAMPLE_RATE);
+    assert!(sound.speed() < 2.0);
+
+    let mut frames = 128;
+    while sound.speed() < 2.0 {
+        let (num_frames, next) = sound.fill_buffer(&mut buffer).unwrap();
+        assert_eq!(next, Some(crate::NextSample::MetadataChanged));
+        assert!(num_frames <= 128);
+        frames += num_frames;
+    }
+    assert_eq!(sound.sample_rate(), DEFAULT_SAMPLE_RATE * 2);
+    // 10ms at a speed going from 1.0 to 2.0.
+    assert!((600..720).contains(&frames), "{frames}");
+    assert_eq!(sound.fill_buffer(&mut buffer).unwrap(), (512, None));
+}
+
+#[test]
+fn seek_with_invalid_speed_is_an_error() {
+    let samples = std::sync::Arc::new(vec![0; 48000]);
+    let mut sound =
+        crate::sounds::MemorySound::from_samples(samples, 1, 48000).with_adjustable_speed();
+    sound.set_speed(2.0);
+    assert_eq!(
+        sound.seek(Duration::from_millis(250)).unwrap(),
+        Duration::from_millis(250)
+    );
+    for speed in [-1.0, f32::NAN, f32::INFINITY] {
+        sound.set_speed(speed);
+        assert!(sound.seek(Duration::from_millis(250)).is_err());
+    }
+}
@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use super::*;
+use crate::{
+    sounds::{wrappers::SetPaused, MemorySound},
+    tests::{ConstantValueSound, Sawtooth},
+    NextSample,
+};
+
+fn sine(frequency: f64, num_frames: usize, sample_rate: u32) -> MemorySound {
+    let samples = (0..num_frames)
+        .map(|i| {
+            let phase = 2.0 * std::f64::consts::PI * frequency * i as f64 / sample_rate as f64;
+            (10000.0 * phase.sin()).round() as i16
+        })
+        .collect();
+    MemorySound::from_samples(Arc::new(samples), 1, sample_rate)
+}
+
+fn collect(sound: &mut dyn Sound) -> Vec<i16> {
+    let mut samples = Vec::new();
+    loop {
+        match sound.next_sample().unwrap() {
+            NextSample::Sample(s) => samples.push(s),
+            NextSample::Finished => return samples,
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+}
+
+/// The number of times the samples go from negative to non-negative.
+fn rising_zero_crossings(samples: &[i16]) -> usize {
+    samples
+        .windows(2)
+        .filter(|pair| pair[0] < 0 && pair[1] >= 0)
+        .count()
+}
+
+#[test]
+fn normal_tempo_passes_through() {
+    let mut sound = Sawtooth::new(2, 1000).with_adjustable_tempo();
+    let mut inner = Sawtooth::new(2, 1000);
+    for _ in 0..100 {
+        assert_eq!(sound.next_sample().unwrap(), inner.next_sample().unwrap());
+    }
+}
+
+#[test]
+fn faster_tempo_shortens_sound() {
+    let mut sound = sine(441.0, 44100, 44100).with_adjustable_tempo_of(2.0);
+    assert_eq!(sound.total_duration(), Some(Duration::from_millis(500)));
+    let samples = collect(&mut sound);
+    assert!(samples.len().abs_diff(22050) < 1000, "{}", samples.len());
+}
+
+#[test]
+fn slower_tempo_keeps_pitch() {
+    let mut sound = sine(441.0, 44100, 44100).with_adjustable_tempo_of(0.5);
+    let samples = collect(&mut sound);
+    assert!(samples.len().abs_diff(88200) < 1000, "{}", samples.len());
+    // One second in the middle still has 441 cycles.
+    let crossings = rising_zero_crossings(&samples[22050..66150]);
+    assert!(crossings.abs_diff(441) <= 2, "{crossings}");
+}
+
+#[test]
+fn constant_stays_constant() {
+    let mut sound = ConstantValueSound::new(1000).with_adjustable_tempo_of(1.5);
+    for _ in 0..10000 {
+        assert_eq!(sound.next_sample().unwrap(), NextSample::Sample(1000));
+    }
+    let mut buffer = [0; 4000];
+    assert_eq!(sound.fill_buffer(&mut buffer).unwrap(), (2000, None));
+    assert!(buffer.iter().all(|s| *s == 1000));
+}
+
+#[test]
+fn paused_inner_resumes() {
+    let mut sound = ConstantValueSound::new(1000)
+        .pausable()
+        .with_adjustable_tempo_of(0.8);
+    for _ in 0..1000 {
+        assert_eq!(sound.next_sample().unwrap(), NextSample::Sample(1000));
+    }
+    sound.set_paused(true);
+    // Samples read ahead are played before pausing.
+    let mut paused = false;
+    for _ in 0..10000 {
+        match sound.next_sample().unwrap() {
+            NextSample::Sample(1000) => {}
+            NextSample::Paused => {
+                paused = true;
+                break;
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+    assert!(paused);
+    sound.set_paused(false);
+    assert_eq!(sound.next_sample().unwrap(), NextSample::Sample(1000));
+}
+
+#[test]
+fn tempo_set_through_controller() {
+    let (mut sound, mut controller) = sine(441.0, 44100, 44100)
+        .with_adjustable_tempo()
+        .controllable();
+    controller.set_tempo(2.0);
+    sound.on_start_of_batch();
+    let mut num_samples: usize = 0;
+    loop {
+        match sound.next_sample().unwrap() {
+            NextSample::Sample(_) => num_samples += 1,
+            NextSample::Paused => break,
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+    assert!(num_samples.abs_diff(22050) < 1000, "{num_samples}");
+}
@@ -1,9 +1,13 @@
+use std::time::Duration;
+
 use super::*;
+use crate::sounds::wrappers::Easing;
 use crate::tests::ConstantValueSound;
 
 #[test]
 fn adjust_down() {
     let mut first = ConstantValueSound::new(1000).with_adjustable_volume();
+    first.set_ramp_duration(Duration::ZERO);
     first.set_volume(0.5);
     assert_eq!(first.next_sample().unwrap(), crate::NextSample::Sample(500));
 }
@@ -11,6 +15,7 @@ fn adjust_down() {
 #[test]
 fn adjust_up() {
     let mut first = ConstantValueSound::new(1000).with_adjustable_volume();
+    first.set_ramp_duration(Duration::ZERO);
     first.set_volume(5.0);
     assert_eq!(
         first.next_sample().unwrap(),
@@ -21,9 +26,120 @@ fn adjust_up() {
 #[test]
 fn test_saturation() {
     let mut first = ConstantValueSound::new(1000).with_adjustable_volume();
+    first.set_ramp_duration(Duration::ZERO);
     first.set_volume(1000.0);
     assert_eq!(
         first.next_sample().unwrap(),
         crate::NextSample::Sample(i16::MAX)
     );
 }
+
+#[test]
+fn fill_buffer() {
+    let mut first = ConstantValueSound::new(1000).with_adjustable_volume_of(0.5);
+    let mut buffer = [0; 4];
+    assert_eq!(first.fill_buffer(&mut buffer).unwrap(), (2, None));
+    assert_eq!(buffer, [500; 4]);
+}
+
+#[test]
+fn f32_is_not_clipped() {
+    let mut first = ConstantValueSound::new(16384).with_adjustable_volume_of(4.0);
+    assert_eq!(
+        first.next_sample_f32().unwrap(),
+        crate::NextSample::Sample(2.0)
+    );
+    let mut buffer = [0.0; 2];
+    assert_eq!(first.fill_buffer_f32(&mut buffer).unwrap(), (1, None));
+    assert_eq!(buffer, [2.0; 2]);
+}
+
+#[test]
+fn volume_changes_are_ramped() {
+    let mut first = ConstantValueSound::new(1000).with_adjustable_volume();
+    first.set_ramp_duration(Duration::from_millis(1));
+    first.set_volume(0.0);
+    // 44 frames in 1ms at 44100. Both channels of a frame get the same gain.
+    let mut buffer = [0; 90];
+    assert_eq!(first.fill_buffer(&mut buffer).unwrap(), (45, None));
+    assert_eq!(buffer[..2], [1000, 1000]);
+    for frame in buffer
+        .chunks_exact(2)
+        .take(44)
+        .collect::<Vec<_>>()
+        .windows(2)
+    {
+        assert_eq!(frame[0][0], frame[0][1]);
+        assert!(frame[1][0] < frame[0][0]);
+    }
+    assert_eq!(buffer[88..], [0, 0]);
+    assert_eq!(first.next_sample().unwrap(), crate::NextSample::Sample(0));
+
+    // Ramps start from the gain in the middle of a ramp.
+    first.set_volume(1.0);
+    first.set_volume(0.5);
+    assert_eq!(first.volume(), 0.5);
+    first.next_sample().unwrap();
+    let mut buffer = [0; 100];
+    first.fill_buffer(&mut buffer).unwrap();
+    assert!(buffer[..86].iter().all(|s| *s < 500));
+    assert_eq!(buffer[87..], [500; 13]);
+}
+
+#[test]
+fn set_volume_db() {
+    let mut first = ConstantValueSound::new(1000).with_adjustable_volume();
+    first.set_ramp_duration(Duration::ZERO);
+    first.set_volume_db(-20.0);
+    assert_eq!(first.next_sample().unwrap(), crate::NextSample::Sample(100));
+    first.set_volume_db(f32::NEG_INFINITY);
+    assert_eq!(first.next_sample().unwrap(), crate::NextSample::Sample(0));
+}
+
+#[test]
+fn volume_curves() {
+    assert_eq!(VolumeCurve::Linear.gain(0.5), 0.5);
+    assert_eq!(VolumeCurve::Cubic.gain(0.5), 0.125);
+    assert_eq!(VolumeCurve::Cubic.gain(2.0), 1.0);
+    let decibels = VolumeCurve::Decibels { range_db: 60.0 };
+    assert_eq!(decibels.gain(0.0), 0.0);
+    assert_eq!(decibels.gain(1.0), 1.0);
+    assert!((decibels.gain(0.5) - 0.031_622_8).abs() < 1e-6);
+}
+
+#[test]
+fn ramp_volume_linear_and_exponential() {
+    let mut sound =
+        crate::sounds::MemorySound::from_samples(std::sync::Arc::new(vec![1000; 20]), 1, 1000)
+            .with_adjustable_volume();
+    sound.ramp_volume(0.0, Duration::from_millis(4), Ramp::Linear);
+    let mut buffer = [0; 6];
+    assert_eq!(sound.fill_buffer(&mut buffer).unwrap(), (6, None));
+    assert_eq!(buffer, [1000, 750, 500, 250, 0, 0]);
+    assert_eq!(sound.volume(), 0.0);
+
+    sound.set_ramp_duration(Duration::ZERO);
+    sound.set_volume(1.0);
+    sound.ramp_volume(1.0 / 16.0, Duration::from_millis(4), Ramp::Exponential);
+    let samples: Vec<_> = (0..5).map(|_| sound.next_sample().unwrap()).collect();
+    assert_eq!(
+        samples,
+        [1000, 500, 250, 125, 62].map(crate::NextSample::Sample)
+    );
+}
+
+#[test]
+fn tween_volume_chain() {
+    let mut sound =
+        crate::sounds::MemorySound::from_samples(std::sync::Arc::new(vec![1000; 20]), 1, 1000)
+            .with_adjustable_volume();
+    sound.start_volume_tween(
+        Tween::new(0.0, Duration::from_millis(2), Easing::OutCubic)
+            .then_hold(Duration::from_millis(1))
+            .then(1.0, Duration::from_millis(2), Easing::InQuad),
+    );
+    let mut buffer = [0; 7];
+    assert_eq!(sound.fill_buffer(&mut buffer).unwrap(), (7, None));
+    assert_eq!(buffer, [1000, 125, 0, 0, 250, 1000, 1000]);
+    assert_eq!(sound.volume(), 1.0);
+}
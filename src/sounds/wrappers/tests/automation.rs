@@ -0,0 +1,79 @@
+use super::*;
+
+#[test]
+fn linear_ramp() {
+    let mut tweener = Tweener::new(
+        0.0,
+        Tween::ramp(1.0, Duration::from_millis(4), Ramp::Linear),
+    );
+    assert_eq!(tweener.value_at(0, 1000), 0.0);
+    assert_eq!(tweener.value_at(1, 1000), 0.25);
+    assert_eq!(tweener.value_at(8, 1000), 1.0);
+    assert!(!tweener.advance(3, 1000));
+    assert_eq!(tweener.value_at(0, 1000), 0.75);
+    assert!(tweener.advance(1, 1000));
+    assert_eq!(tweener.value_at(0, 1000), 1.0);
+}
+
+#[test]
+fn exponential_ramp() {
+    let tween = Tween::ramp(16.0, Duration::from_millis(4), Ramp::Exponential);
+    let tweener = Tweener::new(1.0, tween);
+    assert_eq!(tweener.value_at(1, 1000), 2.0);
+    assert_eq!(tweener.value_at(2, 1000), 4.0);
+    assert_eq!(tweener.value_at(4, 1000), 16.0);
+}
+
+#[test]
+fn exponential_ramp_to_zero_is_linear() {
+    let tween = Tween::ramp(0.0, Duration::from_millis(4), Ramp::Exponential);
+    assert_eq!(Tweener::new(1.0, tween).value_at(2, 1000), 0.5);
+}
+
+#[test]
+fn zero_duration_finishes_immediately() {
+    let tweener = Tweener::new(1.0, Tween::ramp(0.0, Duration::ZERO, Ramp::Linear));
+    assert!(tweener.is_finished());
+    assert_eq!(tweener.target(), 0.0);
+}
+
+#[test]
+fn easings_start_at_zero_and_end_at_one() {
+    let easings = [
+        Easing::Linear,
+        Easing::InQuad,
+        Easing::OutQuad,
+        Easing::InOutQuad,
+        Easing::InCubic,
+        Easing::OutCubic,
+        Easing::InOutCubic,
+        Easing::InElastic,
+        Easing::OutElastic,
+        Easing::InOutElastic,
+    ];
+    for easing in easings {
+        assert_eq!(easing.ease(0.0), 0.0, "{easing:?}");
+        assert!((easing.ease(1.0) - 1.0).abs() < 1e-6, "{easing:?}");
+    }
+    assert_eq!(Easing::InQuad.ease(0.5), 0.25);
+    assert_eq!(Easing::OutCubic.ease(0.5), 0.875);
+    assert_eq!(Easing::InOutCubic.ease(0.25), 0.0625);
+    // Elastic easing overshoots the target.
+    assert!((1..100).any(|i| Easing::OutElastic.ease(i as f32 / 100.0) > 1.0));
+}
+
+#[test]
+fn chained_steps() {
+    let tween = Tween::new(1.0, Duration::from_millis(2), Easing::Linear)
+        .then_hold(Duration::from_millis(2))
+        .then(0.0, Duration::from_millis(4), Easing::InQuad);
+    assert_eq!(tween.target(), Some(0.0));
+    let mut tweener = Tweener::new(0.0, tween);
+    let values: Vec<_> = (0..9).map(|i| tweener.value_at(i, 1000)).collect();
+    assert_eq!(values, [0.0, 0.5, 1.0, 1.0, 1.0, 0.9375, 0.75, 0.4375, 0.0]);
+
+    assert!(!tweener.advance(5, 1000));
+    assert_eq!(tweener.value_at(0, 1000), 0.9375);
+    assert!(tweener.advance(3, 1000));
+    assert_eq!(tweener.value_at(0, 1000), 0.0);
+}
@@ -0,0 +1,117 @@
+use std::sync::Arc;
+
+use crate::{sounds::MemorySound, tests::DEFAULT_SAMPLE_RATE, NextSample, Sound};
+
+use super::*;
+
+fn memory_sound(samples: Vec<i16>, channel_count: u16) -> MemorySound {
+    MemorySound::from_samples(Arc::new(samples), channel_count, DEFAULT_SAMPLE_RATE)
+}
+
+fn collect_samples<S: Sound>(sound: &mut S) -> Vec<i16> {
+    let mut samples = Vec::new();
+    while let NextSample::Sample(s) = sound.next_sample().unwrap() {
+        samples.push(s);
+    }
+    samples
+}
+
+#[test]
+fn mono_and_stereo() {
+    let mut converter = ChannelCountConverter::new(memory_sound(vec![1, 2], 1), 2);
+    assert_eq!(collect_samples(&mut converter), [1, 1, 2, 2]);
+    let mut converter = ChannelCountConverter::new(memory_sound(vec![1, 3, 10, 20], 2), 1);
+    assert_eq!(collect_samples(&mut converter), [2, 15]);
+}
+
+#[test]
+fn quad_to_stereo() {
+    let samples = vec![100, 200, 300, 400, 10, 20, 30, 40];
+    let mut converter = ChannelCountConverter::new(memory_sound(samples.clone(), 4), 2);
+    assert_eq!(converter.channel_count(), 2);
+    assert_eq!(collect_samples(&mut converter), [200, 300, 20, 30]);
+
+    let mut converter = ChannelCountConverter::new(memory_sound(samples, 4), 2);
+    let mut buffer = [0; 6];
+    assert_eq!(
+        converter.fill_buffer(&mut buffer).unwrap(),
+        (2, Some(NextSample::Finished))
+    );
+    assert_eq!(buffer[..4], [200, 300, 20, 30]);
+}
+
+#[test]
+fn five_one_to_stereo_and_mono() {
+    // L, R, C, LFE, SL, SR
+    let samples = vec![1000, 2000, 3000, 4000, 5000, 6000];
+    let mut converter = ChannelCountConverter::new(memory_sound(samples.clone(), 6), 2);
+    assert_eq!(
+        collect_samples(&mut converter),
+        [
+            (1000.0 + 3000.0 * SQRT_HALF + 5000.0 * SQRT_HALF).round() as i16,
+            (2000.0 + 3000.0 * SQRT_HALF + 6000.0 * SQRT_HALF).round() as i16
+        ]
+    );
+    let mut converter = ChannelCountConverter::new(memory_sound(samples, 6), 1);
+    assert_eq!(
+        collect_samples(&mut converter),
+        [(3000.0 * SQRT_HALF + 3000.0 + 5500.0).round() as i16]
+    );
+}
+
+#[test]
+fn mono_up_mixes_to_center() {
+    let mut converter = ChannelCountConverter::new(memory_sound(vec![7], 1), 6);
+    assert_eq!(collect_samples(&mut converter), [0, 0, 7, 0, 0, 0]);
+    let mut converter = ChannelCountConverter::new(memory_sound(vec![7], 1), 8);
+    assert_eq!(collect_samples(&mut converter), [0, 0, 7, 0, 0, 0, 0, 0]);
+}
+
+#[test]
+fn seven_one_to_stereo() {
+    // L, R, C, LFE, BL, BR, SL, SR
+    let samples = vec![1000, 0, 0, 5000, 0, 0, 2000, 0];
+    let mut converter = ChannelCountConverter::new(memory_sound(samples, 8), 2);
+    // The side channel is mixed into surround and then into the front.
+    assert_eq!(collect_samples(&mut converter), [2000, 0]);
+}
+
+#[test]
+fn unsupported_conversion_is_an_error() {
+    let Err(crate::Error::UnsupportedChannelConversion { from: 3, to: 2 }) =
+        ChannelCountConverter::try_new(memory_sound(vec![1, 2, 3], 3), 2)
+    else {
+        panic!("expected unsupported conversion error");
+    };
+    let mut converter = ChannelCountConverter::new(memory_sound(vec![1, 2, 3], 3), 2);
+    assert!(converter.next_sample().is_err());
+    assert!(ChannelMixMatrix::standard(2, 5).is_err());
+}
+
+#[test]
+fn custom_matrix() {
+    // Swap left and right and mix 3 channels that have no standard rule.
+    let matrix = ChannelMixMatrix::new(3, 2, vec![0.0, 1.0, 0.5, 1.0, 0.0, 0.5]);
+    let mut converter =
+        ChannelCountConverter::with_matrix(memory_sound(vec![10, 20, 100, 1, 2, 4], 3), matrix);
+    assert_eq!(converter.channel_count(), 2);
+    assert_eq!(collect_samples(&mut converter), [70, 60, 4, 3]);
+}
+
+#[test]
+fn channel_count_change_uses_the_matching_matrix() {
+    let mut list = crate::sounds::SoundList::new();
+    list.add(Box::new(memory_sound(vec![100, 200, 300, 400], 4)));
+    list.add(Box::new(memory_sound(vec![1000, 2000, 0, 0, 0, 0], 6)));
+    list.add(Box::new(memory_sound(vec![5], 1)));
+    let mut converter = ChannelCountConverter::new(list, 2);
+    let mut samples = Vec::new();
+    loop {
+        match converter.next_sample().unwrap() {
+            NextSample::Sample(s) => samples.push(s),
+            NextSample::MetadataChanged => assert_eq!(converter.channel_count(), 2),
+            NextSample::Paused | NextSample::Finished => break,
+        }
+    }
+    assert_eq!(samples, [200, 300, 1000, 2000, 5, 5]);
+}
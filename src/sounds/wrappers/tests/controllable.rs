@@ -0,0 +1,95 @@
+use super::*;
+use crate::sounds::wrappers::AdjustableVolume;
+use crate::tests::ConstantValueSound;
+
+fn controllable() -> (
+    Controllable<AdjustableVolume<ConstantValueSound>>,
+    Controller<AdjustableVolume<ConstantValueSound>>,
+) {
+    let mut sound = ConstantValueSound::new(100).with_adjustable_volume();
+    sound.set_ramp_duration(Duration::ZERO);
+    Controllable::new(sound)
+}
+
+#[test]
+fn timed_command_applies_within_buffer() {
+    let (mut controllable, mut controller) = controllable();
+    controller.send_command_at(3, |s| s.set_volume(0.5));
+    controller.send_command_at(6, |s| s.set_volume(2.0));
+    let mut buffer = [0; 8];
+    controllable.on_start_of_batch();
+    assert_eq!(controllable.fill_buffer(&mut buffer).unwrap(), (4, None));
+    assert_eq!(buffer, [100, 100, 100, 100, 100, 100, 50, 50]);
+    assert_eq!(controllable.frame(), 4);
+    assert_eq!(controller.frame(), 0);
+
+    assert_eq!(controllable.fill_buffer(&mut buffer).unwrap(), (4, None));
+    assert_eq!(buffer, [50, 50, 50, 50, 200, 200, 200, 200]);
+    controllable.on_start_of_batch();
+    assert_eq!(controller.frame(), 8);
+}
+
+#[test]
+fn timed_command_applies_at_frame_from_next_sample() {
+    let (mut controllable, mut controller) = controllable();
+    controller.send_command_at(1, |s| s.set_volume(0.5));
+    controllable.on_start_of_batch();
+    let samples: Vec<_> = (0..4)
+        .map(|_| controllable.next_sample().unwrap())
+        .collect();
+    assert_eq!(samples, [100, 100, 50, 50].map(NextSample::Sample));
+}
+
+#[test]
+fn past_timed_command_applies_at_start_of_batch() {
+    let (mut controllable, mut controller) = controllable();
+    let mut buffer = [0; 4];
+    controllable.on_start_of_batch();
+    controllable.fill_buffer(&mut buffer).unwrap();
+    controller.send_command_at(1, |s| s.set_volume(0.5));
+    controllable.on_start_of_batch();
+    controllable.fill_buffer(&mut buffer).unwrap();
+    assert_eq!(buffer, [50; 4]);
+}
+
+#[test]
+fn full_queue_rejects_commands() {
+    let sound = ConstantValueSound::new(100).with_adjustable_volume();
+    let (mut controllable, mut controller) = Controllable::with_command_capacity(sound, 2);
+    assert_eq!(controller.try_send_command(|s| s.set_volume(0.5)), Ok(()));
+    assert_eq!(
+        controller.try_send_command_at(1, |s| s.set_volume(0.5)),
+        Ok(())
+    );
+    assert_eq!(
+        controller.try_send_command(|s| s.set_volume(0.5)),
+        Err(CommandQueueFull)
+    );
+    // Overflows without waiting for the Controllable.
+    controller.send_command(|s| s.set_volume(0.5));
+
+    controllable.on_start_of_batch();
+    assert_eq!(controller.try_send_command(|s| s.set_volume(0.5)), Ok(()));
+}
+
+#[test]
+fn overflowed_commands_are_applied_in_order() {
+    let mut sound = ConstantValueSound::new(100).with_adjustable_volume();
+    sound.set_ramp_duration(Duration::ZERO);
+    let (mut controllable, mut controller) = Controllable::with_command_capacity(sound, 2);
+    for volume in [0.1, 0.2, 0.3, 0.4, 0.5] {
+        controller.send_command(move |s| s.set_volume(volume));
+    }
+
+    controllable.on_start_of_batch();
+    assert_eq!(controllable.next_sample().unwrap(), NextSample::Sample(50));
+    controller.send_command(|s| s.set_volume(0.25));
+    controllable.on_start_of_batch();
+    assert_eq!(controllable.next_sample().unwrap(), NextSample::Sample(25));
+}
+
+#[test]
+#[should_panic(expected = "the command capacity must be at least 1")]
+fn zero_command_capacity_panics() {
+    let _ = Controllable::with_command_capacity(ConstantValueSound::new(100), 0);
+}
@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use super::*;
+use crate::{
+    sounds::MemorySound,
+    tests::{ConstantValueSound, DEFAULT_SAMPLE_RATE},
+};
+
+fn next_frame(sound: &mut dyn Sound) -> [i16; 2] {
+    let mut frame = [0; 2];
+    for sample in &mut frame {
+        *sample = match sound.next_sample().unwrap() {
+            NextSample::Sample(s) => s,
+            other => panic!("unexpected {other:?}"),
+        };
+    }
+    frame
+}
+
+#[test]
+fn fade_in_ramps_up_per_frame() {
+    let mut sound = ConstantValueSound::new(1000).fade_in(Duration::from_millis(1));
+    // 44.1 frames in 1ms rounds down to 44
+    assert_eq!(next_frame(&mut sound), [0, 0]);
+    let mut previous = 0;
+    for _ in 1..44 {
+        let [left, right] = next_frame(&mut sound);
+        assert_eq!(left, right);
+        assert!(left > previous);
+        previous = left;
+    }
+    assert_eq!(next_frame(&mut sound), [1000, 1000]);
+    assert_eq!(next_frame(&mut sound), [1000, 1000]);
+}
+
+#[test]
+fn fade_out_and_stop_finishes_after_duration() {
+    let mut sound = ConstantValueSound::new(1000).fadeable();
+    assert_eq!(next_frame(&mut sound), [1000, 1000]);
+    sound.fade_out_and_stop(Duration::from_millis(10));
+    let mut buffer = [0; 1000];
+    assert_eq!(
+        sound.fill_buffer(&mut buffer).unwrap(),
+        (441, Some(NextSample::Finished))
+    );
+    assert_eq!(buffer[..2], [1000, 1000]);
+    assert!(buffer[878] < 10);
+    assert_eq!(sound.next_sample().unwrap(), NextSample::Finished);
+}
+
+#[test]
+fn fade_to_holds_target() {
+    let mut sound = ConstantValueSound::new(1000).fadeable();
+    sound.fade_to(0.5, Duration::from_millis(1));
+    for _ in 0..44 {
+        next_frame(&mut sound);
+    }
+    assert_eq!(sound.gain(), 0.5);
+    let mut buffer = [0; 100];
+    assert_eq!(sound.fill_buffer(&mut buffer).unwrap(), (50, None));
+    assert!(buffer.iter().all(|s| *s == 500));
+    sound.fade_to(1.0, Duration::ZERO);
+    assert_eq!(next_frame(&mut sound), [1000, 1000]);
+}
+
+#[test]
+fn fade_continues_after_metadata_changed() {
+    let mut sound = ConstantValueSound::new(1000).fadeable();
+    sound.fade_to(0.0, Duration::from_millis(2));
+    // Half of the fade at 44100
+    for _ in 0..44 {
+        next_frame(&mut sound);
+    }
+    sound.inner_mut().set_sample_rate(DEFAULT_SAMPLE_RATE * 2);
+    assert_eq!(sound.next_sample().unwrap(), NextSample::MetadataChanged);
+    // The other half takes twice as many frames at the new rate.
+    for _ in 0..88 {
+        let [left, _] = next_frame(&mut sound);
+        assert!(left > 0);
+    }
+    assert_eq!(next_frame(&mut sound), [0, 0]);
+}
+
+#[test]
+fn fade_out_at_end_of_known_duration() {
+    let inner = MemorySound::from_samples(Arc::new(vec![1000; 200]), 1, 1000);
+    let mut sound = inner.fade_out_at_end(Duration::from_millis(100));
+    let mut buffer = [0; 300];
+    assert_eq!(
+        sound.fill_buffer(&mut buffer).unwrap(),
+        (200, Some(NextSample::Finished))
+    );
+    assert!(buffer[..100].iter().all(|s| *s == 1000));
+    assert_eq!(buffer[100], 1000);
+    assert_eq!(buffer[150], 500);
+    assert_eq!(buffer[199], 10);
+}
+
+#[test]
+fn fade_through_controller() {
+    let (mut sound, mut controller) = ConstantValueSound::new(1000).fadeable().controllable();
+    controller.fade_out_and_stop(Duration::ZERO);
+    sound.finish_with_inner();
+    sound.on_start_of_batch();
+    assert_eq!(sound.next_sample().unwrap(), NextSample::Finished);
+}
@@ -1,5 +1,8 @@
 use super::*;
-use crate::{sounds::wrappers::SetPaused, tests::ConstantValueSound};
+use crate::{
+    sounds::{wrappers::SetPaused, MemorySound},
+    tests::ConstantValueSound,
+};
 
 #[test]
 fn test_simple() {
@@ -88,3 +91,41 @@ fn test_metadata_change_end() {
     sound.inner_mut().set_channel_count(1);
     assert_eq!(sound.next_sample().unwrap(), crate::NextSample::Finished);
 }
+
+#[test]
+fn test_seek() {
+    let inner = MemorySound::from_samples(std::sync::Arc::new((0..100).collect()), 1, 1000);
+    let mut sound = inner.finish_after(Duration::from_millis(50));
+    assert_eq!(
+        sound.seek(Duration::from_millis(40)).unwrap(),
+        Duration::from_millis(40)
+    );
+    for i in 40..50 {
+        assert_eq!(sound.next_sample().unwrap(), crate::NextSample::Sample(i));
+    }
+    assert_eq!(sound.next_sample().unwrap(), crate::NextSample::Finished);
+    assert_eq!(
+        sound.seek(Duration::from_millis(60)).unwrap(),
+        Duration::from_millis(50)
+    );
+    assert_eq!(sound.next_sample().unwrap(), crate::NextSample::Finished);
+    sound.seek(Duration::ZERO).unwrap();
+    assert_eq!(sound.next_sample().unwrap(), crate::NextSample::Sample(0));
+}
+
+#[test]
+fn test_position_and_total_duration() {
+    let inner = MemorySound::from_samples(std::sync::Arc::new(vec![0; 100]), 1, 1000);
+    let mut sound = inner.finish_after(Duration::from_millis(50));
+    assert_eq!(sound.total_duration(), Some(Duration::from_millis(50)));
+    assert_eq!(sound.position(), Some(Duration::ZERO));
+    for _ in 0..10 {
+        sound.next_sample().unwrap();
+    }
+    assert_eq!(sound.position(), Some(Duration::from_millis(10)));
+
+    // The inner sound is shorter than the duration
+    let inner = MemorySound::from_samples(std::sync::Arc::new(vec![0; 100]), 1, 1000);
+    let sound = inner.finish_after(Duration::from_secs(1));
+    assert_eq!(sound.total_duration(), Some(Duration::from_millis(100)));
+}
@@ -0,0 +1,46 @@
+use super::*;
+
+struct HalfAmplitude;
+
+impl FloatSound for HalfAmplitude {
+    fn channel_count(&self) -> u16 {
+        2
+    }
+
+    fn sample_rate(&self) -> u32 {
+        48000
+    }
+
+    fn next_sample(&mut self) -> Result<NextSample<f32>, crate::Error> {
+        Ok(NextSample::Sample(0.5))
+    }
+
+    fn on_start_of_batch(&mut self) {}
+}
+
+#[test]
+fn float_samples_pass_through() {
+    let mut sound = HalfAmplitude.into_sound();
+    assert_eq!(sound.next_sample_f32().unwrap(), NextSample::Sample(0.5));
+    let mut buffer = [0.0; 4];
+    assert_eq!(sound.fill_buffer_f32(&mut buffer).unwrap(), (2, None));
+    assert_eq!(buffer, [0.5; 4]);
+}
+
+#[test]
+fn converted_to_i16() {
+    let mut sound = HalfAmplitude.into_sound();
+    assert_eq!(sound.next_sample().unwrap(), NextSample::Sample(16384));
+    let mut buffer = [0; 4];
+    assert_eq!(sound.fill_buffer(&mut buffer).unwrap(), (2, None));
+    assert_eq!(buffer, [16384; 4]);
+}
+
+#[test]
+fn round_trip_through_into_float() {
+    let mut sound = crate::tests::ConstantValueSound::new(-16384)
+        .into_float()
+        .into_sound();
+    assert_eq!(sound.next_sample_f32().unwrap(), NextSample::Sample(-0.5));
+    assert_eq!(sound.next_sample().unwrap(), NextSample::Sample(-16384));
+}
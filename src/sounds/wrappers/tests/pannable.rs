@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use super::*;
+use crate::{sounds::MemorySound, tests::ConstantValueSound};
+
+fn mono(value: i16) -> MemorySound {
+    MemorySound::from_samples(Arc::new(vec![value; 10000]), 1, 1000)
+}
+
+#[test]
+fn mono_is_upmixed_with_constant_power() {
+    let mut sound = mono(10000).pannable();
+    assert_eq!(sound.channel_count(), 2);
+    assert_eq!(sound.next_sample().unwrap(), NextSample::Sample(7071));
+    assert_eq!(sound.next_sample().unwrap(), NextSample::Sample(7071));
+
+    let mut sound = Pannable::new_with_pan(mono(10000), -1.0);
+    let mut buffer = [0; 4];
+    assert_eq!(sound.fill_buffer(&mut buffer).unwrap(), (2, None));
+    assert_eq!(buffer, [10000, 0, 10000, 0]);
+}
+
+#[test]
+fn linear_law() {
+    let mut sound = Pannable::new_with_pan(mono(10000), 0.5);
+    sound.set_pan_law(PanLaw::Linear);
+    let mut buffer = [0.0; 4];
+    assert_eq!(sound.fill_buffer_f32(&mut buffer).unwrap(), (2, None));
+    let sample = 10000.0 / 32768.0;
+    assert_eq!(
+        buffer,
+        [sample * 0.25, sample * 0.75, sample * 0.25, sample * 0.75]
+    );
+}
+
+#[test]
+fn stereo_balance() {
+    let mut sound = ConstantValueSound::new(1000).pannable();
+    assert_eq!(sound.next_sample().unwrap(), NextSample::Sample(1000));
+    assert_eq!(sound.next_sample().unwrap(), NextSample::Sample(1000));
+
+    let mut sound = Pannable::new_with_pan(ConstantValueSound::new(1000), 0.5);
+    sound.set_pan_law(PanLaw::Linear);
+    assert_eq!(sound.next_sample().unwrap(), NextSample::Sample(500));
+    assert_eq!(sound.next_sample().unwrap(), NextSample::Sample(1000));
+}
+
+#[test]
+fn pan_changes_are_ramped() {
+    let mut sound = mono(10000).pannable();
+    sound.set_pan(1.0);
+    // 10 frames at 1000Hz
+    let mut buffer = [0; 24];
+    assert_eq!(sound.fill_buffer(&mut buffer).unwrap(), (12, None));
+    let frames: Vec<_> = buffer.chunks_exact(2).collect();
+    assert_eq!(frames[0], [7071, 7071]);
+    for pair in frames[..10].windows(2) {
+        assert!(pair[1][0] < pair[0][0]);
+        assert!(pair[1][1] > pair[0][1]);
+    }
+    assert_eq!(frames[10], [0, 10000]);
+    assert_eq!(frames[11], [0, 10000]);
+
+    // next_sample follows the same ramp
+    let mut first = mono(10000).pannable();
+    first.set_pan(1.0);
+    for sample in buffer {
+        assert_eq!(first.next_sample().unwrap(), NextSample::Sample(sample));
+    }
+}
+
+#[test]
+fn pan_through_controller_and_wrappers() {
+    let (mut sound, mut controller) = mono(10000)
+        .pannable()
+        .with_adjustable_volume()
+        .pausable()
+        .controllable();
+    controller.set_pan(-1.0);
+    sound.on_start_of_batch();
+    let mut buffer = [0; 40];
+    sound.fill_buffer(&mut buffer).unwrap();
+    assert_eq!(buffer[38..], [10000, 0]);
+}
+
+#[test]
+fn ramp_pan() {
+    let mut sound = Pannable::new_with_pan(mono(10000), -1.0);
+    sound.set_pan_law(PanLaw::Linear);
+    sound.ramp_pan(1.0, Duration::from_millis(4), Ramp::Linear);
+    assert_eq!(sound.pan(), 1.0);
+    let mut buffer = [0; 12];
+    assert_eq!(sound.fill_buffer(&mut buffer).unwrap(), (6, None));
+    assert_eq!(
+        buffer,
+        [10000, 0, 7500, 2500, 5000, 5000, 2500, 7500, 0, 10000, 0, 10000]
+    );
+}
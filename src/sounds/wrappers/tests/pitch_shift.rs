@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use super::*;
+use crate::{sounds::MemorySound, tests::Sawtooth, NextSample};
+
+fn sine(frequency: f64, num_frames: usize, sample_rate: u32) -> MemorySound {
+    let samples = (0..num_frames)
+        .map(|i| {
+            let phase = 2.0 * std::f64::consts::PI * frequency * i as f64 / sample_rate as f64;
+            (10000.0 * phase.sin()).round() as i16
+        })
+        .collect();
+    MemorySound::from_samples(Arc::new(samples), 1, sample_rate)
+}
+
+fn collect(sound: &mut dyn Sound) -> Vec<i16> {
+    let mut samples = Vec::new();
+    loop {
+        match sound.next_sample().unwrap() {
+            NextSample::Sample(s) => samples.push(s),
+            NextSample::Finished | NextSample::Paused => return samples,
+            NextSample::MetadataChanged => panic!("unexpected MetadataChanged"),
+        }
+    }
+}
+
+/// The number of times the samples go from negative to non-negative.
+fn rising_zero_crossings(samples: &[i16]) -> usize {
+    samples
+        .windows(2)
+        .filter(|pair| pair[0] < 0 && pair[1] >= 0)
+        .count()
+}
+
+#[test]
+fn no_shift_passes_through() {
+    let mut sound = Sawtooth::new(2, 1000).with_adjustable_pitch();
+    let mut inner = Sawtooth::new(2, 1000);
+    for _ in 0..100 {
+        assert_eq!(sound.next_sample().unwrap(), inner.next_sample().unwrap());
+    }
+}
+
+#[test]
+fn octave_up_keeps_duration() {
+    let mut sound = sine(441.0, 44100, 44100).with_adjustable_pitch_of(12.0);
+    assert_eq!(sound.total_duration(), Some(Duration::from_secs(1)));
+    let samples = collect(&mut sound);
+    assert!(samples.len().abs_diff(44100) < 1000, "{}", samples.len());
+    let crossings = rising_zero_crossings(&samples[4410..26460]);
+    assert!(crossings.abs_diff(441) <= 3, "{crossings}");
+}
+
+#[test]
+fn fifth_down_at_other_sample_rate() {
+    let mut sound = sine(480.0, 48000, 48000).with_adjustable_pitch_of(-7.0);
+    assert_eq!(sound.sample_rate(), 48000);
+    let samples = collect(&mut sound);
+    assert!(samples.len().abs_diff(48000) < 1000, "{}", samples.len());
+    // Half a second at 480 * 2^(-7/12) = 320.4Hz
+    let crossings = rising_zero_crossings(&samples[4800..28800]);
+    assert!(crossings.abs_diff(160) <= 3, "{crossings}");
+}
+
+#[test]
+fn fill_buffer_matches_next_sample() {
+    let mut first = sine(441.0, 4410, 44100).with_adjustable_pitch_of(3.0);
+    let mut second = sine(441.0, 4410, 44100).with_adjustable_pitch_of(3.0);
+    let mut buffer = [0; 1000];
+    assert_eq!(second.fill_buffer(&mut buffer).unwrap(), (1000, None));
+    for sample in buffer {
+        assert_eq!(first.next_sample().unwrap(), NextSample::Sample(sample));
+    }
+}
+
+#[test]
+fn pitch_set_through_controller() {
+    let (mut sound, mut controller) = sine(441.0, 44100, 44100)
+        .with_adjustable_pitch()
+        .controllable();
+    controller.set_pitch(12.0);
+    sound.on_start_of_batch();
+    let samples = collect(&mut sound);
+    assert!(samples.len().abs_diff(44100) < 1000, "{}", samples.len());
+    let crossings = rising_zero_crossings(&samples[4410..26460]);
+    assert!(crossings.abs_diff(441) <= 3, "{crossings}");
+}
+
+#[test]
+fn seek_past_the_representable_duration_is_an_error() {
+    let mut sound = sine(441.0, 4410, 44100).with_adjustable_pitch_of(12.0);
+    assert_eq!(
+        sound.seek(Duration::from_millis(50)).unwrap(),
+        Duration::from_millis(50)
+    );
+    assert!(sound.seek(Duration::MAX).is_err());
+}
@@ -0,0 +1,204 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::*;
+use crate::sounds::MemorySound;
+use crate::tests::ConstantValueSound;
+
+/// Returns the items of `steps` in order, then Finished.
+struct Scripted {
+    steps: VecDeque<Result<NextSample, crate::Error>>,
+    channel_count: u16,
+    sample_rate: u32,
+    /// Blocks until a message is received before the first step.
+    start: Option<mpsc::Receiver<()>>,
+}
+
+impl Scripted {
+    fn new(steps: Vec<Result<NextSample, crate::Error>>) -> Self {
+        Scripted {
+            steps: steps.into(),
+            channel_count: 1,
+            sample_rate: 1000,
+            start: None,
+        }
+    }
+}
+
+impl Sound for Scripted {
+    fn channel_count(&self) -> u16 {
+        self.channel_count
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn next_sample(&mut self) -> Result<NextSample, crate::Error> {
+        if let Some(start) = self.start.take() {
+            let _ = start.recv();
+        }
+        let next = self.steps.pop_front().unwrap_or(Ok(NextSample::Finished));
+        if let Ok(NextSample::MetadataChanged) = next {
+            self.channel_count = 2;
+            self.sample_rate = 2000;
+        }
+        next
+    }
+
+    fn on_start_of_batch(&mut self) {}
+}
+
+/// Call next_sample until the worker has caught up.
+fn next_ready(sound: &mut Prefetched) -> Result<NextSample, crate::Error> {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        let next = sound.next_sample()?;
+        if next != NextSample::Paused {
+            return Ok(next);
+        }
+        assert!(
+            Instant::now() < deadline,
+            "the worker did not produce samples"
+        );
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn plays_all_samples_then_finishes() {
+    let samples: Vec<i16> = (0..10_000).map(|i| i as i16).collect();
+    let inner = MemorySound::from_samples(Arc::new(samples.clone()), 2, 1000);
+    let mut sound = inner.prefetched(Duration::from_millis(100)).unwrap();
+    assert_eq!(sound.channel_count(), 2);
+    assert_eq!(sound.total_duration(), Some(Duration::from_secs(5)));
+
+    let mut played = Vec::new();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut buffer = [0; 300];
+    loop {
+        let (num_frames, next) = sound.fill_buffer(&mut buffer).unwrap();
+        played.extend_from_slice(&buffer[..num_frames * 2]);
+        match next {
+            None => (),
+            Some(NextSample::Paused) => thread::sleep(Duration::from_millis(1)),
+            Some(NextSample::Finished) => break,
+            Some(other) => panic!("unexpected {other:?}"),
+        }
+        assert!(Instant::now() < deadline);
+    }
+    assert_eq!(played, samples);
+    assert_eq!(sound.position(), Some(Duration::from_secs(5)));
+    assert_eq!(sound.next_sample().unwrap(), NextSample::Finished);
+}
+
+#[test]
+fn float_samples_are_not_quantized() {
+    let inner = crate::sounds::SineWave::new(440.0).into_float();
+    let mut expected = crate::sounds::SineWave::new(440.0);
+    let mut sound = crate::sounds::wrappers::FromFloat::new(inner)
+        .prefetched(Duration::from_millis(10))
+        .unwrap();
+    for _ in 0..2000 {
+        let NextSample::Sample(expected) = expected.next_sample_f32().unwrap() else {
+            panic!("sine wave only returns samples");
+        };
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            match sound.next_sample_f32().unwrap() {
+                NextSample::Sample(s) => {
+                    assert_eq!(s, expected);
+                    break;
+                }
+                NextSample::Paused => thread::sleep(Duration::from_millis(1)),
+                other => panic!("unexpected {other:?}"),
+            }
+            assert!(Instant::now() < deadline);
+        }
+    }
+}
+
+#[test]
+fn metadata_changes_and_errors_are_in_order() {
+    let inner = Scripted::new(vec![
+        Ok(NextSample::Sample(1)),
+        Ok(NextSample::Sample(2)),
+        Ok(NextSample::MetadataChanged),
+        Ok(NextSample::Sample(3)),
+        Ok(NextSample::Sample(4)),
+        Ok(NextSample::Paused),
+        Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into()),
+    ]);
+    let mut sound = inner.prefetched(Duration::from_millis(10)).unwrap();
+    assert_eq!(next_ready(&mut sound).unwrap(), NextSample::Sample(1));
+    assert_eq!(next_ready(&mut sound).unwrap(), NextSample::Sample(2));
+    assert_eq!((sound.channel_count(), sound.sample_rate()), (1, 1000));
+    assert_eq!(next_ready(&mut sound).unwrap(), NextSample::MetadataChanged);
+    assert_eq!((sound.channel_count(), sound.sample_rate()), (2, 2000));
+    assert_eq!(next_ready(&mut sound).unwrap(), NextSample::Sample(3));
+    assert_eq!(next_ready(&mut sound).unwrap(), NextSample::Sample(4));
+    let Err(crate::Error::IoError(e)) = next_ready(&mut sound) else {
+        panic!("expected the error of the inner sound");
+    };
+    assert_eq!(e.kind(), std::io::ErrorKind::UnexpectedEof);
+    assert_eq!(sound.next_sample().unwrap(), NextSample::Finished);
+}
+
+#[test]
+fn underflow_is_paused() {
+    let (start, receiver) = mpsc::channel();
+    let mut inner = Scripted::new(vec![Ok(NextSample::Sample(7))]);
+    inner.start = Some(receiver);
+    let mut sound = inner.prefetched(Duration::from_millis(10)).unwrap();
+    assert_eq!(sound.next_sample().unwrap(), NextSample::Paused);
+    let mut buffer = [0; 4];
+    assert_eq!(
+        sound.fill_buffer(&mut buffer).unwrap(),
+        (0, Some(NextSample::Paused))
+    );
+    start.send(()).unwrap();
+    assert_eq!(next_ready(&mut sound).unwrap(), NextSample::Sample(7));
+    assert_eq!(next_ready(&mut sound).unwrap(), NextSample::Finished);
+}
+
+#[test]
+fn dropping_stops_the_worker() {
+    /// Plays forever and keeps `alive` until dropped.
+    struct Endless {
+        inner: ConstantValueSound,
+        _alive: Arc<()>,
+    }
+
+    impl Sound for Endless {
+        fn channel_count(&self) -> u16 {
+            self.inner.channel_count()
+        }
+
+        fn sample_rate(&self) -> u32 {
+            self.inner.sample_rate()
+        }
+
+        fn next_sample(&mut self) -> Result<NextSample, crate::Error> {
+            self.inner.next_sample()
+        }
+
+        fn on_start_of_batch(&mut self) {}
+    }
+
+    let alive = Arc::new(());
+    let sound = Endless {
+        inner: ConstantValueSound::new(5),
+        _alive: alive.clone(),
+    }
+    .prefetched(Duration::from_millis(10))
+    .unwrap();
+    // Let the worker fill the buffer and wait.
+    thread::sleep(Duration::from_millis(20));
+    drop(sound);
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Arc::strong_count(&alive) > 1 {
+        assert!(Instant::now() < deadline, "the worker was not stopped");
+        thread::sleep(Duration::from_millis(1));
+    }
+}
@@ -121,3 +121,105 @@ fn catch_metadata_changed_when_passing_through() {
     assert_eq!(converted.next_sample().unwrap(), NextSample::Sample(6));
     assert_eq!(converted.channel_count(), 1);
 }
+
+#[test]
+fn position_does_not_include_buffered_frame() {
+    let inner =
+        crate::sounds::MemorySound::from_samples(std::sync::Arc::new(vec![0; 100]), 1, 1000);
+    let mut sound = SampleRateConverter::new(inner, 4000);
+    assert_eq!(sound.total_duration(), Some(Duration::from_millis(100)));
+    assert_eq!(sound.position(), Some(Duration::ZERO));
+    for _ in 0..40 {
+        sound.next_sample().unwrap();
+    }
+    assert_eq!(sound.position(), Some(Duration::from_millis(10)));
+}
+
+#[test]
+fn f32_interpolates_between_samples() {
+    let sound = Sawtooth::new(1, 1000);
+    let mut converted = SampleRateConverter::new(sound, 4000);
+    let expected = [0.0, 0.25, 0.5, 0.75, 1.0, 1.25];
+    for expected in expected {
+        assert_eq!(
+            converted.next_sample_f32().unwrap(),
+            NextSample::Sample(expected / 32768.0)
+        );
+    }
+    let mut buffer = [0.0; 2];
+    assert_eq!(converted.fill_buffer_f32(&mut buffer).unwrap(), (2, None));
+    assert_eq!(buffer, [1.5 / 32768.0, 1.75 / 32768.0]);
+}
+
+#[test]
+fn sinc_keeps_constant_value() {
+    let sound = crate::tests::ConstantValueSound::new(1000);
+    let mut converted = SampleRateConverter::with_quality(sound, 48000, ResamplerQuality::Sinc);
+    assert_eq!(converted.quality(), ResamplerQuality::Sinc);
+    // Skip the ramp up from the silence before the first frame.
+    for _ in 0..2000 {
+        converted.next_sample().unwrap();
+    }
+    for _ in 0..1000 {
+        let NextSample::Sample(sample) = converted.next_sample().unwrap() else {
+            panic!("expected a sample");
+        };
+        assert!((sample - 1000).abs() <= 1, "{sample}");
+    }
+}
+
+#[test]
+fn sinc_attenuates_above_output_nyquist() {
+    // A tone at 0.45 of the input rate is above the Nyquist frequency of the
+    // output so it must be filtered out instead of aliasing.
+    let samples: Vec<i16> = (0..20000)
+        .map(|i| (10000.0 * (2.0 * std::f64::consts::PI * 0.45 * i as f64).sin()).round() as i16)
+        .collect();
+    let peak = |quality| {
+        let inner = crate::sounds::MemorySound::from_samples(
+            std::sync::Arc::new(samples.clone()),
+            1,
+            48000,
+        );
+        let mut converted = SampleRateConverter::with_quality(inner, 24000, quality);
+        let mut peak = 0;
+        for i in 0.. {
+            match converted.next_sample().unwrap() {
+                NextSample::Sample(s) if i > 1000 && i < 9000 => peak = peak.max(s.abs()),
+                NextSample::Sample(_) => {}
+                NextSample::Finished => break,
+                other => panic!("unexpected {other:?}"),
+            }
+        }
+        peak
+    };
+    assert!(peak(ResamplerQuality::Linear) > 1000);
+    assert!(
+        peak(ResamplerQuality::Sinc) < 100,
+        "{}",
+        peak(ResamplerQuality::Sinc)
+    );
+}
+
+#[test]
+fn sinc_pause_resumes_without_losing_frames() {
+    let sound = Sawtooth::new(1, 1000).pausable();
+    let mut converted = SampleRateConverter::with_quality(sound, 2000, ResamplerQuality::Sinc);
+    let mut uninterrupted =
+        SampleRateConverter::with_quality(Sawtooth::new(1, 1000), 2000, ResamplerQuality::Sinc);
+    for _ in 0..50 {
+        assert_eq!(
+            converted.next_sample().unwrap(),
+            uninterrupted.next_sample().unwrap()
+        );
+    }
+    converted.inner_mut().set_paused(true);
+    assert_eq!(converted.next_sample().unwrap(), NextSample::Paused);
+    converted.inner_mut().set_paused(false);
+    for _ in 0..50 {
+        assert_eq!(
+            converted.next_sample().unwrap(),
+            uninterrupted.next_sample().unwrap()
+        );
+    }
+}
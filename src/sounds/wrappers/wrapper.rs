@@ -1,12 +1,19 @@
 use crate::Sound;
 
-use super::{AddSound, ClearSounds, SetPaused, SetSpeed, SetStopped, SetVolume};
+use super::{
+    AddSound, ClearSounds, SetFade, SetPan, SetPaused, SetPitch, SetSpeed, SetStopped, SetTempo,
+    SetVolume, Tween,
+};
 
 /// Super trait that implements all traits that a wrapper Sound should
 /// transparently pass through if implemented by the inner sound. If you have
 /// a wrapper that should handle any of these traits specially besides just
 /// passing through, then you should not implement this trait but implement all
 /// of the traits individually.
+///
+/// [Seek][super::Seek] is not passed through since wrappers that keep state
+/// about the samples they have output usually need to reset it when seeking.
+/// Implement it separately.
 pub trait Wrapper {
     /// The wrapped Sound type
     type Inner: Sound;
@@ -49,6 +56,58 @@ where
     fn set_speed(&mut self, new: f32) {
         self.inner_mut().set_speed(new)
     }
+
+    fn start_speed_tween(&mut self, tween: Tween) {
+        self.inner_mut().start_speed_tween(tween)
+    }
+}
+
+impl<S> SetTempo for S
+where
+    S: Wrapper,
+    <S as Wrapper>::Inner: SetTempo,
+{
+    fn set_tempo(&mut self, new: f32) {
+        self.inner_mut().set_tempo(new)
+    }
+}
+
+impl<S> SetPitch for S
+where
+    S: Wrapper,
+    <S as Wrapper>::Inner: SetPitch,
+{
+    fn set_pitch(&mut self, semitones: f32) {
+        self.inner_mut().set_pitch(semitones)
+    }
+}
+
+impl<S> SetFade for S
+where
+    S: Wrapper,
+    <S as Wrapper>::Inner: SetFade,
+{
+    fn fade_to(&mut self, volume: f32, duration: std::time::Duration) {
+        self.inner_mut().fade_to(volume, duration)
+    }
+
+    fn fade_out_and_stop(&mut self, duration: std::time::Duration) {
+        self.inner_mut().fade_out_and_stop(duration)
+    }
+}
+
+impl<S> SetPan for S
+where
+    S: Wrapper,
+    <S as Wrapper>::Inner: SetPan,
+{
+    fn set_pan(&mut self, pan: f32) {
+        self.inner_mut().set_pan(pan)
+    }
+
+    fn start_pan_tween(&mut self, tween: Tween) {
+        self.inner_mut().start_pan_tween(tween)
+    }
 }
 
 impl<S> SetVolume for S
@@ -59,6 +118,10 @@ where
     fn set_volume(&mut self, new: f32) {
         self.inner_mut().set_volume(new)
     }
+
+    fn start_volume_tween(&mut self, tween: Tween) {
+        self.inner_mut().start_volume_tween(tween)
+    }
 }
 
 impl<S> AddSound for S
@@ -80,3 +143,146 @@ where
         self.inner_mut().clear()
     }
 }
+
+/// Implement each listed trait for `$wrapper<S>` by passing it through to the
+/// `inner` field when the inner sound implements it.
+///
+/// This is for wrappers that handle one of the [Wrapper] traits specially and
+/// so cannot implement [Wrapper] since the blanket impls would conflict, and
+/// for passing [Seek][super::Seek] through which [Wrapper] does not.
+macro_rules! forward_to_inner {
+    ($wrapper:ident: $($name:ident),+ $(,)?) => {
+        $(forward_to_inner!(@impl $wrapper, $name);)+
+    };
+    (@impl $wrapper:ident, SetPaused) => {
+        impl<S> $crate::sounds::wrappers::SetPaused for $wrapper<S>
+        where
+            S: $crate::Sound + $crate::sounds::wrappers::SetPaused,
+        {
+            fn set_paused(&mut self, paused: bool) {
+                $crate::sounds::wrappers::SetPaused::set_paused(&mut self.inner, paused)
+            }
+        }
+    };
+    (@impl $wrapper:ident, SetStopped) => {
+        impl<S> $crate::sounds::wrappers::SetStopped for $wrapper<S>
+        where
+            S: $crate::Sound + $crate::sounds::wrappers::SetStopped,
+        {
+            fn set_stopped(&mut self) {
+                $crate::sounds::wrappers::SetStopped::set_stopped(&mut self.inner)
+            }
+        }
+    };
+    (@impl $wrapper:ident, SetSpeed) => {
+        impl<S> $crate::sounds::wrappers::SetSpeed for $wrapper<S>
+        where
+            S: $crate::Sound + $crate::sounds::wrappers::SetSpeed,
+        {
+            fn set_speed(&mut self, multiplier: f32) {
+                $crate::sounds::wrappers::SetSpeed::set_speed(&mut self.inner, multiplier)
+            }
+
+            fn start_speed_tween(&mut self, tween: $crate::sounds::wrappers::Tween) {
+                $crate::sounds::wrappers::SetSpeed::start_speed_tween(&mut self.inner, tween)
+            }
+        }
+    };
+    (@impl $wrapper:ident, SetTempo) => {
+        impl<S> $crate::sounds::wrappers::SetTempo for $wrapper<S>
+        where
+            S: $crate::Sound + $crate::sounds::wrappers::SetTempo,
+        {
+            fn set_tempo(&mut self, multiplier: f32) {
+                $crate::sounds::wrappers::SetTempo::set_tempo(&mut self.inner, multiplier)
+            }
+        }
+    };
+    (@impl $wrapper:ident, SetPitch) => {
+        impl<S> $crate::sounds::wrappers::SetPitch for $wrapper<S>
+        where
+            S: $crate::Sound + $crate::sounds::wrappers::SetPitch,
+        {
+            fn set_pitch(&mut self, semitones: f32) {
+                $crate::sounds::wrappers::SetPitch::set_pitch(&mut self.inner, semitones)
+            }
+        }
+    };
+    (@impl $wrapper:ident, SetVolume) => {
+        impl<S> $crate::sounds::wrappers::SetVolume for $wrapper<S>
+        where
+            S: $crate::Sound + $crate::sounds::wrappers::SetVolume,
+        {
+            fn set_volume(&mut self, multiplier: f32) {
+                $crate::sounds::wrappers::SetVolume::set_volume(&mut self.inner, multiplier)
+            }
+
+            fn start_volume_tween(&mut self, tween: $crate::sounds::wrappers::Tween) {
+                $crate::sounds::wrappers::SetVolume::start_volume_tween(&mut self.inner, tween)
+            }
+        }
+    };
+    (@impl $wrapper:ident, SetPan) => {
+        impl<S> $crate::sounds::wrappers::SetPan for $wrapper<S>
+        where
+            S: $crate::Sound + $crate::sounds::wrappers::SetPan,
+        {
+            fn set_pan(&mut self, pan: f32) {
+                $crate::sounds::wrappers::SetPan::set_pan(&mut self.inner, pan)
+            }
+
+            fn start_pan_tween(&mut self, tween: $crate::sounds::wrappers::Tween) {
+                $crate::sounds::wrappers::SetPan::start_pan_tween(&mut self.inner, tween)
+            }
+        }
+    };
+    (@impl $wrapper:ident, SetFade) => {
+        impl<S> $crate::sounds::wrappers::SetFade for $wrapper<S>
+        where
+            S: $crate::Sound + $crate::sounds::wrappers::SetFade,
+        {
+            fn fade_to(&mut self, volume: f32, duration: std::time::Duration) {
+                $crate::sounds::wrappers::SetFade::fade_to(&mut self.inner, volume, duration)
+            }
+
+            fn fade_out_and_stop(&mut self, duration: std::time::Duration) {
+                $crate::sounds::wrappers::SetFade::fade_out_and_stop(&mut self.inner, duration)
+            }
+        }
+    };
+    (@impl $wrapper:ident, Seek) => {
+        impl<S> $crate::sounds::wrappers::Seek for $wrapper<S>
+        where
+            S: $crate::Sound + $crate::sounds::wrappers::Seek,
+        {
+            fn seek(
+                &mut self,
+                position: std::time::Duration,
+            ) -> Result<std::time::Duration, $crate::Error> {
+                $crate::sounds::wrappers::Seek::seek(&mut self.inner, position)
+            }
+        }
+    };
+    (@impl $wrapper:ident, AddSound) => {
+        impl<S> $crate::sounds::wrappers::AddSound for $wrapper<S>
+        where
+            S: $crate::Sound + $crate::sounds::wrappers::AddSound,
+        {
+            fn add(&mut self, sound: Box<dyn $crate::Sound>) {
+                $crate::sounds::wrappers::AddSound::add(&mut self.inner, sound)
+            }
+        }
+    };
+    (@impl $wrapper:ident, ClearSounds) => {
+        impl<S> $crate::sounds::wrappers::ClearSounds for $wrapper<S>
+        where
+            S: $crate::Sound + $crate::sounds::wrappers::ClearSounds,
+        {
+            fn clear(&mut self) {
+                $crate::sounds::wrappers::ClearSounds::clear(&mut self.inner)
+            }
+        }
+    };
+}
+
+pub(crate) use forward_to_inner;
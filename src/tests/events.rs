@@ -0,0 +1,35 @@
+use super::*;
+
+#[test]
+fn ids_are_unique() {
+    let first = SoundId::new();
+    let second = SoundId::new();
+    assert_ne!(first, second);
+}
+
+#[test]
+fn all_subscribers_receive_events() {
+    let sender = EventSender::new();
+    // Sent before subscribing so never received.
+    sender.send_finished(SoundId::new());
+    let first = sender.subscribe();
+    let second = sender.subscribe();
+    let id = SoundId::new();
+    sender.send_error(id, std::io::Error::other("test error").into());
+    for receiver in [first, second] {
+        let event = receiver.try_recv().unwrap();
+        assert_eq!(event.id(), id);
+        assert!(matches!(event, SoundEvent::Error { .. }));
+        assert!(receiver.try_recv().is_err());
+    }
+}
+
+#[test]
+fn dropped_subscribers_are_removed() {
+    let sender = EventSender::new();
+    let receiver = sender.subscribe();
+    drop(sender.subscribe());
+    sender.send_finished(SoundId::new());
+    assert_eq!(sender.subscribers.lock().unwrap().len(), 1);
+    assert!(receiver.try_recv().is_ok());
+}
@@ -100,3 +100,22 @@ impl Sound for Sawtooth {
 
     fn on_start_of_batch(&mut self) {}
 }
+
+/// Returns an Error on the first call to next_sample.
+pub struct ErrorSound;
+
+impl Sound for ErrorSound {
+    fn channel_count(&self) -> u16 {
+        DEFAULT_CHANNEL_COUNT
+    }
+
+    fn sample_rate(&self) -> u32 {
+        DEFAULT_SAMPLE_RATE
+    }
+
+    fn next_sample(&mut self) -> Result<NextSample, crate::Error> {
+        Err(std::io::Error::other("test error").into())
+    }
+
+    fn on_start_of_batch(&mut self) {}
+}
@@ -74,4 +74,22 @@ mod tests {
         let sound = Sawtooth::new(1, 48000);
         assert_eq!(sound.sample_rate(), 48000);
     }
-}
\ No newline at end of file
+}
+
+use crate::tests::ConstantValueSound;
+use crate::{NextSample, Sound};
+
+#[test]
+fn test_default_fill_buffer() {
+    let mut sound = ConstantValueSound::new(42);
+    let mut buffer = [0; 6];
+    assert_eq!(sound.fill_buffer(&mut buffer).unwrap(), (3, None));
+    assert_eq!(buffer, [42; 6]);
+
+    sound.set_channel_count(3);
+    assert_eq!(
+        sound.fill_buffer(&mut buffer).unwrap(),
+        (0, Some(NextSample::MetadataChanged))
+    );
+    assert_eq!(sound.fill_buffer(&mut buffer).unwrap(), (2, None));
+}
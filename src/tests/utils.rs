@@ -30,3 +30,49 @@ fn test_duration_to_num_samples() {
         264_600_000_000
     );
 }
+
+#[test]
+fn test_num_samples_to_duration() {
+    assert_eq!(
+        num_samples_to_duration(44100, 1, 44100),
+        Duration::from_secs(1)
+    );
+    assert_eq!(
+        num_samples_to_duration(88200, 2, 44100),
+        Duration::from_secs(1)
+    );
+    assert_eq!(
+        num_samples_to_duration(441, 1, 44100),
+        Duration::from_millis(10)
+    );
+    assert_eq!(
+        num_samples_to_duration(264_600_000_000, 6, 44100),
+        Duration::from_secs(1_000_000)
+    );
+    // Round trips even though duration_to_num_samples truncates
+    for num_samples in 0..1000 {
+        let duration = num_samples_to_duration(num_samples, 1, 44100);
+        assert_eq!(duration_to_num_samples(duration, 1, 44100), num_samples);
+    }
+}
+
+#[test]
+fn test_sample_conversion() {
+    assert_eq!(i16_to_f32(0), 0.0);
+    assert_eq!(i16_to_f32(i16::MIN), -1.0);
+    assert_eq!(f32_to_i16(1.0), i16::MAX);
+    assert_eq!(f32_to_i16(-2.0), i16::MIN);
+    for sample in [i16::MIN, -1000, -1, 0, 1, 1000, i16::MAX] {
+        assert_eq!(f32_to_i16(i16_to_f32(sample)), sample);
+    }
+}
+
+#[test]
+fn test_db_conversion() {
+    assert_eq!(db_to_gain(0.0), 1.0);
+    assert_eq!(db_to_gain(-20.0), 0.1);
+    assert_eq!(db_to_gain(f32::NEG_INFINITY), 0.0);
+    assert_eq!(gain_to_db(1.0), 0.0);
+    assert_eq!(gain_to_db(10.0), 20.0);
+    assert_eq!(gain_to_db(0.0), f32::NEG_INFINITY);
+}
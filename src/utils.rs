@@ -37,6 +37,46 @@ pub fn duration_to_num_samples(duration: Duration, channel_count: u16, sample_ra
     )
 }
 
+/// Return the amount of time it takes to play `num_samples` samples.
+///
+/// This is the inverse of [duration_to_num_samples]. The result is rounded up
+/// to the microsecond so that converting it back gives the same number of
+/// samples.
+pub fn num_samples_to_duration(num_samples: u64, channel_count: u16, sample_rate: u32) -> Duration {
+    let num_frames = num_samples / channel_count as u64;
+    let remainder = num_frames % sample_rate as u64;
+    Duration::from_secs(num_frames / sample_rate as u64)
+        + Duration::from_micros((remainder * 1_000_000).div_ceil(sample_rate as u64))
+}
+
+/// Convert an i16 sample to an f32 sample in the range -1.0 to 1.0.
+///
+/// Lossless. Converting back with [f32_to_i16] returns the original value.
+pub fn i16_to_f32(sample: i16) -> f32 {
+    sample as f32 / 32768.0
+}
+
+/// Convert an f32 sample in the range -1.0 to 1.0 to an i16 sample.
+///
+/// Values outside of the range are clamped.
+pub fn f32_to_i16(sample: f32) -> i16 {
+    // Since Rust 1.45, the `as` keyword performs a *saturating cast* when
+    // casting from float to int.
+    (sample * 32768.0).round() as i16
+}
+
+/// Convert a gain in decibels to a linear multiplier. `f32::NEG_INFINITY`
+/// converts to 0.0.
+pub fn db_to_gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// Convert a linear multiplier to a gain in decibels. 0.0 converts to
+/// `f32::NEG_INFINITY`.
+pub fn gain_to_db(gain: f32) -> f32 {
+    20.0 * gain.abs().log10()
+}
+
 #[cfg(test)]
 #[path = "./tests/utils.rs"]
 mod tests;
@@ -0,0 +1,448 @@
+//! Checks that rendering does not allocate or free memory once warmed up.
+//!
+//! This is an integration test since a global allocator needs unsafe code
+//! which the library forbids.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::Arc;
+use std::time::Duration;
+
+use awedio::events::SoundEvent;
+use awedio::manager::{BackendSource, Manager, Renderer};
+use awedio::sounds::wrappers::{Easing, Prefetched, ResamplerQuality, SetVolume};
+use awedio::sounds::{Ducking, MasterEffect, MemorySound, SineWave, SoundList};
+use awedio::Sound;
+
+const CHANNEL_COUNT: u16 = 2;
+const SAMPLE_RATE: u32 = 48000;
+const BATCH_FRAMES: usize = 512;
+
+struct CountingAllocator;
+
+thread_local! {
+    static COUNTING: Cell<bool> = const { Cell::new(false) };
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    static DEALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn record(counter: &'static std::thread::LocalKey<Cell<usize>>) {
+    // try_with since the allocator is also called while thread locals are
+    // destroyed.
+    if COUNTING.try_with(Cell::get).unwrap_or(false) {
+        let _ = counter.try_with(|count| count.set(count.get() + 1));
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(&ALLOCATIONS);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        record(&DEALLOCATIONS);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record(&ALLOCATIONS);
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record(&ALLOCATIONS);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Run `f` and return the number of allocations and deallocations it made on
+/// this thread.
+fn count_allocations(f: impl FnOnce()) -> (usize, usize) {
+    ALLOCATIONS.with(|count| count.set(0));
+    DEALLOCATIONS.with(|count| count.set(0));
+    COUNTING.with(|counting| counting.set(true));
+    f();
+    COUNTING.with(|counting| counting.set(false));
+    (ALLOCATIONS.with(Cell::get), DEALLOCATIONS.with(Cell::get))
+}
+
+fn start() -> (Manager, Renderer) {
+    let (manager, mut renderer) = Manager::new();
+    renderer.set_output_channel_count_and_sample_rate(CHANNEL_COUNT, SAMPLE_RATE);
+    (manager, renderer)
+}
+
+fn render_batch(renderer: &mut Renderer, buffer: &mut [f32]) {
+    renderer.on_start_of_batch();
+    renderer.fill_buffer_f32(buffer).unwrap();
+}
+
+fn render_batch_i16(renderer: &mut Renderer, buffer: &mut [i16]) {
+    renderer.on_start_of_batch();
+    renderer.fill_buffer(buffer).unwrap();
+}
+
+fn render_batch_per_sample(renderer: &mut Renderer, num_samples: usize) {
+    renderer.on_start_of_batch();
+    for _ in 0..num_samples {
+        renderer.next_sample_f32().unwrap();
+    }
+}
+
+fn memory_sound(num_frames: usize, channel_count: u16, sample_rate: u32) -> MemorySound {
+    let samples = (0..num_frames * channel_count as usize)
+        .map(|i| (i % 1000) as i16)
+        .collect();
+    MemorySound::from_samples(Arc::new(samples), channel_count, sample_rate)
+}
+
+fn looping(mut sound: MemorySound) -> MemorySound {
+    sound.set_looping(true);
+    sound
+}
+
+struct Gain(f32);
+
+impl MasterEffect for Gain {
+    fn process(&mut self, frames: &mut [f32]) {
+        for sample in frames {
+            *sample *= self.0;
+        }
+    }
+}
+
+#[test]
+fn rendering_does_not_allocate() {
+    let (mut manager, mut renderer) = start();
+    let mut buffer = vec![0.0; BATCH_FRAMES * CHANNEL_COUNT as usize];
+
+    manager.play(Box::new(SineWave::with_sample_rate(440.0, SAMPLE_RATE)));
+    // Resampled
+    manager.play(Box::new(looping(memory_sound(1000, 2, 44100))));
+    // Converted to stereo
+    manager.play(Box::new(looping(memory_sound(1000, 1, 22050))));
+    // Converted from more channels
+    manager.play(Box::new(looping(memory_sound(1000, 4, SAMPLE_RATE))));
+    let mut sinc = manager.play_with_handle(Box::new(looping(memory_sound(1000, 2, 32000))));
+    sinc.set_resampler_quality(ResamplerQuality::Sinc);
+    let (sound, mut controller) = SineWave::with_sample_rate(220.0, 44100)
+        .with_adjustable_volume()
+        .controllable();
+    manager.play(Box::new(sound));
+    let mut bus = manager.create_bus("music");
+    bus.play(Box::new(looping(memory_sound(1000, 2, SAMPLE_RATE))));
+    // Finishes while counting.
+    manager.play(Box::new(memory_sound(20 * BATCH_FRAMES, 2, SAMPLE_RATE)));
+    let mut stopped = manager.play_with_handle(Box::new(SineWave::new(330.0)));
+
+    for _ in 0..10 {
+        render_batch(&mut renderer, &mut buffer);
+    }
+
+    // Commands are sent off the renderer thread and applied while counting.
+    controller.tween_volume(0.25, Duration::from_millis(50), Easing::InOutQuad);
+    sinc.set_volume(0.5);
+    bus.set_volume(0.5);
+    stopped.stop();
+    manager.play(Box::new(SineWave::with_sample_rate(660.0, 44100)));
+    manager.play_after(
+        Box::new(memory_sound(BATCH_FRAMES, 1, SAMPLE_RATE)),
+        Duration::from_millis(20),
+    );
+
+    let (allocations, deallocations) = count_allocations(|| {
+        for _ in 0..30 {
+            render_batch(&mut renderer, &mut buffer);
+        }
+        render_batch_per_sample(&mut renderer, buffer.len());
+    });
+    assert_eq!(allocations, 0);
+    assert_eq!(deallocations, 0);
+}
+
+#[test]
+fn clearing_does_not_allocate() {
+    let (mut manager, mut renderer) = start();
+    let mut buffer = vec![0.0; BATCH_FRAMES * CHANNEL_COUNT as usize];
+    let mut bus = manager.create_bus("effects");
+    for i in 0..8 {
+        manager.play(Box::new(SineWave::with_sample_rate(
+            200.0 + i as f32,
+            SAMPLE_RATE,
+        )));
+        bus.play(Box::new(looping(memory_sound(1000, 1, 44100))));
+    }
+    for _ in 0..4 {
+        render_batch(&mut renderer, &mut buffer);
+    }
+
+    bus.clear();
+    manager.clear();
+
+    let (allocations, deallocations) = count_allocations(|| {
+        for _ in 0..4 {
+            render_batch(&mut renderer, &mut buffer);
+        }
+    });
+    assert_eq!(allocations, 0);
+    assert_eq!(deallocations, 0);
+}
+
+#[test]
+fn sound_list_with_event_sender_does_not_allocate() {
+    let (mut manager, mut renderer) = start();
+    let mut buffer = vec![0.0; BATCH_FRAMES * CHANNEL_COUNT as usize];
+    let events = manager.subscribe_events();
+    let mut list = SoundList::new();
+    list.set_event_sender(manager.event_sender().clone());
+    // Finishes while counting.
+    let finishing = list.add(Box::new(memory_sound(20 * BATCH_FRAMES, 2, SAMPLE_RATE)));
+    list.add(Box::new(looping(memory_sound(1000, 2, SAMPLE_RATE))));
+    manager.play(Box::new(list));
+
+    for _ in 0..10 {
+        render_batch(&mut renderer, &mut buffer);
+    }
+
+    let (allocations, deallocations) = count_allocations(|| {
+        for _ in 0..20 {
+            render_batch(&mut renderer, &mut buffer);
+        }
+    });
+    assert_eq!(allocations, 0);
+    assert_eq!(deallocations, 0);
+    let event = events.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert!(matches!(event, SoundEvent::Finished { id } if id == finishing));
+}
+
+#[test]
+fn finishing_bus_sound_does_not_allocate() {
+    let (mut manager, mut renderer) = start();
+    let mut buffer = vec![0.0; BATCH_FRAMES * CHANNEL_COUNT as usize];
+    let events = manager.subscribe_events();
+    let mut bus = manager.create_bus("effects");
+    bus.play(Box::new(looping(memory_sound(1000, 2, SAMPLE_RATE))));
+    // Finishes while counting.
+    let finishing = bus.play(Box::new(memory_sound(10 * BATCH_FRAMES, 2, SAMPLE_RATE)));
+    for _ in 0..4 {
+        render_batch(&mut renderer, &mut buffer);
+    }
+
+    let (allocations, deallocations) = count_allocations(|| {
+        for _ in 0..10 {
+            render_batch(&mut renderer, &mut buffer);
+        }
+    });
+    assert_eq!(allocations, 0);
+    assert_eq!(deallocations, 0);
+    let event = events.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert!(matches!(event, SoundEvent::Finished { id } if id == finishing));
+}
+
+#[test]
+fn controllable_created_before_manager_does_not_free() {
+    let (sound, mut controller) = SineWave::with_sample_rate(220.0, SAMPLE_RATE)
+        .with_adjustable_volume()
+        .controllable();
+    let (mut manager, mut renderer) = start();
+    let mut buffer = vec![0.0; BATCH_FRAMES * CHANNEL_COUNT as usize];
+    manager.play(Box::new(sound));
+    for _ in 0..4 {
+        render_batch(&mut renderer, &mut buffer);
+    }
+
+    controller.set_volume(0.5);
+    controller.send_command_at(controller.frame() + 100, |sound| sound.set_volume(0.25));
+
+    let (allocations, deallocations) = count_allocations(|| {
+        for _ in 0..4 {
+            render_batch(&mut renderer, &mut buffer);
+        }
+    });
+    assert_eq!(allocations, 0);
+    assert_eq!(deallocations, 0);
+}
+
+#[test]
+fn master_effects_and_ducking_do_not_allocate() {
+    let (mut manager, mut renderer) = start();
+    let mut buffer = vec![0.0; BATCH_FRAMES * CHANNEL_COUNT as usize];
+    let trigger = manager.play(Box::new(looping(memory_sound(1000, 2, SAMPLE_RATE))));
+    let target = manager.play(Box::new(SineWave::with_sample_rate(440.0, SAMPLE_RATE)));
+    manager.add_master_effect(Box::new(Gain(0.5)));
+    for _ in 0..4 {
+        render_batch(&mut renderer, &mut buffer);
+    }
+
+    manager.add_master_effect(Box::new(Gain(0.8)));
+    manager.add_ducking(Ducking::new(trigger, target, -12.0));
+    let (allocations, deallocations) = count_allocations(|| {
+        for _ in 0..4 {
+            render_batch(&mut renderer, &mut buffer);
+        }
+        render_batch_per_sample(&mut renderer, buffer.len());
+    });
+    assert_eq!(allocations, 0);
+    assert_eq!(deallocations, 0);
+
+    manager.clear_master_effects();
+    manager.remove_ducking(trigger, target);
+    let (allocations, deallocations) = count_allocations(|| {
+        for _ in 0..4 {
+            render_batch(&mut renderer, &mut buffer);
+        }
+    });
+    assert_eq!(allocations, 0);
+    assert_eq!(deallocations, 0);
+}
+
+#[test]
+fn rendering_i16_does_not_allocate() {
+    let (mut manager, mut renderer) = start();
+    let mut buffer = vec![0; BATCH_FRAMES * CHANNEL_COUNT as usize];
+    manager.play(Box::new(SineWave::with_sample_rate(440.0, SAMPLE_RATE)));
+    manager.play(Box::new(looping(memory_sound(1000, 1, 44100))));
+    manager.play(Box::new(looping(memory_sound(1000, 6, SAMPLE_RATE))));
+    manager.add_master_effect(Box::new(Gain(0.5)));
+    // Finishes while counting.
+    manager.play(Box::new(memory_sound(10 * BATCH_FRAMES, 2, SAMPLE_RATE)));
+    for _ in 0..4 {
+        render_batch_i16(&mut renderer, &mut buffer);
+    }
+
+    let (allocations, deallocations) = count_allocations(|| {
+        for _ in 0..10 {
+            render_batch_i16(&mut renderer, &mut buffer);
+        }
+    });
+    assert_eq!(allocations, 0);
+    assert_eq!(deallocations, 0);
+}
+
+#[test]
+fn tempo_and_pitch_changes_do_not_allocate() {
+    let (mut manager, mut renderer) = start();
+    let mut buffer = vec![0.0; BATCH_FRAMES * CHANNEL_COUNT as usize];
+    let (sound, mut tempo) = looping(memory_sound(1000, 2, SAMPLE_RATE))
+        .with_adjustable_tempo()
+        .controllable();
+    manager.play(Box::new(sound));
+    let (sound, mut fast) = looping(memory_sound(1000, 1, 44100))
+        .with_adjustable_tempo_of(1.5)
+        .controllable();
+    manager.play(Box::new(sound));
+    let (sound, mut pitch) = SineWave::with_sample_rate(440.0, SAMPLE_RATE)
+        .with_adjustable_pitch()
+        .controllable();
+    manager.play(Box::new(sound));
+    for _ in 0..4 {
+        render_batch(&mut renderer, &mut buffer);
+    }
+
+    // Starts stretching and shifting while counting.
+    tempo.set_tempo(0.75);
+    fast.set_tempo(8.0);
+    pitch.set_pitch(7.0);
+    let (allocations, deallocations) = count_allocations(|| {
+        for _ in 0..10 {
+            render_batch(&mut renderer, &mut buffer);
+        }
+        render_batch_per_sample(&mut renderer, buffer.len());
+    });
+    assert_eq!(allocations, 0);
+    assert_eq!(deallocations, 0);
+
+    tempo.seek(Duration::from_millis(5));
+    tempo.set_tempo(1.0);
+    fast.set_tempo(1.25);
+    pitch.set_pitch(-12.0);
+    let (allocations, deallocations) = count_allocations(|| {
+        for _ in 0..10 {
+            render_batch(&mut renderer, &mut buffer);
+        }
+    });
+    assert_eq!(allocations, 0);
+    assert_eq!(deallocations, 0);
+}
+
+#[test]
+fn fade_pan_and_prefetched_do_not_allocate() {
+    let (mut manager, mut renderer) = start();
+    let mut buffer = vec![0.0; BATCH_FRAMES * CHANNEL_COUNT as usize];
+    let (sound, mut fade) = looping(memory_sound(1000, 2, SAMPLE_RATE))
+        .fade_in(Duration::from_millis(20))
+        .controllable();
+    manager.play(Box::new(sound));
+    // Stops while counting.
+    let (sound, mut fade_out) = SineWave::with_sample_rate(330.0, SAMPLE_RATE)
+        .fadeable()
+        .controllable();
+    manager.play(Box::new(sound));
+    let (sound, mut pan) = looping(memory_sound(1000, 1, 44100))
+        .pannable()
+        .controllable();
+    manager.play(Box::new(sound));
+    let prefetched = Prefetched::new(
+        looping(memory_sound(1000, 2, 44100)),
+        Duration::from_millis(100),
+    )
+    .unwrap();
+    manager.play(Box::new(prefetched));
+    // Give the prefetch worker time to fill its buffer.
+    std::thread::sleep(Duration::from_millis(50));
+    for _ in 0..4 {
+        render_batch(&mut renderer, &mut buffer);
+    }
+
+    fade.fade_to(0.25, Duration::from_millis(20));
+    fade_out.fade_out_and_stop(Duration::from_millis(10));
+    pan.tween_pan(-1.0, Duration::from_millis(20), Easing::InOutQuad);
+    let (allocations, deallocations) = count_allocations(|| {
+        for _ in 0..4 {
+            render_batch(&mut renderer, &mut buffer);
+        }
+        render_batch_per_sample(&mut renderer, buffer.len());
+    });
+    assert_eq!(allocations, 0);
+    assert_eq!(deallocations, 0);
+}
+
+#[test]
+fn metadata_change_does_not_allocate() {
+    let (mut manager, mut renderer) = start();
+    let mut buffer = vec![0.0; BATCH_FRAMES * CHANNEL_COUNT as usize];
+    let events = manager.subscribe_events();
+    let mut list = SoundList::new();
+    list.set_event_sender(manager.event_sender().clone());
+    // The channel count and sample rate change while counting.
+    list.add(Box::new(memory_sound(6 * BATCH_FRAMES, 2, SAMPLE_RATE)));
+    list.add(Box::new(memory_sound(BATCH_FRAMES, 4, 44100)));
+    list.add(Box::new(memory_sound(BATCH_FRAMES, 1, 32000)));
+    list.add(Box::new(memory_sound(BATCH_FRAMES, 8, SAMPLE_RATE)));
+    let last = list.add(Box::new(memory_sound(BATCH_FRAMES, 6, 22050)));
+    manager.play(Box::new(list));
+    let mut prefetched_list = SoundList::new();
+    prefetched_list.add(Box::new(memory_sound(6 * BATCH_FRAMES, 2, SAMPLE_RATE)));
+    prefetched_list.add(Box::new(looping(memory_sound(1000, 1, 44100))));
+    let prefetched = Prefetched::new(prefetched_list, Duration::from_millis(500)).unwrap();
+    manager.play(Box::new(prefetched));
+    std::thread::sleep(Duration::from_millis(50));
+    for _ in 0..4 {
+        render_batch(&mut renderer, &mut buffer);
+    }
+
+    let (allocations, deallocations) = count_allocations(|| {
+        for _ in 0..10 {
+            render_batch(&mut renderer, &mut buffer);
+        }
+    });
+    assert_eq!(allocations, 0);
+    assert_eq!(deallocations, 0);
+    let finished = std::iter::from_fn(|| events.recv_timeout(Duration::from_secs(5)).ok())
+        .any(|event| matches!(event, SoundEvent::Finished { id } if id == last));
+    assert!(finished);
+}